@@ -228,14 +228,14 @@ impl HealChannelProcessor {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::heal::storage::HealStorageAPI;
     use nebulafx_common::heal_channel::{HealChannelPriority, HealChannelRequest, HealScanMode};
     use std::sync::Arc;
 
     // Mock storage for testing
-    struct MockStorage;
+    pub(crate) struct MockStorage;
     #[async_trait::async_trait]
     impl HealStorageAPI for MockStorage {
         async fn get_object_meta(
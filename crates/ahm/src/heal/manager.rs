@@ -9,6 +9,7 @@ use crate::{Error, Result};
 use nebulafx_ecstore::disk::DiskAPI;
 use nebulafx_ecstore::disk::error::DiskError;
 use nebulafx_ecstore::global::GLOBAL_LOCAL_DISK_MAP;
+use serde::Serialize;
 use std::{
     collections::{HashMap, VecDeque},
     sync::Arc,
@@ -65,6 +66,21 @@ pub struct HealState {
     pub active_heal_count: usize,
 }
 
+/// Snapshot of an active heal task
+#[derive(Debug, Clone, Serialize)]
+pub struct HealTaskInfo {
+    /// Task ID
+    pub id: String,
+    /// Target bucket, if the task is scoped to one
+    pub bucket: Option<String>,
+    /// Target object, if the task is scoped to one
+    pub object: Option<String>,
+    /// Task status
+    pub status: HealTaskStatus,
+    /// Task progress
+    pub progress: HealProgress,
+}
+
 /// Heal manager
 pub struct HealManager {
     /// Heal config
@@ -191,6 +207,30 @@ impl HealManager {
         }
     }
 
+    /// List active tasks
+    pub async fn list_active_tasks(&self) -> Vec<HealTaskInfo> {
+        let tasks: Vec<Arc<HealTask>> = self.active_heals.lock().await.values().cloned().collect();
+
+        let mut infos = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let (bucket, object) = match &task.heal_type {
+                HealType::Object { bucket, object, .. }
+                | HealType::Metadata { bucket, object }
+                | HealType::ECDecode { bucket, object, .. } => (Some(bucket.clone()), Some(object.clone())),
+                HealType::Bucket { bucket } => (Some(bucket.clone()), None),
+                _ => (None, None),
+            };
+            infos.push(HealTaskInfo {
+                id: task.id.clone(),
+                bucket,
+                object,
+                status: task.get_status().await,
+                progress: task.get_progress().await,
+            });
+        }
+        infos
+    }
+
     /// Cancel task
     pub async fn cancel_task(&self, task_id: &str) -> Result<()> {
         let mut active_heals = self.active_heals.lock().await;
@@ -426,3 +466,41 @@ impl std::fmt::Debug for HealManager {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heal::channel::tests::MockStorage;
+
+    #[tokio::test]
+    async fn test_list_and_cancel_active_task() {
+        let storage: Arc<dyn HealStorageAPI> = Arc::new(MockStorage);
+        let manager = HealManager::new(storage.clone(), None);
+
+        let request = HealRequest::new(
+            HealType::Object {
+                bucket: "bucket".to_string(),
+                object: "object".to_string(),
+                version_id: None,
+            },
+            HealOptions::default(),
+            HealPriority::Normal,
+        );
+        let task = Arc::new(HealTask::from_request(request, storage));
+        let task_id = task.id.clone();
+        manager.active_heals.lock().await.insert(task_id.clone(), task.clone());
+
+        let tasks = manager.list_active_tasks().await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task_id);
+        assert_eq!(tasks[0].bucket.as_deref(), Some("bucket"));
+        assert_eq!(tasks[0].object.as_deref(), Some("object"));
+        assert_eq!(tasks[0].status, HealTaskStatus::Pending);
+
+        manager.cancel_task(&task_id).await.unwrap();
+        assert!(task.cancel_token.is_cancelled());
+        assert!(manager.list_active_tasks().await.is_empty());
+
+        assert!(matches!(manager.cancel_task(&task_id).await, Err(Error::TaskNotFound { .. })));
+    }
+}
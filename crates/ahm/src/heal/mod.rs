@@ -11,6 +11,6 @@ pub mod task;
 pub mod utils;
 
 pub use erasure_healer::ErasureSetHealer;
-pub use manager::HealManager;
+pub use manager::{HealManager, HealTaskInfo};
 pub use resume::{CheckpointManager, ResumeCheckpoint, ResumeManager, ResumeState, ResumeUtils};
 pub use task::{HealOptions, HealPriority, HealRequest, HealTask, HealType};
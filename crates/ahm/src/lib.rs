@@ -6,7 +6,7 @@ pub mod scanner;
 
 pub use error::{Error, Result};
 pub use heal::{HealManager, HealOptions, HealPriority, HealRequest, HealType, channel::HealChannelProcessor};
-pub use scanner::Scanner;
+pub use scanner::{Scanner, ScannerTuning};
 use std::sync::{Arc, OnceLock};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
@@ -88,6 +88,21 @@ pub async fn init_heal_manager(
     Ok(heal_manager)
 }
 
+/// Global scanner instance, registered once the scanner is started
+static GLOBAL_SCANNER: OnceLock<Arc<Scanner>> = OnceLock::new();
+
+/// Register the running scanner so its tuning can be changed at runtime
+pub fn set_global_scanner(scanner: Arc<Scanner>) -> Result<()> {
+    GLOBAL_SCANNER
+        .set(scanner)
+        .map_err(|_| Error::Config("Scanner already initialized".to_string()))
+}
+
+/// Get global scanner instance
+pub fn get_global_scanner() -> Option<&'static Arc<Scanner>> {
+    GLOBAL_SCANNER.get()
+}
+
 /// Get global heal manager instance
 pub fn get_heal_manager() -> Option<&'static Arc<HealManager>> {
     GLOBAL_HEAL_MANAGER.get()
@@ -30,7 +30,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 use time::OffsetDateTime;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, Notify, RwLock};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 use uuid;
@@ -78,6 +78,48 @@ impl Default for ScannerConfig {
     }
 }
 
+/// Shortest scan interval accepted at runtime
+pub const MIN_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+/// Longest scan interval accepted at runtime
+pub const MAX_SCAN_INTERVAL: Duration = Duration::from_secs(7 * 24 * 3600);
+/// Upper bound for `max_concurrent_scans` at runtime
+pub const MAX_CONCURRENT_SCANS_LIMIT: usize = 1024;
+
+/// Scanner settings that can be changed while the scanner runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScannerTuning {
+    pub scan_interval: Duration,
+    pub max_concurrent_scans: usize,
+}
+
+impl ScannerTuning {
+    pub fn from_config(config: &ScannerConfig) -> Self {
+        Self {
+            scan_interval: config.scan_interval,
+            max_concurrent_scans: config.max_concurrent_scans,
+        }
+    }
+
+    /// Check both settings lie within the accepted ranges
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_SCAN_INTERVAL..=MAX_SCAN_INTERVAL).contains(&self.scan_interval) {
+            return Err(Error::Config(format!(
+                "scan interval must be between {}s and {}s, got {}s",
+                MIN_SCAN_INTERVAL.as_secs(),
+                MAX_SCAN_INTERVAL.as_secs(),
+                self.scan_interval.as_secs_f64()
+            )));
+        }
+        if !(1..=MAX_CONCURRENT_SCANS_LIMIT).contains(&self.max_concurrent_scans) {
+            return Err(Error::Config(format!(
+                "max concurrent scans must be between 1 and {MAX_CONCURRENT_SCANS_LIMIT}, got {}",
+                self.max_concurrent_scans
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Scanner state
 #[derive(Debug, Default)]
 pub struct ScannerState {
@@ -135,6 +177,12 @@ pub struct Scanner {
     stats_aggregator: Arc<DecentralizedStatsAggregator>,
     /// Node ID for this scanner instance
     node_id: String,
+    /// Tuning the scanner was created with
+    base_tuning: ScannerTuning,
+    /// Runtime tuning that takes precedence over `base_tuning` until cleared
+    tuning_override: Arc<RwLock<Option<ScannerTuning>>>,
+    /// Wakes the scan loop when the tuning changes
+    config_changed: Arc<Notify>,
 }
 
 impl Scanner {
@@ -173,6 +221,9 @@ impl Scanner {
         let stats_aggregator = Arc::new(DecentralizedStatsAggregator::new(aggregator_config));
 
         Self {
+            base_tuning: ScannerTuning::from_config(&config),
+            tuning_override: Arc::new(RwLock::new(None)),
+            config_changed: Arc::new(Notify::new()),
             config: Arc::new(RwLock::new(config)),
             state: Arc::new(RwLock::new(ScannerState::default())),
             metrics: Arc::new(MetricsCollector::new()),
@@ -205,6 +256,63 @@ impl Scanner {
         config.enable_data_usage_stats = enable;
     }
 
+    /// Interval and concurrency the scanner currently runs with
+    pub async fn tuning(&self) -> ScannerTuning {
+        ScannerTuning::from_config(&*self.config.read().await)
+    }
+
+    /// Runtime override set through [`Scanner::set_tuning_override`], if any
+    pub async fn tuning_override(&self) -> Option<ScannerTuning> {
+        *self.tuning_override.read().await
+    }
+
+    /// Apply `tuning` to the running scanner until [`Scanner::clear_tuning_override`]
+    pub async fn set_tuning_override(&self, tuning: ScannerTuning) -> Result<()> {
+        tuning.validate()?;
+        *self.tuning_override.write().await = Some(tuning);
+        self.apply_tuning(tuning).await;
+        Ok(())
+    }
+
+    /// Drop the runtime override and return to the tuning the scanner was created with
+    pub async fn clear_tuning_override(&self) -> ScannerTuning {
+        self.tuning_override.write().await.take();
+        self.apply_tuning(self.base_tuning).await;
+        self.base_tuning
+    }
+
+    async fn apply_tuning(&self, tuning: ScannerTuning) {
+        {
+            let mut config = self.config.write().await;
+            config.scan_interval = tuning.scan_interval;
+            config.max_concurrent_scans = tuning.max_concurrent_scans;
+        }
+        self.node_scanner.set_scan_interval(tuning.scan_interval).await;
+        // Wake the scan loop so a shorter interval applies without waiting out the old one
+        self.config_changed.notify_waiters();
+        info!(
+            scan_interval = ?tuning.scan_interval,
+            max_concurrent_scans = tuning.max_concurrent_scans,
+            "Scanner tuning updated"
+        );
+    }
+
+    /// Sleep until the scan interval has passed since `cycle_started`, re-reading it whenever the
+    /// tuning changes; returns false when AHM services are cancelled
+    async fn wait_for_next_cycle(&self, cycle_started: tokio::time::Instant) -> bool {
+        let cancel_token = get_ahm_services_cancel_token().cloned().unwrap_or_default();
+        loop {
+            // Registered before reading the interval so a concurrent update is not missed
+            let changed = self.config_changed.notified();
+            let scan_interval = self.config.read().await.scan_interval;
+            tokio::select! {
+                _ = tokio::time::sleep_until(cycle_started + scan_interval) => return true,
+                _ = changed => {}
+                _ = cancel_token.cancelled() => return false,
+            }
+        }
+    }
+
     /// Set the heal manager after construction
     pub fn set_heal_manager(&mut self, heal_manager: Arc<HealManager>) {
         self.heal_manager = Some(heal_manager);
@@ -2470,10 +2578,8 @@ impl Scanner {
                 }
             }
 
-            let (enable_data_usage_stats, scan_interval) = {
-                let config = self.config.read().await;
-                (config.enable_data_usage_stats, config.scan_interval)
-            };
+            let cycle_started = tokio::time::Instant::now();
+            let enable_data_usage_stats = self.config.read().await.enable_data_usage_stats;
 
             if enable_data_usage_stats {
                 if let Err(e) = self.collect_and_persist_data_usage().await {
@@ -2485,17 +2591,9 @@ impl Scanner {
             let local_stats = self.node_scanner.get_stats_summary().await;
             self.stats_aggregator.set_local_stats(local_stats).await;
 
-            match get_ahm_services_cancel_token() {
-                Some(token) => {
-                    tokio::select! {
-                        _ = tokio::time::sleep(scan_interval) => {}
-                        _ = token.cancelled() => {
-                            info!("Cancellation requested, exiting legacy scan loop");
-                            break;
-                        }
-                    }
-                }
-                None => tokio::time::sleep(scan_interval).await,
+            if !self.wait_for_next_cycle(cycle_started).await {
+                info!("Cancellation requested, exiting legacy scan loop");
+                break;
             }
         }
 
@@ -2559,6 +2657,9 @@ impl Scanner {
             node_scanner: Arc::clone(&self.node_scanner),
             stats_aggregator: Arc::clone(&self.stats_aggregator),
             node_id: self.node_id.clone(),
+            base_tuning: self.base_tuning,
+            tuning_override: Arc::clone(&self.tuning_override),
+            config_changed: Arc::clone(&self.config_changed),
         }
     }
 }
@@ -3571,4 +3672,50 @@ mod tests {
         // Clean up
         let _ = std::fs::remove_dir_all(std::path::Path::new(TEST_DIR_HEALTHY));
     }
+
+    #[tokio::test]
+    async fn test_scan_interval_update_is_picked_up_by_running_loop() {
+        let scanner = Scanner::new(None, None);
+        assert_eq!(scanner.tuning().await.scan_interval, Duration::from_secs(300));
+
+        let waiter = scanner.clone_for_background();
+        let wait = tokio::spawn(async move { waiter.wait_for_next_cycle(tokio::time::Instant::now()).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let tuning = ScannerTuning {
+            scan_interval: Duration::from_secs(1),
+            max_concurrent_scans: 4,
+        };
+        scanner.set_tuning_override(tuning).await.unwrap();
+
+        // The wait started under the 5 minute interval ends after the new 1 second one
+        let next_cycle = tokio::time::timeout(Duration::from_secs(5), wait).await;
+        assert!(matches!(next_cycle, Ok(Ok(true))));
+        assert_eq!(scanner.tuning().await, tuning);
+        assert_eq!(scanner.node_scanner.scan_interval().await, Duration::from_secs(1));
+
+        // Clearing the override restores the tuning the scanner was created with
+        let base = ScannerTuning::from_config(&ScannerConfig::default());
+        assert_eq!(scanner.clear_tuning_override().await, base);
+        assert_eq!(scanner.tuning().await, base);
+        assert_eq!(scanner.tuning_override().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_tuning_is_rejected() {
+        let scanner = Scanner::new(None, None);
+        for tuning in [
+            ScannerTuning {
+                scan_interval: Duration::ZERO,
+                max_concurrent_scans: 4,
+            },
+            ScannerTuning {
+                scan_interval: Duration::from_secs(60),
+                max_concurrent_scans: 0,
+            },
+        ] {
+            assert!(matches!(scanner.set_tuning_override(tuning).await, Err(Error::Config(_))));
+        }
+        assert_eq!(scanner.tuning().await.scan_interval, Duration::from_secs(300));
+    }
 }
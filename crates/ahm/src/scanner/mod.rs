@@ -13,7 +13,10 @@ pub mod node_scanner;
 pub mod stats_aggregator;
 
 pub use checkpoint::{CheckpointData, CheckpointInfo, CheckpointManager};
-pub use data_scanner::{ScanMode, Scanner, ScannerConfig, ScannerState};
+pub use data_scanner::{
+    MAX_CONCURRENT_SCANS_LIMIT, MAX_SCAN_INTERVAL, MIN_SCAN_INTERVAL, ScanMode, Scanner, ScannerConfig, ScannerState,
+    ScannerTuning,
+};
 pub use io_monitor::{AdvancedIOMonitor, IOMetrics, IOMonitorConfig};
 pub use io_throttler::{AdvancedIOThrottler, IOThrottlerConfig, MetricsSnapshot, ResourceAllocation, ThrottleDecision};
 pub use local_stats::{BatchScanResult, LocalStatsManager, ScanResultEntry, StatsSummary};
@@ -464,6 +464,16 @@ impl NodeScanner {
         }
     }
 
+    /// Base interval between scan rounds
+    pub async fn scan_interval(&self) -> Duration {
+        self.config.read().await.scan_interval
+    }
+
+    /// Change the base interval between scan rounds; applies from the next round
+    pub async fn set_scan_interval(&self, interval: Duration) {
+        self.config.write().await.scan_interval = interval;
+    }
+
     /// add local disk and create checkpoint manager for it
     pub async fn add_local_disk(&self, disk: Arc<DiskStore>) {
         // get disk path and create corresponding scanner directory
@@ -64,11 +64,11 @@ pub async fn resume_audit_system() -> AuditResult<()> {
     with_audit_system!(|system: Arc<AuditSystem>| async move { system.resume().await })
 }
 
-/// Dispatch an audit log entry to all targets
+/// Dispatch an audit log entry to all targets, waiting for the write in sync mode
 pub async fn dispatch_audit_log(entry: Arc<AuditEntry>) -> AuditResult<()> {
     if let Some(system) = audit_system() {
         if system.is_running().await {
-            system.dispatch(entry).await
+            system.submit(entry).await
         } else {
             // The system is initialized but not running (for example, it is suspended). Silently discard log entries based on original logic.
             // For debugging purposes, it can be useful to add a trace log here.
@@ -21,13 +21,17 @@
 pub mod entity;
 pub mod error;
 pub mod global;
+pub mod mode;
 pub mod observability;
 pub mod registry;
 pub mod system;
+pub mod tail;
 
 pub use entity::{ApiDetails, AuditEntry, ObjectVersion};
 pub use error::{AuditError, AuditResult};
 pub use global::*;
+pub use mode::{AuditMode, DEFAULT_AUDIT_QUEUE_CAPACITY};
 pub use observability::{AuditMetrics, AuditMetricsReport, PerformanceValidation};
 pub use registry::AuditRegistry;
 pub use system::AuditSystem;
+pub use tail::{AUDIT_RECENT_CAPACITY, AuditTail, AuditTailFilter, audit_tail, shutdown_audit_tail, subscribe_audit_tail};
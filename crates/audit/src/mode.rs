@@ -0,0 +1,58 @@
+//  Copyright 2024 NebulaFX Team
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+//! Whether audit writes block the request until persisted.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Entries buffered for asynchronous delivery before new ones are dropped
+pub const DEFAULT_AUDIT_QUEUE_CAPACITY: usize = 10_000;
+
+/// When an audit write is acknowledged relative to the backend write
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuditMode {
+    /// Acknowledge immediately and deliver from a bounded queue; entries are dropped when it is full
+    #[default]
+    Async,
+    /// Acknowledge after every target has stored the entry; a failed write fails the request
+    Sync,
+}
+
+impl AuditMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditMode::Async => "async",
+            AuditMode::Sync => "sync",
+        }
+    }
+}
+
+impl fmt::Display for AuditMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "async" => Ok(AuditMode::Async),
+            "sync" => Ok(AuditMode::Sync),
+            other => Err(format!("invalid audit mode '{other}', expected one of: async, sync")),
+        }
+    }
+}
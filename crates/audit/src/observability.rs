@@ -32,6 +32,7 @@ const NEUBULAFX_AUDIT_METRICS_NAMESPACE: &str = "nebulafx.audit.";
 
 const M_AUDIT_EVENTS_TOTAL: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "events.total");
 const M_AUDIT_EVENTS_FAILED: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "events.failed");
+const M_AUDIT_EVENTS_DROPPED: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "events.dropped");
 const M_AUDIT_DISPATCH_NS: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "dispatch.ns");
 const M_AUDIT_EPS: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "eps");
 const M_AUDIT_TARGET_OPS: &str = const_str::concat!(NEUBULAFX_AUDIT_METRICS_NAMESPACE, "target.ops");
@@ -52,6 +53,7 @@ pub fn init_observability_metrics() {
         // Event/Time-consuming
         describe_counter!(M_AUDIT_EVENTS_TOTAL, "Total audit events (labeled by result).");
         describe_counter!(M_AUDIT_EVENTS_FAILED, "Total failed audit events.");
+        describe_counter!(M_AUDIT_EVENTS_DROPPED, "Total audit events dropped because the async queue was full.");
         describe_histogram!(M_AUDIT_DISPATCH_NS, "Dispatch time per event (ns).");
         describe_gauge!(M_AUDIT_EPS, "Events per second since last reset.");
 
@@ -392,6 +394,11 @@ pub fn record_audit_failure(dispatch_time: Duration) {
     global_metrics().record_event_failure(dispatch_time);
 }
 
+/// Record an audit event dropped because the async queue was full
+pub fn record_audit_dropped() {
+    counter!(M_AUDIT_EVENTS_DROPPED).increment(1);
+}
+
 /// Record a successful target operation
 pub fn record_target_success() {
     global_metrics().record_target_success();
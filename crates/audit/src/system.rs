@@ -12,16 +12,20 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
-use crate::{AuditEntry, AuditError, AuditRegistry, AuditResult, observability};
+use crate::{
+    AuditEntry, AuditError, AuditMode, AuditRegistry, AuditResult, DEFAULT_AUDIT_QUEUE_CAPACITY, audit_tail, observability,
+};
 use nebulafx_ecstore::config::Config;
 use nebulafx_targets::{
     StoreError, Target, TargetError,
     store::{Key, Store},
     target::EntityTarget,
 };
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc::{self, error::TrySendError};
 use tokio::sync::{Mutex, RwLock};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 /// State of the audit system
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -39,6 +43,11 @@ pub struct AuditSystem {
     registry: Arc<Mutex<AuditRegistry>>,
     state: Arc<RwLock<AuditSystemState>>,
     config: Arc<RwLock<Option<Config>>>,
+    sync_mode: Arc<AtomicBool>,
+    /// Async-mode queue, created with its delivery task on first use
+    queue: Arc<OnceLock<mpsc::Sender<Arc<AuditEntry>>>>,
+    queue_capacity: usize,
+    dropped: Arc<AtomicU64>,
 }
 
 impl Default for AuditSystem {
@@ -50,13 +59,40 @@ impl Default for AuditSystem {
 impl AuditSystem {
     /// Creates a new audit system
     pub fn new() -> Self {
+        Self::with_queue_capacity(DEFAULT_AUDIT_QUEUE_CAPACITY)
+    }
+
+    /// Creates a new audit system whose async-mode queue holds at most `capacity` entries
+    pub fn with_queue_capacity(capacity: usize) -> Self {
         Self {
             registry: Arc::new(Mutex::new(AuditRegistry::new())),
             state: Arc::new(RwLock::new(AuditSystemState::Stopped)),
             config: Arc::new(RwLock::new(None)),
+            sync_mode: Arc::new(AtomicBool::new(false)),
+            queue: Arc::new(OnceLock::new()),
+            queue_capacity: capacity.max(1),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Sets whether [`AuditSystem::submit`] waits for the backend write
+    pub fn set_mode(&self, mode: AuditMode) {
+        self.sync_mode.store(mode == AuditMode::Sync, Ordering::Relaxed);
+    }
+
+    pub fn mode(&self) -> AuditMode {
+        if self.sync_mode.load(Ordering::Relaxed) {
+            AuditMode::Sync
+        } else {
+            AuditMode::Async
         }
     }
 
+    /// Entries dropped because the async queue was full
+    pub fn dropped_entries(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
     /// Starts the audit system with the given configuration
     pub async fn start(&self, config: Config) -> AuditResult<()> {
         let state = self.state.write().await;
@@ -227,8 +263,49 @@ impl AuditSystem {
         matches!(*self.state.read().await, AuditSystemState::Running)
     }
 
-    /// Dispatches an audit log entry to all active targets
+    /// Records an audit entry according to the configured [`AuditMode`]
+    ///
+    /// In sync mode this returns once every target has stored the entry and fails if any
+    /// did not; in async mode it queues the entry and returns immediately.
+    pub async fn submit(&self, entry: Arc<AuditEntry>) -> AuditResult<()> {
+        match self.mode() {
+            AuditMode::Sync => self.deliver(entry, true).await,
+            AuditMode::Async => {
+                self.enqueue(entry);
+                Ok(())
+            }
+        }
+    }
+
+    fn enqueue(&self, entry: Arc<AuditEntry>) {
+        let queue = self.queue.get_or_init(|| self.spawn_queue_worker());
+        if let Err(TrySendError::Full(_) | TrySendError::Closed(_)) = queue.try_send(entry) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            observability::record_audit_dropped();
+            debug!("Audit queue is full, dropping audit entry");
+        }
+    }
+
+    fn spawn_queue_worker(&self) -> mpsc::Sender<Arc<AuditEntry>> {
+        let (tx, mut rx) = mpsc::channel(self.queue_capacity);
+        let system = self.clone();
+        tokio::spawn(async move {
+            while let Some(entry) = rx.recv().await {
+                if let Err(e) = system.dispatch(entry).await {
+                    error!(error = %e, "Failed to dispatch queued audit log entry");
+                }
+            }
+        });
+        tx
+    }
+
+    /// Dispatches an audit log entry to all active targets, logging target failures
     pub async fn dispatch(&self, entry: Arc<AuditEntry>) -> AuditResult<()> {
+        self.deliver(entry, false).await
+    }
+
+    /// Sends `entry` to every target; with `strict`, the first target failure is returned
+    async fn deliver(&self, entry: Arc<AuditEntry>, strict: bool) -> AuditResult<()> {
         let start_time = std::time::Instant::now();
 
         let state = self.state.read().await;
@@ -249,6 +326,8 @@ impl AuditSystem {
         }
         drop(state);
 
+        audit_tail().publish(&entry);
+
         let registry = self.registry.lock().await;
         let target_ids = registry.list_targets();
 
@@ -308,12 +387,14 @@ impl AuditSystem {
             observability::record_audit_success(dispatch_time);
         } else {
             observability::record_audit_failure(dispatch_time);
-            // Log errors but don't fail the entire dispatch
             warn!(
                 error_count = errors.len(),
                 success_count = success_count,
                 "Some audit targets failed to receive log entry"
             );
+            if strict {
+                return Err(AuditError::Target(errors.swap_remove(0)));
+            }
         }
 
         Ok(())
@@ -328,6 +409,10 @@ impl AuditSystem {
         }
         drop(state);
 
+        for entry in &entries {
+            audit_tail().publish(entry);
+        }
+
         let registry = self.registry.lock().await;
         let target_ids = registry.list_targets();
 
@@ -608,3 +693,81 @@ impl AuditSystem {
         observability::reset_metrics().await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{ApiDetails, AuditEntryBuilder};
+    use nebulafx_targets::{EventName, arn::TargetID};
+
+    /// A backend whose every write fails
+    #[derive(Clone)]
+    struct FailingTarget;
+
+    #[async_trait::async_trait]
+    impl Target<AuditEntry> for FailingTarget {
+        fn id(&self) -> TargetID {
+            TargetID::new("failing".to_string(), "webhook".to_string())
+        }
+
+        async fn is_active(&self) -> Result<bool, TargetError> {
+            Ok(true)
+        }
+
+        async fn save(&self, _event: Arc<EntityTarget<AuditEntry>>) -> Result<(), TargetError> {
+            Err(TargetError::Network("backend unavailable".to_string()))
+        }
+
+        async fn send_from_store(&self, _key: Key) -> Result<(), TargetError> {
+            Ok(())
+        }
+
+        async fn close(&self) -> Result<(), TargetError> {
+            Ok(())
+        }
+
+        fn store(&self) -> Option<&(dyn Store<EntityTarget<AuditEntry>, Error = StoreError, Key = Key> + Send + Sync)> {
+            None
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Target<AuditEntry> + Send + Sync> {
+            Box::new(self.clone())
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+    }
+
+    async fn running_system(mode: AuditMode, queue_capacity: usize) -> AuditSystem {
+        let system = AuditSystem::with_queue_capacity(queue_capacity);
+        system.set_mode(mode);
+        system
+            .upsert_target("failing".to_string(), Box::new(FailingTarget))
+            .await
+            .unwrap();
+        *system.state.write().await = AuditSystemState::Running;
+        system
+    }
+
+    fn entry() -> Arc<AuditEntry> {
+        Arc::new(AuditEntryBuilder::new("1.0", EventName::ObjectCreatedPut, "s3:PutObject", ApiDetails::default()).build())
+    }
+
+    #[tokio::test]
+    async fn test_sync_mode_surfaces_backend_failure() {
+        let system = running_system(AuditMode::Sync, 1).await;
+        let err = system.submit(entry()).await.unwrap_err();
+        assert!(matches!(err, AuditError::Target(TargetError::Network(_))));
+        assert_eq!(system.dropped_entries(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_async_mode_acknowledges_and_drops_on_overflow() {
+        let system = running_system(AuditMode::Async, 1).await;
+        // The delivery task cannot run until this task yields, so the second entry overflows
+        assert!(system.submit(entry()).await.is_ok());
+        assert!(system.submit(entry()).await.is_ok());
+        assert_eq!(system.dropped_entries(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//  Copyright 2024 NebulaFX Team
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+//! Live tail of dispatched audit entries.
+//!
+//! Entries are fanned out over a broadcast channel to any number of subscribers.
+//! Publishing only records the entry in the recent-entries ring when nobody is
+//! listening, and a slow subscriber only loses the entries it lagged behind on
+//! rather than blocking dispatch.
+
+use crate::AuditEntry;
+use futures::Stream;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::{broadcast, watch};
+use tracing::debug;
+
+/// Number of entries buffered per subscriber before it starts lagging
+const AUDIT_TAIL_CAPACITY: usize = 1024;
+
+/// Most recent entries kept for support bundles
+pub const AUDIT_RECENT_CAPACITY: usize = 256;
+
+static AUDIT_TAIL: LazyLock<AuditTail> = LazyLock::new(AuditTail::new);
+
+/// Optional filters applied to tailed entries; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditTailFilter {
+    pub bucket: Option<String>,
+    pub principal: Option<String>,
+    pub action: Option<String>,
+}
+
+impl AuditTailFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        if let Some(bucket) = &self.bucket {
+            if entry.api.bucket.as_deref() != Some(bucket.as_str()) {
+                return false;
+            }
+        }
+        if let Some(principal) = &self.principal {
+            let matches_principal = entry.access_key.as_deref() == Some(principal.as_str())
+                || entry.parent_user.as_deref() == Some(principal.as_str());
+            if !matches_principal {
+                return false;
+            }
+        }
+        if let Some(action) = &self.action {
+            if entry.api.name.as_deref() != Some(action.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Broadcast hub for live audit entries
+pub struct AuditTail {
+    sender: broadcast::Sender<Arc<AuditEntry>>,
+    shutdown: watch::Sender<bool>,
+    recent: Mutex<VecDeque<Arc<AuditEntry>>>,
+}
+
+impl Default for AuditTail {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditTail {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(AUDIT_TAIL_CAPACITY);
+        let (shutdown, _) = watch::channel(false);
+        Self {
+            sender,
+            shutdown,
+            recent: Mutex::new(VecDeque::with_capacity(AUDIT_RECENT_CAPACITY)),
+        }
+    }
+
+    /// Publish an entry to current subscribers and the recent-entries ring
+    pub fn publish(&self, entry: &Arc<AuditEntry>) {
+        {
+            let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+            if recent.len() == AUDIT_RECENT_CAPACITY {
+                recent.pop_front();
+            }
+            recent.push_back(Arc::clone(entry));
+        }
+        if self.sender.receiver_count() > 0 {
+            // An error only means every subscriber went away in between
+            let _ = self.sender.send(Arc::clone(entry));
+        }
+    }
+
+    /// Up to [`AUDIT_RECENT_CAPACITY`] most recently published entries, oldest first
+    pub fn recent(&self) -> Vec<Arc<AuditEntry>> {
+        self.recent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Subscribe to entries matching `filter`
+    ///
+    /// The stream ends when `shutdown` is called. Dropping the stream unsubscribes.
+    pub fn subscribe(&self, filter: AuditTailFilter) -> impl Stream<Item = Arc<AuditEntry>> + Send + 'static {
+        let receiver = self.sender.subscribe();
+        let shutdown = self.shutdown.subscribe();
+
+        futures::stream::unfold((receiver, shutdown, filter), |(mut receiver, mut shutdown, filter)| async move {
+            loop {
+                if *shutdown.borrow() {
+                    return None;
+                }
+                tokio::select! {
+                    result = receiver.recv() => match result {
+                        Ok(entry) if filter.matches(&entry) => return Some((entry, (receiver, shutdown, filter))),
+                        Ok(_) => continue,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            debug!(skipped, "Audit tail subscriber lagged, entries dropped");
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    },
+                    changed = shutdown.changed() => {
+                        if changed.is_err() {
+                            return None;
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// End all current and future subscriptions
+    pub fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+/// Get the global audit tail
+pub fn audit_tail() -> &'static AuditTail {
+    &AUDIT_TAIL
+}
+
+/// Subscribe to the global audit tail
+pub fn subscribe_audit_tail(filter: AuditTailFilter) -> impl Stream<Item = Arc<AuditEntry>> + Send + 'static {
+    AUDIT_TAIL.subscribe(filter)
+}
+
+/// End all global audit tail subscriptions, called on server shutdown
+pub fn shutdown_audit_tail() {
+    AUDIT_TAIL.shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApiDetails, AuditEntry};
+    use futures::StreamExt;
+
+    fn entry(bucket: &str, action: &str, access_key: &str) -> Arc<AuditEntry> {
+        Arc::new(AuditEntry {
+            api: ApiDetails {
+                name: Some(action.to_string()),
+                bucket: Some(bucket.to_string()),
+                ..Default::default()
+            },
+            access_key: Some(access_key.to_string()),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_tail_streams_matching_entries_until_shutdown() {
+        let tail = AuditTail::new();
+        let stream = tail.subscribe(AuditTailFilter {
+            bucket: Some("photos".to_string()),
+            ..Default::default()
+        });
+        let mut stream = Box::pin(stream);
+        assert_eq!(tail.subscriber_count(), 1);
+
+        tail.publish(&entry("photos", "PutObject", "alice"));
+        tail.publish(&entry("logs", "PutObject", "alice"));
+        tail.publish(&entry("photos", "GetObject", "bob"));
+
+        let first = stream.next().await.unwrap();
+        assert_eq!(first.api.name.as_deref(), Some("PutObject"));
+        let second = stream.next().await.unwrap();
+        assert_eq!(second.api.name.as_deref(), Some("GetObject"));
+
+        tail.shutdown();
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dropping_stream_unsubscribes() {
+        let tail = AuditTail::new();
+        let stream = tail.subscribe(AuditTailFilter::default());
+        assert_eq!(tail.subscriber_count(), 1);
+
+        drop(stream);
+        assert_eq!(tail.subscriber_count(), 0);
+        // Without subscribers the entry is only kept in the recent ring
+        tail.publish(&entry("photos", "PutObject", "alice"));
+        assert_eq!(tail.recent().len(), 1);
+    }
+
+    #[test]
+    fn test_recent_ring_keeps_latest_entries() {
+        let tail = AuditTail::new();
+        for i in 0..AUDIT_RECENT_CAPACITY + 2 {
+            tail.publish(&entry("photos", &format!("op-{i}"), "alice"));
+        }
+        let recent = tail.recent();
+        assert_eq!(recent.len(), AUDIT_RECENT_CAPACITY);
+        assert_eq!(recent[0].api.name.as_deref(), Some("op-2"));
+    }
+
+    #[test]
+    fn test_filter_matches_principal_and_action() {
+        let e = entry("photos", "PutObject", "alice");
+
+        assert!(AuditTailFilter::default().matches(&e));
+        assert!(
+            AuditTailFilter {
+                principal: Some("alice".to_string()),
+                action: Some("PutObject".to_string()),
+                ..Default::default()
+            }
+            .matches(&e)
+        );
+        assert!(
+            !AuditTailFilter {
+                principal: Some("bob".to_string()),
+                ..Default::default()
+            }
+            .matches(&e)
+        );
+    }
+}
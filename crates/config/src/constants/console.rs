@@ -28,6 +28,24 @@ pub const ENV_CONSOLE_CORS_ALLOWED_ORIGINS: &str = "NEUBULAFX_CONSOLE_CORS_ALLOW
 /// Default CORS allowed origins for the console service
 pub const DEFAULT_CONSOLE_CORS_ALLOWED_ORIGINS: &str = "*";
 
+/// HTTP methods the console allows in CORS requests
+/// Comma-separated list of method names
+/// Environment variable: NEBULAFX_CONSOLE_CORS_ALLOWED_METHODS
+/// Example: NEBULAFX_CONSOLE_CORS_ALLOWED_METHODS=GET,OPTIONS
+pub const ENV_CONSOLE_CORS_ALLOWED_METHODS: &str = "NEBULAFX_CONSOLE_CORS_ALLOWED_METHODS";
+
+/// Default CORS allowed methods for the console service
+pub const DEFAULT_CONSOLE_CORS_ALLOWED_METHODS: &str = "GET,POST,PUT,DELETE,OPTIONS";
+
+/// How long browsers may cache a console CORS preflight, in seconds
+/// Sent as `Access-Control-Max-Age`; 0 leaves the header out
+/// Environment variable: NEBULAFX_CONSOLE_CORS_MAX_AGE
+/// Example: NEBULAFX_CONSOLE_CORS_MAX_AGE=3600
+pub const ENV_CONSOLE_CORS_MAX_AGE: &str = "NEBULAFX_CONSOLE_CORS_MAX_AGE";
+
+/// Default console CORS preflight cache time in seconds (10 minutes)
+pub const DEFAULT_CONSOLE_CORS_MAX_AGE: u64 = 600;
+
 /// Enable or disable the console service
 pub const ENV_CONSOLE_ENABLE: &str = "NEUBULAFX_CONSOLE_ENABLE";
 
@@ -80,6 +98,47 @@ pub const ENV_CONSOLE_AUTH_TIMEOUT: &str = "NEUBULAFX_CONSOLE_AUTH_TIMEOUT";
 /// Example: --console-auth-timeout 3600
 pub const DEFAULT_CONSOLE_AUTH_TIMEOUT: u64 = 3600;
 
+/// Maximum size of a console request body
+/// Bytes, optionally with a unit suffix such as `MB`, `MiB` or `GB`
+/// Environment variable: NEBULAFX_CONSOLE_BODY_LIMIT
+/// Example: NEBULAFX_CONSOLE_BODY_LIMIT=50MB
+pub const ENV_CONSOLE_BODY_LIMIT: &str = "NEBULAFX_CONSOLE_BODY_LIMIT";
+
+/// Default console request body limit in bytes
+/// Console requests carry small JSON documents; objects are uploaded through the S3 API
+/// Default value: 10 MiB
+pub const DEFAULT_CONSOLE_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+/// Client networks allowed to reach the console
+/// Comma-separated CIDR ranges or single addresses; when set, every other client gets 403
+/// Environment variable: NEBULAFX_CONSOLE_ALLOW_CIDRS
+/// Example: NEBULAFX_CONSOLE_ALLOW_CIDRS=127.0.0.0/8,10.0.0.0/8,::1
+pub const ENV_CONSOLE_ALLOW_CIDRS: &str = "NEBULAFX_CONSOLE_ALLOW_CIDRS";
+
+/// Client networks refused by the console with 403
+/// Comma-separated CIDR ranges or single addresses; takes precedence over the allow list
+/// Environment variable: NEBULAFX_CONSOLE_DENY_CIDRS
+/// Example: NEBULAFX_CONSOLE_DENY_CIDRS=10.0.13.0/24
+pub const ENV_CONSOLE_DENY_CIDRS: &str = "NEBULAFX_CONSOLE_DENY_CIDRS";
+
+/// Proxies whose `X-Forwarded-For` names the console client
+/// Comma-separated CIDR ranges or single addresses; the header is ignored from every other peer
+/// and the peer address is used instead. Applies to rate limiting, the IP filter and access logs.
+/// Environment variable: NEBULAFX_CONSOLE_TRUSTED_PROXIES
+/// Example: NEBULAFX_CONSOLE_TRUSTED_PROXIES=127.0.0.1,10.0.0.0/24
+pub const ENV_CONSOLE_TRUSTED_PROXIES: &str = "NEBULAFX_CONSOLE_TRUSTED_PROXIES";
+
+/// Toggle JSON console access logs
+/// When enabled, each console request is logged as one JSON object on the
+/// `nebulafx::console::access_json` target instead of as tracing fields
+/// Default value: false
+/// Environment variable: NEBULAFX_CONSOLE_ACCESS_LOG_JSON
+/// Example: NEBULAFX_CONSOLE_ACCESS_LOG_JSON=true
+pub const ENV_CONSOLE_ACCESS_LOG_JSON: &str = "NEBULAFX_CONSOLE_ACCESS_LOG_JSON";
+
+/// Default value for JSON console access logs
+pub const DEFAULT_CONSOLE_ACCESS_LOG_JSON: bool = false;
+
 /// Toggle update check
 /// It controls whether to check for newer versions of nebulafx
 /// Default value: true
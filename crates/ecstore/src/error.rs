@@ -173,6 +173,9 @@ pub enum StorageError {
     #[error("first disk wait")]
     FirstDiskWait,
 
+    #[error("formatting disks is not allowed: {0}")]
+    FormatNotAllowed(String),
+
     #[error("Io error: {0}")]
     Io(std::io::Error),
 
@@ -409,6 +412,7 @@ impl Clone for StorageError {
             StorageError::ErasureWriteQuorum => StorageError::ErasureWriteQuorum,
             StorageError::NotFirstDisk => StorageError::NotFirstDisk,
             StorageError::FirstDiskWait => StorageError::FirstDiskWait,
+            StorageError::FormatNotAllowed(a) => StorageError::FormatNotAllowed(a.clone()),
             StorageError::TooManyOpenFiles => StorageError::TooManyOpenFiles,
             StorageError::NoHealRequired => StorageError::NoHealRequired,
             StorageError::Lock(e) => StorageError::Lock(e.clone()),
@@ -484,6 +488,7 @@ impl StorageError {
             StorageError::PreconditionFailed => 0x3B,
             StorageError::EntityTooSmall(_, _, _) => 0x3C,
             StorageError::InvalidRangeSpec(_) => 0x3D,
+            StorageError::FormatNotAllowed(_) => 0x3E,
         }
     }
 
@@ -554,6 +559,7 @@ impl StorageError {
             0x3B => Some(StorageError::PreconditionFailed),
             0x3C => Some(StorageError::EntityTooSmall(Default::default(), Default::default(), Default::default())),
             0x3D => Some(StorageError::InvalidRangeSpec(Default::default())),
+            0x3E => Some(StorageError::FormatNotAllowed(Default::default())),
             _ => None,
         }
     }
@@ -1137,6 +1143,7 @@ mod tests {
             StorageError::BucketExists("test".to_string()),
             StorageError::ObjectNotFound("bucket".to_string(), "object".to_string()),
             StorageError::DecommissionAlreadyRunning,
+            StorageError::FormatNotAllowed("/data/disk1".to_string()),
         ];
 
         for original_error in test_errors {
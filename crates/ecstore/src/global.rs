@@ -6,13 +6,17 @@ use crate::{
     endpoints::{EndpointServerPools, PoolEndpoints, SetupType},
     event_notification::EventNotifier,
     store::ECStore,
+    store_init::ErasureLayout,
     tier::tier::TierConfigMgr,
 };
 use lazy_static::lazy_static;
 use nebulafx_policy::auth::Credentials;
 use std::{
     collections::HashMap,
-    sync::{Arc, OnceLock},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
     time::SystemTime,
 };
 use tokio::sync::{OnceCell, RwLock};
@@ -49,6 +53,12 @@ lazy_static! {
 /// Global cancellation token for background services (data scanner and auto heal)
 static GLOBAL_BACKGROUND_SERVICES_CANCEL_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
 
+/// Data/parity split of each pool, computed once the setup type is known
+static GLOBAL_ERASURE_LAYOUT: OnceLock<Vec<ErasureLayout>> = OnceLock::new();
+
+/// Whether blank disks may be formatted when a new deployment starts
+static GLOBAL_ALLOW_FORMAT: AtomicBool = AtomicBool::new(true);
+
 /// Global active credentials
 static GLOBAL_ACTIVE_CRED: OnceLock<Credentials> = OnceLock::new();
 
@@ -256,6 +266,36 @@ pub fn set_global_region(region: String) {
     GLOBAL_REGION.set(region).unwrap();
 }
 
+/// Set whether unformatted disks may be formatted on first boot
+///
+/// # Arguments
+/// * `allow` - `false` makes startup fail instead of formatting blank disks
+///
+/// # Returns
+/// * None
+pub fn set_global_allow_format(allow: bool) {
+    GLOBAL_ALLOW_FORMAT.store(allow, Ordering::Relaxed);
+}
+
+/// Whether unformatted disks may be formatted on first boot
+///
+/// # Returns
+/// * `bool` - `true` unless disabled with `set_global_allow_format`
+///
+pub fn is_format_allowed() -> bool {
+    GLOBAL_ALLOW_FORMAT.load(Ordering::Relaxed)
+}
+
+/// Record the data/parity split of each pool; later calls are ignored
+pub fn set_global_erasure_layout(layout: Vec<ErasureLayout>) {
+    let _ = GLOBAL_ERASURE_LAYOUT.set(layout);
+}
+
+/// Data/parity split of each pool, empty until set at startup
+pub fn get_global_erasure_layout() -> &'static [ErasureLayout] {
+    GLOBAL_ERASURE_LAYOUT.get().map(Vec::as_slice).unwrap_or_default()
+}
+
 /// Get the global region
 ///
 /// # Returns
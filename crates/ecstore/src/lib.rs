@@ -41,6 +41,7 @@ pub mod tier;
 pub use global::new_object_layer_fn;
 pub use global::set_global_endpoints;
 pub use global::update_erasure_type;
+pub use store_init::{ErasureLayout, erasure_layout};
 
 pub use global::GLOBAL_Endpoints;
 pub use store_api::StorageAPI;
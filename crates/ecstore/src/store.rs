@@ -17,7 +17,7 @@ use crate::error::{
 use crate::global::{
     DISK_ASSUME_UNKNOWN_SIZE, DISK_FILL_FRACTION, DISK_MIN_INODES, DISK_RESERVE_FRACTION, GLOBAL_BOOT_TIME,
     GLOBAL_LOCAL_DISK_MAP, GLOBAL_LOCAL_DISK_SET_DRIVES, GLOBAL_TierConfigMgr, get_global_deployment_id, get_global_endpoints,
-    is_dist_erasure, is_erasure_sd, set_global_deployment_id, set_object_layer,
+    is_dist_erasure, is_erasure_sd, is_format_allowed, set_global_deployment_id, set_object_layer,
 };
 use crate::notification_sys::get_global_notification_sys;
 use crate::pools::PoolMeta;
@@ -152,16 +152,20 @@ impl ECStore {
                 let mut times = 0;
                 let mut interval = 1;
                 loop {
-                    if let Ok(fm) = store_init::connect_load_init_formats(
+                    match store_init::connect_load_init_formats(
                         first_is_local,
                         &disks,
                         pool_eps.set_count,
                         pool_eps.drives_per_set,
                         deployment_id,
+                        is_format_allowed(),
                     )
                     .await
                     {
-                        break fm;
+                        Ok(fm) => break fm,
+                        // Retrying cannot help until the operator opts in
+                        Err(e @ Error::FormatNotAllowed(_)) => return Err(e),
+                        Err(_) => {}
                     }
                     times += 1;
                     if interval < 16 {
@@ -11,7 +11,7 @@ use crate::{
         format::{FormatErasureVersion, FormatMetaVersion, FormatV3},
         new_disk,
     },
-    endpoints::Endpoints,
+    endpoints::{EndpointServerPools, Endpoints, SetupType},
 };
 use futures::future::join_all;
 use std::collections::{HashMap, hash_map::Entry};
@@ -52,6 +52,7 @@ pub async fn connect_load_init_formats(
     set_count: usize,
     set_drive_count: usize,
     deployment_id: Option<Uuid>,
+    allow_format: bool,
 ) -> Result<FormatV3> {
     let (formats, errs) = load_format_erasure_all(disks, false).await;
 
@@ -64,6 +65,12 @@ pub async fn connect_load_init_formats(
     if first_disk && should_init_erasure_disks(&errs) {
         //  UnformattedDisk, not format file create
         info!("first_disk && should_init_erasure_disks");
+        let unformatted = unformatted_disks(disks, &errs);
+        if !allow_format {
+            warn!("Refusing to format unformatted disks, formatting is disabled: {}", unformatted.join(", "));
+            return Err(Error::FormatNotAllowed(unformatted.join(", ")));
+        }
+        info!("Formatting unformatted disks: {}", unformatted.join(", "));
         // new format and save
         let fm = init_format_erasure(disks, set_count, set_drive_count, deployment_id).await?;
 
@@ -90,6 +97,16 @@ pub async fn connect_load_init_formats(
     Ok(fm)
 }
 
+/// Endpoints of the disks that report `UnformattedDisk`
+fn unformatted_disks(disks: &[Option<DiskStore>], errs: &[Option<DiskError>]) -> Vec<String> {
+    disks
+        .iter()
+        .zip(errs)
+        .filter(|(_, err)| err.as_ref() == Some(&DiskError::UnformattedDisk))
+        .filter_map(|(disk, _)| disk.as_ref().map(|disk| disk.endpoint().to_string()))
+        .collect()
+}
+
 pub fn quorum_unformatted_disks(errs: &[Option<DiskError>]) -> bool {
     count_errs(errs, &DiskError::UnformattedDisk) > (errs.len() / 2)
 }
@@ -327,6 +344,47 @@ pub fn ec_drives_no_config(set_drive_count: usize) -> Result<usize> {
     Ok(sc.get_parity_for_sc(storageclass::STANDARD).unwrap_or_default())
 }
 
+/// Data and parity shards of every erasure set in one pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ErasureLayout {
+    pub pool: usize,
+    pub set_count: usize,
+    pub drives_per_set: usize,
+    pub data: usize,
+    pub parity: usize,
+}
+
+/// Effective data/parity split of each pool, as `ECStore::new` will apply it
+///
+/// Like the store, the parity derived for the first pool is used for every pool.
+/// A single-drive setup has no parity.
+pub fn erasure_layout(endpoint_pools: &EndpointServerPools, setup_type: &SetupType) -> Result<Vec<ErasureLayout>> {
+    let mut common_parity = None;
+    endpoint_pools
+        .as_ref()
+        .iter()
+        .enumerate()
+        .map(|(pool, eps)| {
+            let parity = match common_parity {
+                _ if *setup_type == SetupType::ErasureSD => 0,
+                Some(parity) => parity,
+                None => {
+                    let parity = ec_drives_no_config(eps.drives_per_set)?;
+                    storageclass::validate_parity(parity, eps.drives_per_set)?;
+                    *common_parity.insert(parity)
+                }
+            };
+            Ok(ErasureLayout {
+                pool,
+                set_count: eps.set_count,
+                drives_per_set: eps.drives_per_set,
+                data: eps.drives_per_set.saturating_sub(parity),
+                parity,
+            })
+        })
+        .collect()
+}
+
 // #[derive(Debug, PartialEq, thiserror::Error)]
 // pub enum ErasureError {
 //     #[error("erasure read quorum")]
@@ -377,3 +435,89 @@ pub fn ec_drives_no_config(set_drive_count: usize) -> Result<usize> {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk::endpoint::Endpoint;
+    use std::path::PathBuf;
+
+    /// Blank local disks under a fresh temp directory
+    async fn blank_disks(count: usize) -> (PathBuf, Vec<Option<DiskStore>>) {
+        let base = std::env::temp_dir().join(format!("nebulafx-format-test-{}", Uuid::new_v4()));
+        let opt = DiskOption {
+            cleanup: false,
+            health_check: false,
+        };
+        let mut disks = Vec::with_capacity(count);
+        for i in 0..count {
+            let path = base.join(format!("disk{i}"));
+            std::fs::create_dir_all(&path).unwrap();
+            let ep = Endpoint::try_from(path.to_str().unwrap()).unwrap();
+            disks.push(Some(new_disk(&ep, &opt).await.unwrap()));
+        }
+        (base, disks)
+    }
+
+    #[tokio::test]
+    async fn test_format_refused_when_not_allowed() {
+        let (base, disks) = blank_disks(4).await;
+
+        let err = connect_load_init_formats(true, &disks, 1, 4, None, false).await.unwrap_err();
+        match err {
+            Error::FormatNotAllowed(paths) => assert!(paths.contains("disk0") && paths.contains("disk3")),
+            e => panic!("unexpected error: {e}"),
+        }
+        // Nothing was written, so the disks are still blank
+        let (_, errs) = load_format_erasure_all(&disks, false).await;
+        assert!(should_init_erasure_disks(&errs));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[tokio::test]
+    async fn test_format_allowed_formats_blank_disks() {
+        let (base, disks) = blank_disks(4).await;
+
+        let fm = connect_load_init_formats(true, &disks, 1, 4, None, true).await.unwrap();
+        assert_eq!(fm.drives(), 4);
+        let (formats, _) = load_format_erasure_all(&disks, false).await;
+        assert!(formats.iter().all(Option::is_some));
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    fn pools(shape: &[(usize, usize)]) -> EndpointServerPools {
+        EndpointServerPools(
+            shape
+                .iter()
+                .map(|&(set_count, drives_per_set)| crate::endpoints::PoolEndpoints {
+                    legacy: false,
+                    set_count,
+                    drives_per_set,
+                    endpoints: Endpoints::default(),
+                    cmd_line: String::new(),
+                    platform: String::new(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_erasure_layout_per_volume_configuration() {
+        let layout = |shape: &[(usize, usize)], setup| {
+            erasure_layout(&pools(shape), &setup)
+                .unwrap()
+                .iter()
+                .map(|l| (l.data, l.parity))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(layout(&[(1, 1)], SetupType::ErasureSD), [(1, 0)]);
+        assert_eq!(layout(&[(1, 4)], SetupType::Erasure), [(2, 2)]);
+        assert_eq!(layout(&[(1, 8)], SetupType::Erasure), [(4, 4)]);
+        assert_eq!(layout(&[(2, 16)], SetupType::DistErasure), [(12, 4)]);
+        // Later pools reuse the first pool's parity
+        assert_eq!(layout(&[(1, 4), (1, 8)], SetupType::Erasure), [(2, 2), (6, 2)]);
+    }
+}
@@ -1,25 +1,46 @@
+use crate::error::Error;
 use crate::migrations::ALL_MIGRATIONS;
 use crate::repository::user::UserRepository;
+use nebulafx_postgresqlx::PostgreSQLPool;
 use sqlx::PgPool;
 use tracing::info;
 
 /// Initialize database tables
 /// 
+/// Tables already present in `schema` are left untouched; only the missing ones are created.
+/// 
 /// # Arguments
 /// * `pool` - PostgreSQL connection pool
+/// * `schema` - Schema the IAM tables live in
 /// 
 /// # Returns
-/// Returns `Ok(())` on success, or an error if table creation fails
-pub async fn init_database(pool: &PgPool) -> Result<(), sqlx::Error> {
-    info!("Creating IAM database tables...");
-    
-    for migration in ALL_MIGRATIONS {
-        sqlx::query(migration)
-            .execute(pool)
-            .await?;
+/// Returns `Ok(())` on success, or an error if the check or table creation fails
+pub async fn init_database(pool: &PostgreSQLPool, schema: &str) -> nebulafx_postgresqlx::Result<()> {
+    for (table, migration) in ALL_MIGRATIONS {
+        if pool.table_exists(schema, table).await? {
+            continue;
+        }
+        info!("Creating IAM table '{}' in schema '{}'", table, schema);
+        pool.execute(migration).await?;
     }
     
-    info!("All IAM database tables created successfully");
+    info!("IAM database tables are ready");
+    Ok(())
+}
+
+/// Minimum length accepted for the configured root password
+pub const MIN_ROOT_PASSWORD_LEN: usize = 8;
+
+/// Validate root credentials before they are written to the database
+pub fn validate_root_credentials(root_user: &str, root_password: &str) -> crate::error::Result<()> {
+    if root_user.is_empty() {
+        return Err(Error::StringError("root user must not be empty".to_string()));
+    }
+    if root_password.len() < MIN_ROOT_PASSWORD_LEN {
+        return Err(Error::StringError(format!(
+            "root password must be at least {MIN_ROOT_PASSWORD_LEN} characters"
+        )));
+    }
     Ok(())
 }
 
@@ -53,3 +74,33 @@ pub async fn init_root_user(
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_root_credentials() {
+        assert!(validate_root_credentials("nebulafxadmin", "nebulafxadmin").is_ok());
+        assert!(validate_root_credentials("admin", "12345678").is_ok());
+        assert!(validate_root_credentials("admin", "1234567").is_err());
+        assert!(validate_root_credentials("", "nebulafxadmin").is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_root_user_exists_after_init() {
+        assert!(std::env::var("DATABASE_URL").is_ok(), "DATABASE_URL must be set");
+        let db = PostgreSQLPool::init_or_replace(Some(&Default::default())).await.unwrap();
+        let pool = db.inner();
+
+        init_database(&db, "public").await.unwrap();
+        // Existing tables are detected instead of being created again
+        init_database(&db, "public").await.unwrap();
+        init_root_user(pool, "nebulafxadmin", "nebulafxadmin").await.unwrap();
+        // Seeding twice must be idempotent
+        init_root_user(pool, "nebulafxadmin", "nebulafxadmin").await.unwrap();
+
+        assert!(UserRepository::exists_by_id(pool, 1).await.unwrap());
+        assert!(UserRepository::exists_by_access_key(pool, "nebulafxadmin").await.unwrap());
+    }
+}
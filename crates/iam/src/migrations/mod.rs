@@ -1,6 +1,6 @@
 /// SQL migration for creating users table
 pub const CREATE_USERS_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS users (
+CREATE TABLE users (
     id BIGSERIAL PRIMARY KEY,
     access_key VARCHAR(255) NOT NULL UNIQUE,
     secret_key VARCHAR(255) NOT NULL,
@@ -9,12 +9,12 @@ CREATE TABLE IF NOT EXISTS users (
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
 );
 
-CREATE INDEX IF NOT EXISTS idx_users_access_key ON users(access_key);
+CREATE INDEX idx_users_access_key ON users(access_key);
 "#;
 
 /// SQL migration for creating policies table
 pub const CREATE_POLICIES_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS policies (
+CREATE TABLE policies (
     id BIGSERIAL PRIMARY KEY,
     name VARCHAR(255) NOT NULL UNIQUE,
     policy_doc JSONB NOT NULL,
@@ -23,12 +23,12 @@ CREATE TABLE IF NOT EXISTS policies (
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
 );
 
-CREATE INDEX IF NOT EXISTS idx_policies_name ON policies(name);
+CREATE INDEX idx_policies_name ON policies(name);
 "#;
 
 /// SQL migration for creating groups table
 pub const CREATE_GROUPS_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS groups (
+CREATE TABLE groups (
     id BIGSERIAL PRIMARY KEY,
     name VARCHAR(255) NOT NULL UNIQUE,
     status VARCHAR(50) NOT NULL DEFAULT 'enabled',
@@ -38,12 +38,12 @@ CREATE TABLE IF NOT EXISTS groups (
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
 );
 
-CREATE INDEX IF NOT EXISTS idx_groups_name ON groups(name);
+CREATE INDEX idx_groups_name ON groups(name);
 "#;
 
 /// SQL migration for creating mapped_policies table
 pub const CREATE_MAPPED_POLICIES_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS mapped_policies (
+CREATE TABLE mapped_policies (
     id BIGSERIAL PRIMARY KEY,
     name VARCHAR(255) NOT NULL,
     user_type VARCHAR(50) NOT NULL,
@@ -54,14 +54,14 @@ CREATE TABLE IF NOT EXISTS mapped_policies (
     UNIQUE(name, user_type, is_group)
 );
 
-CREATE INDEX IF NOT EXISTS idx_mapped_policies_name ON mapped_policies(name);
-CREATE INDEX IF NOT EXISTS idx_mapped_policies_user_type ON mapped_policies(user_type);
-CREATE INDEX IF NOT EXISTS idx_mapped_policies_is_group ON mapped_policies(is_group);
+CREATE INDEX idx_mapped_policies_name ON mapped_policies(name);
+CREATE INDEX idx_mapped_policies_user_type ON mapped_policies(user_type);
+CREATE INDEX idx_mapped_policies_is_group ON mapped_policies(is_group);
 "#;
 
 /// SQL migration for creating user_identities table (for storing UserIdentity data)
 pub const CREATE_USER_IDENTITIES_TABLE: &str = r#"
-CREATE TABLE IF NOT EXISTS user_identities (
+CREATE TABLE user_identities (
     id BIGSERIAL PRIMARY KEY,
     name VARCHAR(255) NOT NULL,
     user_type VARCHAR(50) NOT NULL,
@@ -72,16 +72,16 @@ CREATE TABLE IF NOT EXISTS user_identities (
     UNIQUE(name, user_type)
 );
 
-CREATE INDEX IF NOT EXISTS idx_user_identities_name ON user_identities(name);
-CREATE INDEX IF NOT EXISTS idx_user_identities_user_type ON user_identities(user_type);
+CREATE INDEX idx_user_identities_name ON user_identities(name);
+CREATE INDEX idx_user_identities_user_type ON user_identities(user_type);
 "#;
 
-/// All migration SQL statements
-pub const ALL_MIGRATIONS: &[&str] = &[
-    CREATE_USERS_TABLE,
-    CREATE_POLICIES_TABLE,
-    CREATE_GROUPS_TABLE,
-    CREATE_MAPPED_POLICIES_TABLE,
-    CREATE_USER_IDENTITIES_TABLE,
+/// All migration SQL statements, keyed by the table each one creates
+pub const ALL_MIGRATIONS: &[(&str, &str)] = &[
+    ("users", CREATE_USERS_TABLE),
+    ("policies", CREATE_POLICIES_TABLE),
+    ("groups", CREATE_GROUPS_TABLE),
+    ("mapped_policies", CREATE_MAPPED_POLICIES_TABLE),
+    ("user_identities", CREATE_USER_IDENTITIES_TABLE),
 ];
 
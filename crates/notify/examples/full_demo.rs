@@ -11,7 +11,7 @@ use nebulafx_config::{
 };
 use nebulafx_ecstore::config::{Config, KV, KVS};
 use nebulafx_notify::{BucketNotificationConfig, Event, NotificationError};
-use nebulafx_notify::{initialize, notification_system};
+use nebulafx_notify::{QueueLimits, initialize, notification_system};
 use nebulafx_targets::EventName;
 use nebulafx_targets::arn::TargetID;
 use std::sync::Arc;
@@ -26,7 +26,7 @@ async fn main() -> Result<(), NotificationError> {
         Some(sys) => sys,
         None => {
             let config = Config::new();
-            initialize(config).await?;
+            initialize(config, QueueLimits::default()).await?;
             notification_system().expect("Failed to initialize notification system")
         }
     };
@@ -29,6 +29,10 @@ pub trait TargetFactory: Send + Sync {
     /// Validates target configuration
     fn validate_config(&self, id: &str, config: &KVS) -> Result<(), TargetError>;
 
+    /// Checks that the target described by `config` is reachable, without
+    /// registering it, persisting anything or sending an event
+    async fn probe_target(&self, id: String, config: &KVS) -> Result<(), TargetError>;
+
     /// Returns a set of valid configuration field names for this target type.
     /// This is used to filter environment variables.
     fn get_valid_fields(&self) -> HashSet<String>;
@@ -96,6 +100,21 @@ impl TargetFactory for WebhookTargetFactory {
         Ok(())
     }
 
+    async fn probe_target(&self, id: String, config: &KVS) -> Result<(), TargetError> {
+        // Probe without a queue store so nothing is created on disk
+        let mut config = config.clone();
+        config.insert(WEBHOOK_QUEUE_DIR.to_string(), String::new());
+
+        let target = self.create_target(id, &config).await?;
+        let result = target.is_active().await;
+        let _ = target.close().await;
+        match result {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(TargetError::NotConnected),
+            Err(e) => Err(e),
+        }
+    }
+
     fn get_valid_fields(&self) -> HashSet<String> {
         NOTIFY_WEBHOOK_KEYS.iter().map(|s| s.to_string()).collect()
     }
@@ -201,6 +220,19 @@ impl TargetFactory for MQTTTargetFactory {
         Ok(())
     }
 
+    async fn probe_target(&self, _id: String, config: &KVS) -> Result<(), TargetError> {
+        let broker = config
+            .lookup(MQTT_BROKER)
+            .ok_or_else(|| TargetError::Configuration("Missing MQTT broker".to_string()))?;
+        let topic = config
+            .lookup(MQTT_TOPIC)
+            .ok_or_else(|| TargetError::Configuration("Missing MQTT topic".to_string()))?;
+
+        nebulafx_targets::check_mqtt_broker_available(&broker, &topic)
+            .await
+            .map_err(TargetError::Network)
+    }
+
     fn get_valid_fields(&self) -> HashSet<String> {
         NOTIFY_MQTT_KEYS.iter().map(|s| s.to_string()).collect()
     }
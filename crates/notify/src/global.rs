@@ -1,6 +1,6 @@
 
 
-use crate::{BucketNotificationConfig, Event, EventArgs, LifecycleError, NotificationError, NotificationSystem};
+use crate::{BucketNotificationConfig, Event, EventArgs, LifecycleError, NotificationError, NotificationSystem, QueueLimits};
 use nebulafx_ecstore::config::Config;
 use nebulafx_targets::{EventName, arn::TargetID};
 use std::sync::{Arc, OnceLock};
@@ -10,9 +10,10 @@ static NOTIFICATION_SYSTEM: OnceLock<Arc<NotificationSystem>> = OnceLock::new();
 
 /// Initialize the global notification system with the given configuration.
 /// This function should only be called once throughout the application life cycle.
-pub async fn initialize(config: Config) -> Result<(), NotificationError> {
+/// `queue_limits` bounds the events buffered for each target.
+pub async fn initialize(config: Config, queue_limits: QueueLimits) -> Result<(), NotificationError> {
     // `new` is synchronous and responsible for creating instances
-    let system = NotificationSystem::new(config);
+    let system = NotificationSystem::with_queue_limits(config, queue_limits);
     // `init` is asynchronous and responsible for performing I/O-intensive initialization
     system.init().await?;
 
@@ -1,7 +1,8 @@
 
 
 use crate::{
-    Event, error::NotificationError, notifier::EventNotifier, registry::TargetRegistry, rules::BucketNotificationConfig, stream,
+    Event, error::NotificationError, notifier::EventNotifier, queue::QueueLimits, registry::TargetRegistry,
+    rules::BucketNotificationConfig, stream,
 };
 use hashbrown::HashMap;
 use nebulafx_ecstore::config::{Config, KVS};
@@ -96,8 +97,13 @@ pub struct NotificationSystem {
 impl NotificationSystem {
     /// Creates a new NotificationSystem
     pub fn new(config: Config) -> Self {
+        Self::with_queue_limits(config, QueueLimits::default())
+    }
+
+    /// Creates a new NotificationSystem whose per-target event queues use `queue_limits`
+    pub fn with_queue_limits(config: Config, queue_limits: QueueLimits) -> Self {
         NotificationSystem {
-            notifier: Arc::new(EventNotifier::new()),
+            notifier: Arc::new(EventNotifier::with_queue_limits(queue_limits)),
             registry: Arc::new(TargetRegistry::new()),
             config: Arc::new(RwLock::new(config)),
             stream_cancellers: Arc::new(RwLock::new(HashMap::new())),
@@ -415,8 +421,8 @@ impl NotificationSystem {
         }
 
         let rules_map = config.get_rules_map();
-        self.notifier.add_rules_map(bucket_name, rules_map.clone()).await;
-        info!("Loaded notification config for bucket: {}", bucket_name);
+        let update = self.notifier.add_rules_map(bucket_name, rules_map.clone()).await;
+        info!("Loaded notification config for bucket: {} ({:?})", bucket_name, update);
         Ok(())
     }
 
@@ -441,6 +447,9 @@ impl NotificationSystem {
     pub async fn shutdown(&self) {
         info!("Turn off the notification system");
 
+        // Hand queued events to their targets before the streams reading the target stores stop
+        self.notifier.shutdown().await;
+
         // Get the number of active targets
         let active_targets = self.stream_cancellers.read().await.len();
         info!("Stops {} active event stream processing tasks", active_targets);
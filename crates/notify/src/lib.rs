@@ -12,6 +12,7 @@ pub mod factory;
 mod global;
 pub mod integration;
 pub mod notifier;
+mod queue;
 pub mod registry;
 pub mod rules;
 pub mod stream;
@@ -20,4 +21,5 @@ pub use error::{LifecycleError, NotificationError};
 pub use event::{Event, EventArgs, EventArgsBuilder};
 pub use global::{initialize, is_notification_system_initialized, notification_system, notifier_global};
 pub use integration::NotificationSystem;
+pub use queue::{DEFAULT_QUEUE_LIMIT, OverflowPolicy, PushOutcome, QueueLimits};
 pub use rules::BucketNotificationConfig;
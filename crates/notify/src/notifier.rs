@@ -1,5 +1,6 @@
 
 
+use crate::queue::{PushOutcome, QueueLimits, TargetQueue};
 use crate::{error::NotificationError, event::Event, rules::RulesMap};
 use hashbrown::HashMap;
 use nebulafx_targets::EventName;
@@ -8,13 +9,61 @@ use nebulafx_targets::arn::TargetID;
 use nebulafx_targets::target::EntityTarget;
 use starshard::AsyncShardedHashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, instrument, warn};
 
+type EventQueue = TargetQueue<Arc<EntityTarget<Event>>>;
+
+/// Outcome of registering a bucket's rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesUpdate {
+    /// The bucket had no rules before
+    Added,
+    /// Different rules were registered before and have been replaced
+    Replaced,
+    /// Identical rules were already registered
+    Unchanged,
+    /// The new rules were empty, so the bucket's rules were removed
+    Removed,
+}
+
+/// A target's queue and the task delivering from it
+struct TargetWorker {
+    queue: Arc<EventQueue>,
+    handle: JoinHandle<()>,
+}
+
+/// Deliver queued events to a target one at a time, until the queue is closed and drained
+///
+/// The target is looked up per event so reloaded targets pick up the queue.
+fn spawn_target_worker(target_list: Arc<RwLock<TargetList>>, target_id: TargetID, queue: Arc<EventQueue>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(entity_target) = queue.pop().await {
+            let Some(target) = target_list.read().await.get(&target_id) else {
+                warn!("Target {} is no longer registered, discarding queued event", target_id);
+                continue;
+            };
+            if let Err(e) = target.save(entity_target).await {
+                error!("Failed to send event to target {}: {}", target_id, e);
+            } else {
+                debug!("Successfully saved event to target {}", target_id);
+            }
+        }
+        debug!("Event queue for target {} drained, worker stopped", target_id);
+    })
+}
+
 /// Manages event notification to targets based on rules
 pub struct EventNotifier {
     target_list: Arc<RwLock<TargetList>>,
     bucket_rules_map: Arc<AsyncShardedHashMap<String, RulesMap, rustc_hash::FxBuildHasher>>,
+    /// Bounded per-target queues, each drained by its own worker task
+    queues: RwLock<HashMap<TargetID, TargetWorker>>,
+    queue_limits: QueueLimits,
+    /// Set by [`EventNotifier::shutdown`]; no queues are started afterwards
+    shut_down: AtomicBool,
 }
 
 impl Default for EventNotifier {
@@ -26,9 +75,17 @@ impl Default for EventNotifier {
 impl EventNotifier {
     /// Creates a new EventNotifier
     pub fn new() -> Self {
+        Self::with_queue_limits(QueueLimits::default())
+    }
+
+    /// Creates a new EventNotifier whose per-target queues use `queue_limits`
+    pub fn with_queue_limits(queue_limits: QueueLimits) -> Self {
         EventNotifier {
             target_list: Arc::new(RwLock::new(TargetList::new())),
             bucket_rules_map: Arc::new(AsyncShardedHashMap::new(0)),
+            queues: RwLock::new(HashMap::new()),
+            queue_limits,
+            shut_down: AtomicBool::new(false),
         }
     }
 
@@ -62,14 +119,35 @@ impl EventNotifier {
             .collect()
     }
 
-    /// Adds a rules map for a bucket
-    pub async fn add_rules_map(&self, bucket_name: &str, rules_map: RulesMap) {
-        if rules_map.is_empty() {
-            self.bucket_rules_map.remove(&bucket_name.to_string()).await;
+    /// Sets the rules map for a bucket
+    ///
+    /// The bucket's rules are replaced, never merged, so registering the same
+    /// configuration again (e.g. at every startup) cannot accumulate duplicates.
+    pub async fn add_rules_map(&self, bucket_name: &str, rules_map: RulesMap) -> RulesUpdate {
+        let key = bucket_name.to_string();
+        let existing = self.bucket_rules_map.get(&key).await;
+
+        let update = if rules_map.is_empty() {
+            self.bucket_rules_map.remove(&key).await;
+            RulesUpdate::Removed
+        } else if existing.as_ref() == Some(&rules_map) {
+            RulesUpdate::Unchanged
         } else {
-            self.bucket_rules_map.insert(bucket_name.to_string(), rules_map).await;
+            self.bucket_rules_map.insert(key, rules_map).await;
+            if existing.is_some() {
+                RulesUpdate::Replaced
+            } else {
+                RulesUpdate::Added
+            }
+        };
+
+        match update {
+            RulesUpdate::Added => info!("Added rules for bucket: {}", bucket_name),
+            RulesUpdate::Replaced => info!("Replaced rules for bucket: {}", bucket_name),
+            RulesUpdate::Unchanged => info!("Rules for bucket {} already present, nothing to add", bucket_name),
+            RulesUpdate::Removed => info!("Removed rules for bucket: {}", bucket_name),
         }
-        info!("Added rules for bucket: {}", bucket_name);
+        update
     }
 
     /// Gets the rules map for a specific bucket.
@@ -125,37 +203,21 @@ impl EventNotifier {
                 return;
             }
             let target_ids_len = target_ids.len();
-            let mut handles = vec![];
+            let mut pending = Vec::with_capacity(target_ids_len);
 
             // Use scope to limit the borrow scope of target_list
             {
                 let target_list_guard = self.target_list.read().await;
                 info!("Sending event to targets: {:?}", target_ids);
                 for target_id in target_ids {
-                    // `get` now returns Option<Arc<dyn Target + Send + Sync>>
-                    if let Some(target_arc) = target_list_guard.get(&target_id) {
-                        // Clone an Arc<Box<dyn Target>> (which is where target_list is stored) to move into an asynchronous task
-                        // target_arc is already Arc, clone it for the async task
-                        let cloned_target_for_task = target_arc.clone();
-                        let event_clone = event.clone();
-                        let target_name_for_task = cloned_target_for_task.name(); // Get the name before generating the task
-                        debug!("Preparing to send event to target: {}", target_name_for_task);
-                        // Use cloned data in closures to avoid borrowing conflicts
-                        // Create an EntityTarget from the event
+                    if target_list_guard.get(&target_id).is_some() {
                         let entity_target: Arc<EntityTarget<Event>> = Arc::new(EntityTarget {
                             object_name: object_key.to_string(),
                             bucket_name: bucket_name.to_string(),
                             event_name,
-                            data: event_clone.clone().as_ref().clone(),
-                        });
-                        let handle = tokio::spawn(async move {
-                            if let Err(e) = cloned_target_for_task.save(entity_target.clone()).await {
-                                error!("Failed to send event to target {}: {}", target_name_for_task, e);
-                            } else {
-                                debug!("Successfully saved event to target {}", target_name_for_task);
-                            }
+                            data: event.as_ref().clone(),
                         });
-                        handles.push(handle);
+                        pending.push((target_id, entity_target));
                     } else {
                         warn!("Target ID {:?} found in rules but not in target list.", target_id);
                     }
@@ -163,10 +225,17 @@ impl EventNotifier {
                 // target_list is automatically released here
             }
 
-            // Wait for all tasks to be completed
-            for handle in handles {
-                if let Err(e) = handle.await {
-                    error!("Task for sending/saving event failed: {}", e);
+            // Queue outside the target list lock, since the `block` policy may wait here
+            for (target_id, entity_target) in pending {
+                let Some(queue) = self.target_queue(&target_id).await else {
+                    warn!("Event notifier is shut down, discarding event for target {}", target_id);
+                    continue;
+                };
+                match queue.push(entity_target).await {
+                    PushOutcome::Queued => {}
+                    PushOutcome::DroppedOldest => warn!("Event queue for target {} is full, dropped the oldest event", target_id),
+                    PushOutcome::DroppedNew => warn!("Event queue for target {} is full, dropped the new event", target_id),
+                    PushOutcome::Closed => warn!("Event notifier is shut down, discarding event for target {}", target_id),
                 }
             }
             info!("Event processing initiated for {} targets for bucket: {}", target_ids_len, bucket_name);
@@ -175,6 +244,41 @@ impl EventNotifier {
         }
     }
 
+    /// Returns the queue for a target, starting its worker on first use; `None` after shutdown
+    async fn target_queue(&self, target_id: &TargetID) -> Option<Arc<EventQueue>> {
+        if let Some(worker) = self.queues.read().await.get(target_id) {
+            return Some(worker.queue.clone());
+        }
+
+        let mut queues = self.queues.write().await;
+        if self.shut_down.load(Ordering::Acquire) {
+            return None;
+        }
+        let worker = queues.entry(target_id.clone()).or_insert_with(|| {
+            let queue = Arc::new(TargetQueue::new(target_id.to_string(), self.queue_limits));
+            let handle = spawn_target_worker(self.target_list.clone(), target_id.clone(), queue.clone());
+            TargetWorker { queue, handle }
+        });
+        Some(worker.queue.clone())
+    }
+
+    /// Stop accepting events and wait for every target worker to deliver what is already queued
+    pub async fn shutdown(&self) {
+        let workers: Vec<TargetWorker> = {
+            let mut queues = self.queues.write().await;
+            self.shut_down.store(true, Ordering::Release);
+            queues.drain().map(|(_, worker)| worker).collect()
+        };
+        for worker in &workers {
+            worker.queue.close();
+        }
+        for worker in workers {
+            if let Err(e) = worker.handle.await {
+                error!("Event queue worker failed during shutdown: {}", e);
+            }
+        }
+    }
+
     /// Initializes the targets for buckets
     #[instrument(skip(self, targets_to_init))]
     pub async fn init_bucket_targets(
@@ -276,3 +380,56 @@ impl TargetList {
         self.targets.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(target: &str) -> RulesMap {
+        let mut rules = RulesMap::new();
+        rules.add_rule_config(
+            &[EventName::ObjectCreatedAll],
+            "images/*".to_string(),
+            TargetID::new(target.to_string(), "webhook".to_string()),
+        );
+        rules
+    }
+
+    #[tokio::test]
+    async fn test_registering_same_rules_twice_does_not_duplicate() {
+        let notifier = EventNotifier::new();
+
+        assert_eq!(notifier.add_rules_map("photos", rules("1")).await, RulesUpdate::Added);
+        assert_eq!(notifier.add_rules_map("photos", rules("1")).await, RulesUpdate::Unchanged);
+
+        let registered = notifier.get_rules_map("photos").await.unwrap();
+        assert_eq!(registered, rules("1"));
+        let targets = registered.match_rules(EventName::ObjectCreatedPut, "images/cat.png");
+        assert_eq!(targets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_changed_rules_replace_previous_ones() {
+        let notifier = EventNotifier::new();
+        notifier.add_rules_map("photos", rules("1")).await;
+
+        assert_eq!(notifier.add_rules_map("photos", rules("2")).await, RulesUpdate::Replaced);
+        assert_eq!(notifier.get_rules_map("photos").await, Some(rules("2")));
+
+        assert_eq!(notifier.add_rules_map("photos", RulesMap::new()).await, RulesUpdate::Removed);
+        assert_eq!(notifier.get_rules_map("photos").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_queue_workers() {
+        let notifier = EventNotifier::new();
+        let target_id = TargetID::new("1".to_string(), "webhook".to_string());
+        assert!(notifier.target_queue(&target_id).await.is_some());
+
+        // Returns only once the idle worker has seen its queue close
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.shutdown())
+            .await
+            .unwrap();
+        assert!(notifier.target_queue(&target_id).await.is_none());
+    }
+}
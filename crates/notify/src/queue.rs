@@ -0,0 +1,290 @@
+use metrics::counter;
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+/// Default number of events buffered per target
+pub const DEFAULT_QUEUE_LIMIT: usize = 10_000;
+
+/// What to do with an event when a target's queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Evict the oldest queued event to make room
+    #[default]
+    DropOldest,
+    /// Discard the incoming event
+    DropNew,
+    /// Wait for the target to drain, applying backpressure to the sender
+    Block,
+}
+
+impl OverflowPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverflowPolicy::DropOldest => "drop_oldest",
+            OverflowPolicy::DropNew => "drop_new",
+            OverflowPolicy::Block => "block",
+        }
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "drop_oldest" => Ok(OverflowPolicy::DropOldest),
+            "drop_new" => Ok(OverflowPolicy::DropNew),
+            "block" => Ok(OverflowPolicy::Block),
+            other => Err(format!(
+                "invalid overflow policy '{other}', expected one of: drop_oldest, drop_new, block"
+            )),
+        }
+    }
+}
+
+/// Bound and overflow behaviour of each target's event queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueLimits {
+    pub capacity: usize,
+    pub policy: OverflowPolicy,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUEUE_LIMIT,
+            policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// Result of queueing an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Queued,
+    /// The event was queued after evicting the oldest one
+    DroppedOldest,
+    /// The event was discarded
+    DroppedNew,
+    /// The queue was closed, so the event was discarded
+    Closed,
+}
+
+/// Bounded FIFO between the notifier and a single target
+pub(crate) struct TargetQueue<T> {
+    target: String,
+    limits: QueueLimits,
+    items: Mutex<VecDeque<T>>,
+    closed: AtomicBool,
+    not_empty: Notify,
+    not_full: Notify,
+}
+
+impl<T> TargetQueue<T> {
+    pub(crate) fn new(target: impl Into<String>, limits: QueueLimits) -> Self {
+        Self {
+            target: target.into(),
+            limits: QueueLimits {
+                capacity: limits.capacity.max(1),
+                ..limits
+            },
+            items: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+            not_empty: Notify::new(),
+            not_full: Notify::new(),
+        }
+    }
+
+    /// Queue an event, applying the overflow policy when the queue is full
+    pub(crate) async fn push(&self, item: T) -> PushOutcome {
+        loop {
+            // Register interest before checking so a concurrent pop cannot be missed
+            let not_full = self.not_full.notified();
+            {
+                let mut items = self.items.lock().unwrap();
+                if self.is_closed() {
+                    return PushOutcome::Closed;
+                }
+                if items.len() < self.limits.capacity {
+                    items.push_back(item);
+                    drop(items);
+                    self.not_empty.notify_one();
+                    return PushOutcome::Queued;
+                }
+
+                match self.limits.policy {
+                    OverflowPolicy::DropOldest => {
+                        items.pop_front();
+                        items.push_back(item);
+                        drop(items);
+                        self.record_drop();
+                        self.not_empty.notify_one();
+                        return PushOutcome::DroppedOldest;
+                    }
+                    OverflowPolicy::DropNew => {
+                        drop(items);
+                        self.record_drop();
+                        return PushOutcome::DroppedNew;
+                    }
+                    OverflowPolicy::Block => {}
+                }
+            }
+            not_full.await;
+        }
+    }
+
+    /// Wait for the next queued event; `None` once the queue is closed and drained
+    pub(crate) async fn pop(&self) -> Option<T> {
+        loop {
+            let not_empty = self.not_empty.notified();
+            {
+                let mut items = self.items.lock().unwrap();
+                if let Some(item) = items.pop_front() {
+                    drop(items);
+                    self.not_full.notify_one();
+                    return Some(item);
+                }
+                if self.is_closed() {
+                    return None;
+                }
+            }
+            not_empty.await;
+        }
+    }
+
+    /// Refuse further events; those already queued are still handed out by [`TargetQueue::pop`]
+    pub(crate) fn close(&self) {
+        // Under the lock, so no push can land after a pop has found the queue closed and empty
+        let items = self.items.lock().unwrap();
+        self.closed.store(true, Ordering::Release);
+        drop(items);
+        self.not_empty.notify_waiters();
+        // Senders blocked on a full queue give up rather than wait for a stopped worker
+        self.not_full.notify_waiters();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    fn record_drop(&self) {
+        counter!(
+            "nebulafx_notify_events_dropped_total",
+            "target" => self.target.clone(),
+            "policy" => self.limits.policy.as_str()
+        )
+        .increment(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn queue(policy: OverflowPolicy) -> TargetQueue<u32> {
+        TargetQueue::new("webhook:1", QueueLimits { capacity: 2, policy })
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_newest_events() {
+        let queue = queue(OverflowPolicy::DropOldest);
+        assert_eq!(queue.push(1).await, PushOutcome::Queued);
+        assert_eq!(queue.push(2).await, PushOutcome::Queued);
+        assert_eq!(queue.push(3).await, PushOutcome::DroppedOldest);
+
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop().await, Some(2));
+        assert_eq!(queue.pop().await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_drop_new_keeps_queued_events() {
+        let queue = queue(OverflowPolicy::DropNew);
+        queue.push(1).await;
+        queue.push(2).await;
+        assert_eq!(queue.push(3).await, PushOutcome::DroppedNew);
+
+        assert_eq!(queue.pop().await, Some(1));
+        assert_eq!(queue.pop().await, Some(2));
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let queue = Arc::new(queue(OverflowPolicy::Block));
+        queue.push(1).await;
+        queue.push(2).await;
+
+        let pusher = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(3).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!pusher.is_finished(), "push should block while the queue is full");
+
+        assert_eq!(queue.pop().await, Some(1));
+        let outcome = tokio::time::timeout(Duration::from_secs(1), pusher).await.unwrap().unwrap();
+        assert_eq!(outcome, PushOutcome::Queued);
+        assert_eq!(queue.pop().await, Some(2));
+        assert_eq!(queue.pop().await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_closed_queue_drains_then_stops() {
+        let queue = Arc::new(queue(OverflowPolicy::Block));
+        queue.push(1).await;
+        queue.push(2).await;
+        let blocked = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(3).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        queue.close();
+        let outcome = tokio::time::timeout(Duration::from_secs(1), blocked).await.unwrap().unwrap();
+        assert_eq!(outcome, PushOutcome::Closed);
+        assert_eq!(queue.push(4).await, PushOutcome::Closed);
+        assert_eq!(queue.pop().await, Some(1));
+        assert_eq!(queue.pop().await, Some(2));
+        assert_eq!(queue.pop().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_close_wakes_waiting_consumer() {
+        let queue = Arc::new(queue(OverflowPolicy::DropOldest));
+        let consumer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        queue.close();
+        let popped = tokio::time::timeout(Duration::from_secs(1), consumer).await.unwrap().unwrap();
+        assert_eq!(popped, None);
+    }
+
+    #[test]
+    fn test_parse_overflow_policy() {
+        assert_eq!("drop_oldest".parse(), Ok(OverflowPolicy::DropOldest));
+        assert_eq!("DROP_NEW".parse(), Ok(OverflowPolicy::DropNew));
+        assert_eq!("block".parse(), Ok(OverflowPolicy::Block));
+        assert!("spill".parse::<OverflowPolicy>().is_err());
+    }
+}
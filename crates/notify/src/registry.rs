@@ -7,8 +7,20 @@ use hashbrown::{HashMap, HashSet};
 use nebulafx_config::{DEFAULT_DELIMITER, ENABLE_KEY, ENV_PREFIX, notify::NOTIFY_ROUTE_PREFIX};
 use nebulafx_ecstore::config::{Config, KVS};
 use nebulafx_targets::{Target, TargetError, target::ChannelTargetType};
+use serde::Serialize;
+use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// Outcome of probing a target definition without registering it
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetProbe {
+    pub target_type: String,
+    pub id: String,
+    pub reachable: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
 /// Registry for managing target factories
 pub struct TargetRegistry {
     factories: HashMap<String, Box<dyn TargetFactory>>,
@@ -58,6 +70,32 @@ impl TargetRegistry {
         factory.create_target(id, config).await
     }
 
+    /// Probes a target definition for connectivity without registering it or sending an event
+    ///
+    /// Returns an error when the target type is unknown or the definition is invalid;
+    /// an unreachable target is reported through [`TargetProbe::reachable`].
+    pub async fn probe_target(&self, target_type: &str, id: String, config: &KVS) -> Result<TargetProbe, TargetError> {
+        let factory = self
+            .factories
+            .get(target_type)
+            .ok_or_else(|| TargetError::Configuration(format!("Unknown target type: {target_type}")))?;
+
+        factory.validate_config(&id, config)?;
+
+        let start = Instant::now();
+        let result = factory.probe_target(id.clone(), config).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+        debug!(target_type, id = %id, latency_ms, ok = result.is_ok(), "Target probe finished");
+
+        Ok(TargetProbe {
+            target_type: target_type.to_string(),
+            id,
+            reachable: result.is_ok(),
+            latency_ms,
+            error: result.err().map(|e| e.to_string()),
+        })
+    }
+
     /// Creates all targets from a configuration
     /// Create all notification targets from system configuration and environment variables.
     /// This method processes the creation of each target concurrently as follows:
@@ -305,3 +343,53 @@ impl TargetRegistry {
         Ok(successful_targets)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_config::WEBHOOK_ENDPOINT;
+    use tokio::net::TcpListener;
+
+    fn webhook_config(endpoint: &str) -> KVS {
+        let mut config = KVS::new();
+        config.insert(WEBHOOK_ENDPOINT.to_string(), endpoint.to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn test_probe_reachable_webhook() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let registry = TargetRegistry::new();
+        let probe = registry
+            .probe_target("webhook", "probe".to_string(), &webhook_config(&format!("http://{addr}/hook")))
+            .await
+            .unwrap();
+
+        assert!(probe.reachable, "unexpected probe error: {:?}", probe.error);
+        assert!(probe.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_probe_unreachable_webhook() {
+        // Bind and release a port so nothing is listening on it
+        let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let registry = TargetRegistry::new();
+        let probe = registry
+            .probe_target("webhook", "probe".to_string(), &webhook_config(&format!("http://{addr}/hook")))
+            .await
+            .unwrap();
+
+        assert!(!probe.reachable);
+        assert!(probe.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_probe_rejects_unknown_type() {
+        let registry = TargetRegistry::new();
+        let result = registry.probe_target("kafka", "probe".to_string(), &KVS::new()).await;
+        assert!(matches!(result, Err(TargetError::Configuration(_))));
+    }
+}
@@ -1,6 +1,9 @@
 
 
+use nebulafx_tomlx::LogLevel;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
 
 // Default values for observability configuration
 pub const DEFAULT_APP_NAME: &str = "NebulaFX";
@@ -15,6 +18,10 @@ pub const DEFAULT_OBS_LOG_POOL_CAPA: usize = 10240;
 pub const DEFAULT_OBS_LOG_MESSAGE_CAPA: usize = 32768;
 pub const DEFAULT_OBS_LOG_FLUSH_MS: u64 = 200;
 
+// Default values for telemetry export
+pub const DEFAULT_OBS_METER_INTERVAL: Duration = Duration::from_secs(30);
+pub const DEFAULT_OBS_TRACE_BATCH_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Observability configuration
 ///
 /// This struct defines all configuration options for the observability system,
@@ -32,7 +39,7 @@ pub struct ObservabilityConfig {
     pub service_name: Option<String>,     // Service name
     pub service_version: Option<String>,  // Service version
     pub environment: Option<String>,      // Environment (production/development/test/staging)
-    pub logger_level: Option<String>,     // Logger level (trace/debug/info/warn/error)
+    pub logger_level: Option<LogLevel>,   // Logger level (trace/debug/info/warn/error)
     pub log_stdout_enabled: Option<bool>, // Stdout logging enabled
     // File logging configurations
     pub log_directory: Option<String>,     // Log file directory
@@ -41,11 +48,44 @@ pub struct ObservabilityConfig {
     pub log_rotation_time: Option<String>, // Log rotation time (hour/day/minute/second)
     pub log_keep_files: Option<u32>,       // Number of log files to keep
     // Async logging configurations
-    pub log_pool_capa: Option<usize>,      // Log pool capacity for async logging
-    pub log_message_capa: Option<usize>,   // Maximum message capacity for async logging
-    pub log_flush_ms: Option<u64>,         // Log flush interval in milliseconds
+    pub log_pool_capa: Option<usize>,    // Log pool capacity for async logging
+    pub log_message_capa: Option<usize>, // Maximum message capacity for async logging
+    pub log_flush_ms: Option<u64>,       // Log flush interval in milliseconds
     // Output format configurations
-    pub log_json: Option<bool>,            // Whether to use JSON format for log output
+    pub log_json: Option<bool>,        // Whether to use JSON format for log output
+    pub log_format: Option<LogFormat>, // Stdout log format (pretty/compact/json), overrides log_json
+    // Telemetry export configurations
+    pub meter_interval: Option<String>,      // How often metrics are exported, e.g. "30s"
+    pub trace_batch_timeout: Option<String>, // Longest a span batch waits before export, e.g. "5s"
+}
+
+/// Formatting of stdout logs
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Multi-line, human-readable output
+    Pretty,
+    /// Single-line text output
+    #[default]
+    Compact,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Compact => "compact",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl ObservabilityConfig {
@@ -77,6 +117,35 @@ impl ObservabilityConfig {
             log_message_capa: None,
             log_flush_ms: None,
             log_json: None,
+            log_format: None,
+            meter_interval: None,
+            trace_batch_timeout: None,
+        }
+    }
+
+    /// Interval between metric exports, `DEFAULT_OBS_METER_INTERVAL` when unset
+    pub fn meter_interval(&self) -> Result<Duration, String> {
+        parse_interval("meter_interval", self.meter_interval.as_deref(), DEFAULT_OBS_METER_INTERVAL)
+    }
+
+    /// Maximum delay before a batch of spans is exported, `DEFAULT_OBS_TRACE_BATCH_TIMEOUT` when unset
+    pub fn trace_batch_timeout(&self) -> Result<Duration, String> {
+        parse_interval("trace_batch_timeout", self.trace_batch_timeout.as_deref(), DEFAULT_OBS_TRACE_BATCH_TIMEOUT)
+    }
+
+    /// Check the export intervals so bad values fail at load rather than at export time
+    pub fn validate(&self) -> Result<(), String> {
+        self.meter_interval()?;
+        self.trace_batch_timeout()?;
+        Ok(())
+    }
+
+    /// The stdout log format: `log_format` if set, otherwise JSON when `log_json` is true, else compact
+    pub fn effective_log_format(&self) -> LogFormat {
+        match (self.log_format, self.log_json) {
+            (Some(format), _) => format,
+            (None, Some(true)) => LogFormat::Json,
+            (None, _) => LogFormat::default(),
         }
     }
 }
@@ -98,6 +167,18 @@ impl Default for ObservabilityConfig {
     }
 }
 
+/// Parse a human-readable, non-zero interval such as `"30s"` or `"1m"`
+fn parse_interval(key: &str, value: Option<&str>, default: Duration) -> Result<Duration, String> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    let interval = humantime::parse_duration(value).map_err(|e| format!("invalid observability.{key} '{value}': {e}"))?;
+    if interval.is_zero() {
+        return Err(format!("observability.{key} must be greater than zero"));
+    }
+    Ok(interval)
+}
+
 /// Check if the given environment string is production
 ///
 /// # Arguments
@@ -109,5 +190,65 @@ pub fn is_production_environment(environment: &str) -> bool {
     environment.eq_ignore_ascii_case(DEFAULT_ENVIRONMENT_PRODUCTION)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_tomlx::load_config_from_str;
+
+    #[test]
+    fn test_log_format_values_are_accepted() {
+        for (value, expected) in [
+            ("pretty", LogFormat::Pretty),
+            ("compact", LogFormat::Compact),
+            ("json", LogFormat::Json),
+        ] {
+            let config: ObservabilityConfig = load_config_from_str(&format!("log_format = \"{value}\"")).unwrap();
+            assert_eq!(config.log_format, Some(expected));
+            assert_eq!(config.effective_log_format(), expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_log_format_is_rejected_at_load() {
+        assert!(load_config_from_str::<ObservabilityConfig>("log_format = \"yaml\"").is_err());
+    }
+
+    #[test]
+    fn test_export_intervals_accept_durations() {
+        let toml = "meter_interval = \"1m\"\ntrace_batch_timeout = \"500ms\"";
+        let config: ObservabilityConfig = load_config_from_str(toml).unwrap();
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.meter_interval().unwrap(), Duration::from_secs(60));
+        assert_eq!(config.trace_batch_timeout().unwrap(), Duration::from_millis(500));
 
+        let config = ObservabilityConfig::new();
+        assert_eq!(config.meter_interval().unwrap(), DEFAULT_OBS_METER_INTERVAL);
+        assert_eq!(config.trace_batch_timeout().unwrap(), DEFAULT_OBS_TRACE_BATCH_TIMEOUT);
+    }
+
+    #[test]
+    fn test_invalid_export_intervals_are_rejected() {
+        let mut config = ObservabilityConfig::new();
+        config.meter_interval = Some("0s".to_string());
+        assert_eq!(config.validate().unwrap_err(), "observability.meter_interval must be greater than zero");
+
+        config.meter_interval = Some("30".to_string());
+        assert!(config.validate().unwrap_err().contains("invalid observability.meter_interval '30'"));
+
+        config.meter_interval = None;
+        config.trace_batch_timeout = Some("soon".to_string());
+        assert!(config.validate().unwrap_err().contains("observability.trace_batch_timeout"));
+    }
 
+    #[test]
+    fn test_log_format_defaults_follow_log_json() {
+        let mut config = ObservabilityConfig::new();
+        assert_eq!(config.effective_log_format(), LogFormat::Compact);
+
+        config.log_json = Some(true);
+        assert_eq!(config.effective_log_format(), LogFormat::Json);
+
+        config.log_format = Some(LogFormat::Pretty);
+        assert_eq!(config.effective_log_format(), LogFormat::Pretty);
+    }
+}
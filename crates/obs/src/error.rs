@@ -17,6 +17,8 @@ pub enum GlobalError {
     Timeout(&'static str),
     #[error("Telemetry initialization failed: {0}")]
     TelemetryError(#[from] TelemetryError),
+    #[error("Metrics recorder already installed")]
+    MetricsRecorder,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -27,6 +29,8 @@ pub enum TelemetryError {
     Io(String),
     #[error("Set permissions failed: {0}")]
     SetPermissions(String),
+    #[error("Invalid observability config: {0}")]
+    InvalidConfig(String),
 }
 
 impl From<std::io::Error> for TelemetryError {
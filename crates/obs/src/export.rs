@@ -0,0 +1,101 @@
+use crate::{ObservabilityConfig, TelemetryError};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider, exporter::PushMetricExporter};
+use opentelemetry_sdk::trace::{BatchConfigBuilder, BatchSpanProcessor, SdkTracerProvider, SpanExporter};
+
+/// Meter provider that pushes to `exporter` every `observability.meter_interval`
+pub fn build_meter_provider<E>(config: &ObservabilityConfig, exporter: E) -> Result<SdkMeterProvider, TelemetryError>
+where
+    E: PushMetricExporter,
+{
+    let interval = config.meter_interval().map_err(TelemetryError::InvalidConfig)?;
+    let reader = PeriodicReader::builder(exporter).with_interval(interval).build();
+    Ok(SdkMeterProvider::builder().with_reader(reader).build())
+}
+
+/// Tracer provider that sends spans to `exporter` in batches at most `observability.trace_batch_timeout` apart
+pub fn build_tracer_provider<E>(config: &ObservabilityConfig, exporter: E) -> Result<SdkTracerProvider, TelemetryError>
+where
+    E: SpanExporter + 'static,
+{
+    let timeout = config.trace_batch_timeout().map_err(TelemetryError::InvalidConfig)?;
+    let batch_config = BatchConfigBuilder::default().with_scheduled_delay(timeout).build();
+    let processor = BatchSpanProcessor::builder(exporter).with_batch_config(batch_config).build();
+    Ok(SdkTracerProvider::builder().with_span_processor(processor).build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry::trace::{Tracer, TracerProvider};
+    use opentelemetry_sdk::metrics::InMemoryMetricExporter;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use std::time::{Duration, Instant};
+
+    fn config(meter_interval: &str, trace_batch_timeout: &str) -> ObservabilityConfig {
+        ObservabilityConfig {
+            meter_interval: Some(meter_interval.to_string()),
+            trace_batch_timeout: Some(trace_batch_timeout.to_string()),
+            ..ObservabilityConfig::new()
+        }
+    }
+
+    /// Wait up to `limit` for `exported` to hold
+    fn exported_within(limit: Duration, exported: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + limit;
+        while Instant::now() < deadline {
+            if exported() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        exported()
+    }
+
+    #[test]
+    fn test_meter_interval_sets_the_export_cadence() {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = build_meter_provider(&config("100ms", "5s"), exporter.clone()).unwrap();
+        provider.meter("test").u64_counter("requests").build().add(1, &[]);
+        assert!(exported_within(Duration::from_secs(5), || !exporter
+            .get_finished_metrics()
+            .unwrap()
+            .is_empty()));
+
+        // Nothing is pushed before the interval elapses
+        let exporter = InMemoryMetricExporter::default();
+        let provider = build_meter_provider(&config("1h", "5s"), exporter.clone()).unwrap();
+        provider.meter("test").u64_counter("requests").build().add(1, &[]);
+        assert!(!exported_within(Duration::from_millis(300), || !exporter
+            .get_finished_metrics()
+            .unwrap()
+            .is_empty()));
+        drop(provider);
+    }
+
+    #[test]
+    fn test_trace_batch_timeout_sets_the_batch_delay() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = build_tracer_provider(&config("30s", "100ms"), exporter.clone()).unwrap();
+        provider.tracer("test").in_span("request", |_| {});
+        assert!(exported_within(Duration::from_secs(5), || exporter.get_finished_spans().unwrap().len() == 1));
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = build_tracer_provider(&config("30s", "1h"), exporter.clone()).unwrap();
+        provider.tracer("test").in_span("request", |_| {});
+        assert!(!exported_within(Duration::from_millis(300), || !exporter
+            .get_finished_spans()
+            .unwrap()
+            .is_empty()));
+        drop(provider);
+    }
+
+    #[test]
+    fn test_invalid_interval_builds_nothing() {
+        let err = build_meter_provider(&config("0s", "5s"), InMemoryMetricExporter::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid observability config: observability.meter_interval must be greater than zero"
+        );
+    }
+}
@@ -1,6 +1,6 @@
-
-
-use crate::{GlobalError, ObservabilityConfig, LoggingGuard, telemetry::init_telemetry};
+use crate::{
+    GlobalError, LoggingGuard, ObservabilityConfig, TelemetryError, install_metrics_registry, telemetry::init_telemetry,
+};
 use std::fmt;
 use std::sync::{Arc, Mutex};
 use tokio::sync::OnceCell;
@@ -18,7 +18,8 @@ impl fmt::Display for Success {
 /// Initialize the observability module
 ///
 /// This function initializes the logging system and stores the guard globally,
-/// similar to how `init_config` works. The guard is automatically stored in
+/// similar to how `init_config` works. It also installs the in-process metrics
+/// registry served by the console metrics endpoints. The guard is automatically stored in
 /// the global static variable for later access via `get_global_guard()`.
 ///
 /// # Arguments
@@ -42,9 +43,11 @@ impl fmt::Display for Success {
 /// ```
 pub fn init_obs(config: Option<&ObservabilityConfig>) -> Result<Success, GlobalError> {
     let config = config.cloned().unwrap_or_default();
+    config.validate().map_err(TelemetryError::InvalidConfig)?;
     let logging_guard = init_telemetry(&config)?;
     // Store in global storage automatically
     GLOBAL_GUARD.set(Arc::new(Mutex::new(logging_guard))).map_err(GlobalError::SetError)?;
+    install_metrics_registry().map_err(|_| GlobalError::MetricsRecorder)?;
     Ok(Success)
 }
 
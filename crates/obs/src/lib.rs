@@ -37,10 +37,17 @@
 /// ```
 mod config;
 mod error;
+mod export;
 mod global;
+mod registry;
 mod telemetry;
 
-pub use config::{ObservabilityConfig, is_production_environment};
+pub use config::{LogFormat, ObservabilityConfig, is_production_environment};
 pub use error::*;
+pub use export::{build_meter_provider, build_tracer_provider};
 pub use global::*;
+pub use registry::{
+    MetricFamily, MetricKind, MetricSample, MetricsRegistry, MetricsSnapshot, install_metrics_registry, metrics_registry,
+    render_prometheus,
+};
 pub use telemetry::LoggingGuard;
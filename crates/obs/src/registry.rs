@@ -0,0 +1,314 @@
+use metrics::{Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SetRecorderError, SharedString, Unit};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+static GLOBAL_REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+
+/// Kind of a metric family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    /// Histograms are exported as a count and sum, without buckets
+    Summary,
+}
+
+impl MetricKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Summary => "summary",
+        }
+    }
+}
+
+/// One labelled value of a metric family
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSample {
+    pub labels: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sum: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+/// All samples recorded under one metric name
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricFamily {
+    #[serde(rename = "type")]
+    pub kind: MetricKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    pub samples: Vec<MetricSample>,
+}
+
+/// Point-in-time copy of every metric, keyed by exported metric name
+pub type MetricsSnapshot = BTreeMap<String, MetricFamily>;
+
+#[derive(Default)]
+struct Summary {
+    count: AtomicU64,
+    sum: Mutex<f64>,
+}
+
+impl HistogramFn for Summary {
+    fn record(&self, value: f64) {
+        *self.sum.lock().unwrap() += value;
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    counters: RwLock<HashMap<Key, Arc<AtomicU64>>>,
+    gauges: RwLock<HashMap<Key, Arc<AtomicU64>>>,
+    summaries: RwLock<HashMap<Key, Arc<Summary>>>,
+    descriptions: RwLock<HashMap<String, String>>,
+}
+
+/// In-process recorder backing both the Prometheus text and JSON metrics exports
+///
+/// Clones share the same storage, so every export reads from one source of truth.
+#[derive(Clone, Default)]
+pub struct MetricsRegistry {
+    inner: Arc<Inner>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy the current value of every registered metric
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let descriptions = self.inner.descriptions.read().unwrap();
+        let mut snapshot = MetricsSnapshot::new();
+        let mut family = |key: &Key, kind: MetricKind, sample: MetricSample| {
+            snapshot
+                .entry(export_name(key.name()))
+                .or_insert_with(|| MetricFamily {
+                    kind,
+                    help: descriptions.get(key.name()).cloned(),
+                    samples: Vec::new(),
+                })
+                .samples
+                .push(sample);
+        };
+
+        for (key, counter) in self.inner.counters.read().unwrap().iter() {
+            let value = counter.load(Ordering::Relaxed) as f64;
+            family(key, MetricKind::Counter, MetricSample::value(key, value));
+        }
+        for (key, gauge) in self.inner.gauges.read().unwrap().iter() {
+            let value = f64::from_bits(gauge.load(Ordering::Relaxed));
+            family(key, MetricKind::Gauge, MetricSample::value(key, value));
+        }
+        for (key, summary) in self.inner.summaries.read().unwrap().iter() {
+            let sample = MetricSample {
+                labels: labels(key),
+                value: None,
+                sum: Some(*summary.sum.lock().unwrap()),
+                count: Some(summary.count.load(Ordering::Relaxed)),
+            };
+            family(key, MetricKind::Summary, sample);
+        }
+
+        for family in snapshot.values_mut() {
+            family.samples.sort_by(|a, b| a.labels.cmp(&b.labels));
+        }
+        snapshot
+    }
+
+    /// Render the current snapshot in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        render_prometheus(&self.snapshot())
+    }
+
+    fn describe(&self, key: KeyName, description: SharedString) {
+        self.inner
+            .descriptions
+            .write()
+            .unwrap()
+            .insert(key.as_str().to_string(), description.to_string());
+    }
+}
+
+impl MetricSample {
+    fn value(key: &Key, value: f64) -> Self {
+        Self {
+            labels: labels(key),
+            value: Some(value),
+            sum: None,
+            count: None,
+        }
+    }
+}
+
+impl Recorder for MetricsRegistry {
+    fn describe_counter(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+        self.describe(key, description);
+    }
+
+    fn describe_gauge(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+        self.describe(key, description);
+    }
+
+    fn describe_histogram(&self, key: KeyName, _: Option<Unit>, description: SharedString) {
+        self.describe(key, description);
+    }
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(get_or_insert(&self.inner.counters, key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(get_or_insert(&self.inner.gauges, key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(get_or_insert(&self.inner.summaries, key))
+    }
+}
+
+fn get_or_insert<T: Default>(map: &RwLock<HashMap<Key, Arc<T>>>, key: &Key) -> Arc<T> {
+    if let Some(existing) = map.read().unwrap().get(key) {
+        return existing.clone();
+    }
+    map.write().unwrap().entry(key.clone()).or_default().clone()
+}
+
+fn labels(key: &Key) -> BTreeMap<String, String> {
+    key.labels().map(|l| (export_name(l.key()), l.value().to_string())).collect()
+}
+
+/// Map a metric or label name onto the Prometheus charset, e.g. `request.latency.ms` -> `request_latency_ms`
+fn export_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Render a snapshot in the Prometheus text exposition format
+pub fn render_prometheus(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    for (name, family) in snapshot {
+        if let Some(help) = &family.help {
+            let _ = writeln!(out, "# HELP {name} {}", help.replace('\\', "\\\\").replace('\n', "\\n"));
+        }
+        let _ = writeln!(out, "# TYPE {name} {}", family.kind.as_str());
+        for sample in &family.samples {
+            let labels = render_labels(&sample.labels);
+            if let Some(value) = sample.value {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+            if let Some(sum) = sample.sum {
+                let _ = writeln!(out, "{name}_sum{labels} {sum}");
+            }
+            if let Some(count) = sample.count {
+                let _ = writeln!(out, "{name}_count{labels} {count}");
+            }
+        }
+    }
+    out
+}
+
+fn render_labels(labels: &BTreeMap<String, String>) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| {
+            let v = v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{k}=\"{v}\"")
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+/// The process-wide registry; metrics are only recorded into it once installed
+pub fn metrics_registry() -> &'static MetricsRegistry {
+    GLOBAL_REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Install the process-wide registry as the global `metrics` recorder
+pub fn install_metrics_registry() -> Result<(), SetRecorderError<MetricsRegistry>> {
+    metrics::set_global_recorder(metrics_registry().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{counter, describe_counter, gauge, histogram};
+
+    fn record_fixture(registry: &MetricsRegistry) {
+        metrics::with_local_recorder(registry, || {
+            describe_counter!("nebulafx_api_requests_total", "Total S3 API requests");
+            counter!("nebulafx_api_requests_total", "method" => "GET").increment(3);
+            counter!("nebulafx_api_requests_total", "method" => "PUT").increment(1);
+            gauge!("nebulafx_db_pool_connections", "pool" => "default").set(4.0);
+            histogram!("request.latency.ms").record(10.0);
+            histogram!("request.latency.ms").record(30.0);
+        });
+    }
+
+    /// Parse `name{labels} value` lines of the text format
+    fn text_sample(text: &str, series: &str) -> f64 {
+        text.lines()
+            .find_map(|line| line.strip_prefix(series)?.strip_prefix(' ')?.parse().ok())
+            .unwrap_or_else(|| panic!("series {series} missing from:\n{text}"))
+    }
+
+    #[test]
+    fn test_counter_matches_in_text_and_json() {
+        let registry = MetricsRegistry::new();
+        record_fixture(&registry);
+
+        let text = registry.render_prometheus();
+        let json = serde_json::to_value(registry.snapshot()).unwrap();
+
+        let family = &json["nebulafx_api_requests_total"];
+        assert_eq!(family["type"], "counter");
+        assert_eq!(family["help"], "Total S3 API requests");
+        let get = family["samples"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["labels"]["method"] == "GET")
+            .unwrap();
+        assert_eq!(get["value"], 3.0);
+        assert_eq!(text_sample(&text, "nebulafx_api_requests_total{method=\"GET\"}"), 3.0);
+        assert!(text.contains("# TYPE nebulafx_api_requests_total counter"));
+    }
+
+    #[test]
+    fn test_gauges_and_histograms_are_exported() {
+        let registry = MetricsRegistry::new();
+        record_fixture(&registry);
+
+        let snapshot = registry.snapshot();
+        let text = render_prometheus(&snapshot);
+
+        assert_eq!(snapshot["nebulafx_db_pool_connections"].samples[0].value, Some(4.0));
+        assert_eq!(text_sample(&text, "nebulafx_db_pool_connections{pool=\"default\"}"), 4.0);
+
+        let latency = &snapshot["request_latency_ms"];
+        assert_eq!(latency.kind, MetricKind::Summary);
+        assert_eq!(latency.samples[0].count, Some(2));
+        assert_eq!(text_sample(&text, "request_latency_ms_sum"), 40.0);
+        assert_eq!(text_sample(&text, "request_latency_ms_count"), 2.0);
+    }
+}
@@ -1,12 +1,9 @@
-
-
+use crate::TelemetryError;
 use crate::config::{
+    DEFAULT_APP_NAME, DEFAULT_ENVIRONMENT, DEFAULT_ENVIRONMENT_PRODUCTION, DEFAULT_LOG_KEEP_FILES, DEFAULT_LOG_LEVEL,
+    DEFAULT_OBS_LOG_FLUSH_MS, DEFAULT_OBS_LOG_MESSAGE_CAPA, DEFAULT_OBS_LOG_POOL_CAPA, DEFAULT_OBS_LOG_STDOUT_ENABLED, LogFormat,
     ObservabilityConfig,
-    DEFAULT_APP_NAME, DEFAULT_ENVIRONMENT, DEFAULT_ENVIRONMENT_PRODUCTION, DEFAULT_LOG_KEEP_FILES,
-    DEFAULT_LOG_LEVEL, DEFAULT_OBS_LOG_FLUSH_MS, DEFAULT_OBS_LOG_MESSAGE_CAPA, DEFAULT_OBS_LOG_POOL_CAPA,
-    DEFAULT_OBS_LOG_STDOUT_ENABLED,
 };
-use crate::TelemetryError;
 use flexi_logger::{DeferredNow, Record, WriteMode, WriteMode::AsyncWith, style};
 use metrics::counter;
 use nu_ansi_term::Color;
@@ -134,58 +131,80 @@ fn init_stdout_logging(config: &ObservabilityConfig, logger_level: &str, is_prod
     let env_filter = build_env_filter(logger_level, None);
     let (nb, guard) = tracing_appender::non_blocking(std::io::stdout());
     let enable_color = std::io::stdout().is_terminal();
-    
-    // Check if JSON format is enabled from config
-    let use_json = config.log_json.unwrap_or(false);
-    
+
+    let log_format = config.effective_log_format();
+
     let span_event = if is_production { FmtSpan::CLOSE } else { FmtSpan::FULL };
-    
+
     // 根据配置选择格式（必须在创建时就决定，不能后续修改）
-    if use_json {
-        // JSON 格式（用于生产环境或日志收集系统）
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_timer(LocalTime::rfc_3339())
-            .with_target(true)
-            .with_ansi(enable_color)
-            .with_writer(nb)
-            .json()
-            .with_current_span(true)
-            .with_span_list(true)
-            .with_thread_names(true)
-            .with_thread_ids(true)
-            .with_file(true)
-            .with_line_number(true)
-            .with_span_events(span_event);
-        
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(ErrorLayer::default())
-            .with(fmt_layer)
-            .init();
-    } else {
-        // 友好的文本格式（用于开发环境）
-        // 格式: [时间] [级别] [模块] 消息
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_timer(LocalTime::rfc_3339())
-            .with_target(true)
-            .with_ansi(enable_color)
-            .with_writer(nb)
-            .compact() // 使用紧凑格式，更易读
-            .with_thread_names(false) // 开发环境不需要线程名
-            .with_thread_ids(false)   // 开发环境不需要线程ID
-            .with_file(false)         // 开发环境不需要文件名
-            .with_line_number(false)  // 开发环境不需要行号
-            .with_span_events(span_event);
-        
-        tracing_subscriber::registry()
-            .with(env_filter)
-            .with(ErrorLayer::default())
-            .with(fmt_layer)
-            .init();
+    match log_format {
+        LogFormat::Json => {
+            // JSON 格式（用于生产环境或日志收集系统）
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_timer(LocalTime::rfc_3339())
+                .with_target(true)
+                .with_ansi(enable_color)
+                .with_writer(nb)
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_thread_names(true)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_span_events(span_event);
+
+            tracing_subscriber::registry()
+                .with(env_filter)
+                .with(ErrorLayer::default())
+                .with(fmt_layer)
+                .init();
+        }
+        LogFormat::Pretty => {
+            // Multi-line output with source locations, for reading locally
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_timer(LocalTime::rfc_3339())
+                .with_target(true)
+                .with_ansi(enable_color)
+                .with_writer(nb)
+                .pretty()
+                .with_thread_names(false)
+                .with_thread_ids(false)
+                .with_file(true)
+                .with_line_number(true)
+                .with_span_events(span_event);
+
+            tracing_subscriber::registry()
+                .with(env_filter)
+                .with(ErrorLayer::default())
+                .with(fmt_layer)
+                .init();
+        }
+        LogFormat::Compact => {
+            // 友好的文本格式（用于开发环境）
+            // 格式: [时间] [级别] [模块] 消息
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_timer(LocalTime::rfc_3339())
+                .with_target(true)
+                .with_ansi(enable_color)
+                .with_writer(nb)
+                .compact() // 使用紧凑格式，更易读
+                .with_thread_names(false) // 开发环境不需要线程名
+                .with_thread_ids(false) // 开发环境不需要线程ID
+                .with_file(false) // 开发环境不需要文件名
+                .with_line_number(false) // 开发环境不需要行号
+                .with_span_events(span_event);
+
+            tracing_subscriber::registry()
+                .with(env_filter)
+                .with(ErrorLayer::default())
+                .with(fmt_layer)
+                .init();
+        }
     }
 
     counter!("nebulafx.start.total").increment(1);
-    info!("Init stdout logging (level: {}, format: {})", logger_level, if use_json { "json" } else { "text" });
+    info!("Init stdout logging (level: {}, format: {})", logger_level, log_format);
     LoggingGuard {
         flexi_logger_handles: None,
         tracing_guard: Some(guard),
@@ -329,7 +348,7 @@ fn init_file_logging(config: &ObservabilityConfig, logger_level: &str, is_produc
 pub(crate) fn init_telemetry(config: &ObservabilityConfig) -> Result<LoggingGuard, TelemetryError> {
     let environment = config.environment.as_deref().unwrap_or(DEFAULT_ENVIRONMENT);
     let is_production = environment.eq_ignore_ascii_case(DEFAULT_ENVIRONMENT_PRODUCTION);
-    let logger_level = config.logger_level.as_deref().unwrap_or(DEFAULT_LOG_LEVEL);
+    let logger_level = config.logger_level.map(|l| l.as_str()).unwrap_or(DEFAULT_LOG_LEVEL);
 
     // Rule 1: If log directory is set in config, use file logging
     if config.log_directory.is_some() {
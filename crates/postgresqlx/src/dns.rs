@@ -0,0 +1,156 @@
+use futures::future::BoxFuture;
+use sqlx::PgPool;
+use sqlx::postgres::{PgConnectOptions, PgSslMode};
+use std::io;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{info, warn};
+
+/// Looks up the addresses of the database host
+pub(crate) trait HostResolver: Send + Sync {
+    fn resolve<'a>(&'a self, host: &'a str, port: u16) -> BoxFuture<'a, io::Result<Vec<IpAddr>>>;
+}
+
+/// Resolver backed by the system resolver
+pub(crate) struct SystemResolver;
+
+impl HostResolver for SystemResolver {
+    fn resolve<'a>(&'a self, host: &'a str, port: u16) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+        Box::pin(async move { Ok(tokio::net::lookup_host((host, port)).await?.map(|addr| addr.ip()).collect()) })
+    }
+}
+
+/// Re-resolves the database host so new connections follow DNS-based failover
+///
+/// The pool dials the pinned address; every new connection (and every failed acquisition)
+/// looks the host up again and re-points the pool when the address changed.
+pub(crate) struct DnsRefresh {
+    pool_name: String,
+    host: String,
+    port: u16,
+    base: PgConnectOptions,
+    resolver: Arc<dyn HostResolver>,
+    current: Mutex<Option<IpAddr>>,
+    pool: OnceLock<PgPool>,
+}
+
+impl DnsRefresh {
+    pub(crate) fn new(pool_name: &str, base: PgConnectOptions, resolver: Arc<dyn HostResolver>) -> Self {
+        Self {
+            pool_name: pool_name.to_string(),
+            host: base.get_host().to_string(),
+            port: base.get_port(),
+            base,
+            resolver,
+            current: Mutex::new(None),
+            pool: OnceLock::new(),
+        }
+    }
+
+    /// Whether connections made with `options` can be pinned to a resolved address
+    ///
+    /// Unix sockets have no host to resolve. sqlx checks TLS certificates and sends SNI for the
+    /// host it dials and has no separate `hostaddr`, so any pool that may negotiate TLS keeps
+    /// dialing the hostname, which the system resolver looks up for every connection anyway.
+    pub(crate) fn can_pin(options: &PgConnectOptions) -> bool {
+        let is_socket = options.get_socket().is_some() || options.get_host().starts_with('/');
+        !is_socket && matches!(options.get_ssl_mode(), PgSslMode::Disable)
+    }
+
+    /// Resolve the host and return connect options pinned to its first address
+    pub(crate) async fn resolve_options(&self) -> io::Result<PgConnectOptions> {
+        let addrs = self.resolver.resolve(&self.host, self.port).await?;
+        let addr = addrs
+            .first()
+            .copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no addresses found for {}", self.host)))?;
+
+        let previous = self.current.lock().unwrap().replace(addr);
+        if let Some(previous) = previous.filter(|previous| *previous != addr) {
+            info!(
+                pool = %self.pool_name,
+                host = %self.host,
+                "Database host moved from {} to {}, new connections will use the new address",
+                previous,
+                addr
+            );
+        }
+        Ok(self.base.clone().host(&addr.to_string()))
+    }
+
+    /// Give the refresher the pool whose connect options it updates
+    pub(crate) fn attach(&self, pool: PgPool) {
+        let _ = self.pool.set(pool);
+    }
+
+    /// Re-resolve the host and point the pool's future connections at the result
+    pub(crate) async fn refresh(&self) {
+        match self.resolve_options().await {
+            Ok(options) => {
+                if let Some(pool) = self.pool.get() {
+                    pool.set_connect_options(options);
+                }
+            }
+            Err(e) => warn!(pool = %self.pool_name, host = %self.host, "Failed to re-resolve database host: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Returns the next address on every lookup
+    struct StubResolver {
+        addrs: Vec<IpAddr>,
+        lookups: AtomicUsize,
+    }
+
+    impl HostResolver for StubResolver {
+        fn resolve<'a>(&'a self, _host: &'a str, _port: u16) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+            let n = self.lookups.fetch_add(1, Ordering::SeqCst);
+            let addr = self.addrs[n.min(self.addrs.len() - 1)];
+            Box::pin(async move { Ok(vec![addr]) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_host_is_re_resolved_per_connection() {
+        let resolver = Arc::new(StubResolver {
+            addrs: vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            lookups: AtomicUsize::new(0),
+        });
+        let base = PgConnectOptions::new().host("db.internal").port(5433);
+        let refresh = DnsRefresh::new("default", base, resolver.clone());
+
+        let first = refresh.resolve_options().await.unwrap();
+        assert_eq!(first.get_host(), "10.0.0.1");
+        assert_eq!(first.get_port(), 5433);
+
+        // What the pool's after_connect hook runs for each new connection: another lookup,
+        // whose failover address the pool dials from then on
+        let pool = PgPoolOptions::new().connect_lazy_with(first);
+        refresh.attach(pool.clone());
+        refresh.refresh().await;
+        refresh.refresh().await;
+        assert_eq!(resolver.lookups.load(Ordering::SeqCst), 3);
+        assert_eq!(*refresh.current.lock().unwrap(), Some("10.0.0.2".parse().unwrap()));
+        assert_eq!(pool.connect_options().get_host(), "10.0.0.2");
+        assert_eq!(pool.connect_options().get_port(), 5433);
+    }
+
+    #[test]
+    fn test_only_plain_tcp_hosts_are_pinned() {
+        let tcp = PgConnectOptions::new().host("db.internal");
+        assert!(DnsRefresh::can_pin(&tcp.clone().ssl_mode(PgSslMode::Disable)));
+        for mode in [PgSslMode::Prefer, PgSslMode::Require, PgSslMode::VerifyFull] {
+            assert!(!DnsRefresh::can_pin(&tcp.clone().ssl_mode(mode)), "{mode:?}");
+        }
+
+        let socket = PgConnectOptions::new().ssl_mode(PgSslMode::Disable);
+        assert!(!DnsRefresh::can_pin(&socket.clone().socket("/var/run/postgresql")));
+        assert!(!DnsRefresh::can_pin(&socket.host("/tmp")));
+    }
+}
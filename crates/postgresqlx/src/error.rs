@@ -13,6 +13,12 @@ pub enum PostgreSQLError {
     
     #[error("Pool error: {0}")]
     PoolError(String),
+
+    #[error("Transaction failed: {0}")]
+    TransactionError(String),
+
+    #[error("Migration failed: {0}")]
+    MigrationFailed(String),
 }
 
 pub type Result<T> = std::result::Result<T, PostgreSQLError>;
@@ -0,0 +1,347 @@
+use crate::pool_metrics::record_listener_reconnect;
+use crate::{PostgreSQLError, PostgreSQLListenerConfig, PostgreSQLPool};
+use futures::Stream;
+use futures::future::BoxFuture;
+use sqlx::postgres::PgListener;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Backoff applied between failed attempts to re-establish a `LISTEN` connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectBackoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            initial: DEFAULT_INITIAL_BACKOFF,
+            max: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    /// Resolve the backoff from `[database.listener]`, falling back to the defaults
+    pub fn from_config(config: Option<&PostgreSQLListenerConfig>) -> Self {
+        let parse =
+            |value: Option<&String>, default: Duration| value.and_then(|s| humantime::parse_duration(s).ok()).unwrap_or(default);
+
+        Self {
+            initial: parse(config.and_then(|c| c.reconnect_initial_backoff.as_ref()), DEFAULT_INITIAL_BACKOFF),
+            max: parse(config.and_then(|c| c.reconnect_max_backoff.as_ref()), DEFAULT_MAX_BACKOFF),
+        }
+    }
+
+    /// Delay before the given reconnect attempt (1-based), doubling up to `max`
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .saturating_mul(1u32 << attempt.saturating_sub(1).min(16))
+            .min(self.max)
+    }
+}
+
+/// A notification received on one of the subscribed channels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenerNotification {
+    pub channel: String,
+    pub payload: String,
+}
+
+/// A single `LISTEN` connection driven by the supervisor
+pub(crate) trait ListenConnection: Send {
+    /// Subscribe to every channel
+    fn listen_all<'a>(&'a mut self, channels: &'a [String]) -> BoxFuture<'a, Result<(), sqlx::Error>>;
+
+    /// Wait for the next notification; `Ok(None)` means the connection was lost
+    fn next(&mut self) -> BoxFuture<'_, Result<Option<ListenerNotification>, sqlx::Error>>;
+}
+
+impl ListenConnection for PgListener {
+    fn listen_all<'a>(&'a mut self, channels: &'a [String]) -> BoxFuture<'a, Result<(), sqlx::Error>> {
+        Box::pin(PgListener::listen_all(self, channels.iter().map(String::as_str)))
+    }
+
+    fn next(&mut self) -> BoxFuture<'_, Result<Option<ListenerNotification>, sqlx::Error>> {
+        Box::pin(async move {
+            Ok(self.try_recv().await?.map(|n| ListenerNotification {
+                channel: n.channel().to_string(),
+                payload: n.payload().to_string(),
+            }))
+        })
+    }
+}
+
+/// Spawn a supervised `LISTEN` on `channels` that survives database restarts
+///
+/// Each notification is passed to `on_notification`. When the connection drops, the
+/// supervisor reconnects with `backoff`, re-subscribes to all channels and then calls
+/// `on_reload`, since notifications sent during the gap are lost and state must be
+/// rebuilt from scratch. The task runs until it is aborted.
+pub fn spawn_listener<N, R>(
+    pool: &PostgreSQLPool,
+    channels: Vec<String>,
+    backoff: ReconnectBackoff,
+    on_notification: N,
+    on_reload: R,
+) -> JoinHandle<()>
+where
+    N: FnMut(ListenerNotification) + Send + 'static,
+    R: FnMut() + Send + 'static,
+{
+    let pool_name = pool.name().to_string();
+    let pg_pool = pool.inner().clone();
+    let connect = move || {
+        let pg_pool = pg_pool.clone();
+        Box::pin(async move { PgListener::connect_with(&pg_pool).await }) as BoxFuture<'static, _>
+    };
+
+    tokio::spawn(async move { supervise(&pool_name, &channels, backoff, connect, on_notification, on_reload).await })
+}
+
+/// Payloads received on one channel, as returned by [`PostgreSQLPool::listen`]
+///
+/// Reconnection happens in the background. After a reconnect one `Err` is yielded, since
+/// notifications sent while disconnected are lost. Dropping the stream ends the `LISTEN`.
+pub struct NotificationStream {
+    receiver: mpsc::UnboundedReceiver<crate::Result<String>>,
+    task: JoinHandle<()>,
+}
+
+impl Stream for NotificationStream {
+    type Item = crate::Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for NotificationStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Subscribe to `channel` before returning, so no notification sent afterwards is missed
+pub(crate) async fn listen(pool: &PostgreSQLPool, channel: &str, backoff: ReconnectBackoff) -> crate::Result<NotificationStream> {
+    let mut listener = PgListener::connect_with(pool.inner())
+        .await
+        .map_err(|e| PostgreSQLError::ConnectionFailed(format!("failed to connect listener: {}", e)))?;
+    listener
+        .listen(channel)
+        .await
+        .map_err(|e| PostgreSQLError::QueryError(format!("failed to LISTEN on '{}': {}", channel, e)))?;
+
+    // The supervisor starts from the subscribed connection and only dials again after it drops
+    let pg_pool = pool.inner().clone();
+    let mut subscribed = Some(listener);
+    let connect = move || {
+        let subscribed = subscribed.take();
+        let pg_pool = pg_pool.clone();
+        Box::pin(async move {
+            match subscribed {
+                Some(listener) => Ok(listener),
+                None => PgListener::connect_with(&pg_pool).await,
+            }
+        }) as BoxFuture<'static, _>
+    };
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let reload_sender = sender.clone();
+    let pool_name = pool.name().to_string();
+    let channels = vec![channel.to_string()];
+    let task = tokio::spawn(async move {
+        let lost = format!(
+            "listener on '{}' reconnected, notifications sent while disconnected were lost",
+            channels[0]
+        );
+        supervise(
+            &pool_name,
+            &channels,
+            backoff,
+            connect,
+            move |notification| {
+                let _ = sender.send(Ok(notification.payload));
+            },
+            move || {
+                let _ = reload_sender.send(Err(PostgreSQLError::ConnectionFailed(lost.clone())));
+            },
+        )
+        .await
+    });
+
+    Ok(NotificationStream { receiver, task })
+}
+
+/// Connect, subscribe and forward notifications forever, reconnecting whenever the connection is lost
+pub(crate) async fn supervise<C, L, N, R>(
+    pool_name: &str,
+    channels: &[String],
+    backoff: ReconnectBackoff,
+    mut connect: C,
+    mut on_notification: N,
+    mut on_reload: R,
+) where
+    C: FnMut() -> BoxFuture<'static, Result<L, sqlx::Error>>,
+    L: ListenConnection,
+    N: FnMut(ListenerNotification),
+    R: FnMut(),
+{
+    let mut subscribed_before = false;
+    let mut attempt = 0;
+
+    loop {
+        let mut connection = match connect().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                attempt += 1;
+                let delay = backoff.delay(attempt);
+                warn!(pool = %pool_name, "Failed to connect listener (attempt {}, retry in {:?}): {}", attempt, delay, e);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = connection.listen_all(channels).await {
+            attempt += 1;
+            let delay = backoff.delay(attempt);
+            warn!(pool = %pool_name, "Failed to subscribe listener (attempt {}, retry in {:?}): {}", attempt, delay, e);
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+
+        if subscribed_before {
+            record_listener_reconnect(pool_name);
+            info!(pool = %pool_name, channels = ?channels, "Listener re-subscribed, triggering full reload");
+            on_reload();
+        } else {
+            info!(pool = %pool_name, channels = ?channels, "Listener subscribed");
+        }
+        subscribed_before = true;
+        attempt = 0;
+
+        loop {
+            match connection.next().await {
+                Ok(Some(notification)) => on_notification(notification),
+                Ok(None) => {
+                    warn!(pool = %pool_name, "Listener connection lost, reconnecting");
+                    break;
+                }
+                Err(e) => {
+                    warn!(pool = %pool_name, "Listener connection failed, reconnecting: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::mpsc;
+
+    /// Scripted connection: yields its queued events, then reports the connection as lost
+    struct FakeConnection {
+        subscriptions: Arc<Mutex<Vec<Vec<String>>>>,
+        events: VecDeque<ListenerNotification>,
+        hold_open: bool,
+    }
+
+    impl ListenConnection for FakeConnection {
+        fn listen_all<'a>(&'a mut self, channels: &'a [String]) -> BoxFuture<'a, Result<(), sqlx::Error>> {
+            self.subscriptions.lock().unwrap().push(channels.to_vec());
+            Box::pin(async { Ok(()) })
+        }
+
+        fn next(&mut self) -> BoxFuture<'_, Result<Option<ListenerNotification>, sqlx::Error>> {
+            Box::pin(async move {
+                match self.events.pop_front() {
+                    Some(event) => Ok(Some(event)),
+                    None if self.hold_open => futures::future::pending().await,
+                    None => Ok(None),
+                }
+            })
+        }
+    }
+
+    fn notification(payload: &str) -> ListenerNotification {
+        ListenerNotification {
+            channel: "config".to_string(),
+            payload: payload.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let backoff = ReconnectBackoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(350),
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(200));
+        assert_eq!(backoff.delay(3), Duration::from_millis(350));
+    }
+
+    #[tokio::test]
+    async fn test_resubscribes_and_reloads_after_dropped_connection() {
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+        let mut connects = 0;
+        let connect = {
+            let subscriptions = subscriptions.clone();
+            move || {
+                connects += 1;
+                // First connection drops after one event; the second stays open
+                let connection = FakeConnection {
+                    subscriptions: subscriptions.clone(),
+                    events: if connects == 1 {
+                        VecDeque::from([notification("v1")])
+                    } else {
+                        VecDeque::new()
+                    },
+                    hold_open: connects > 1,
+                };
+                Box::pin(async move { Ok(connection) }) as BoxFuture<'static, _>
+            }
+        };
+
+        let channels = vec!["config".to_string(), "cache".to_string()];
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (reload_tx, mut reload_rx) = mpsc::unbounded_channel();
+
+        let task = {
+            let received = received.clone();
+            let channels = channels.clone();
+            tokio::spawn(async move {
+                supervise(
+                    "default",
+                    &channels,
+                    ReconnectBackoff::default(),
+                    connect,
+                    move |n| received.lock().unwrap().push(n),
+                    move || reload_tx.send(()).unwrap(),
+                )
+                .await
+            })
+        };
+
+        tokio::time::timeout(Duration::from_secs(5), reload_rx.recv())
+            .await
+            .expect("reload was not triggered")
+            .unwrap();
+        task.abort();
+
+        assert_eq!(*subscriptions.lock().unwrap(), vec![channels.clone(), channels]);
+        assert_eq!(*received.lock().unwrap(), vec![notification("v1")]);
+    }
+}
@@ -1,15 +1,37 @@
-use crate::{PostgreSQLConfig, PostgreSQLError, Result};
-use sqlx::PgPool;
+use crate::dns::DnsRefresh;
+use crate::listener::{self, NotificationStream, ReconnectBackoff};
+use crate::pool_metrics::{record_health_check, record_pool_stats};
+use crate::retry::{is_failover_error, retry_once_on_failover, retry_transient};
+use crate::{DEFAULT_POOL_NAME, PostgreSQLConfig, PostgreSQLError, Result};
+use futures::future::BoxFuture;
+use sqlx::migrate::Migrator;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::{FromRow, PgConnection, PgPool, Postgres, Transaction};
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
-use tokio::sync::OnceCell;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, instrument, warn};
 
-static GLOBAL_POOL: OnceCell<Arc<PgPool>> = OnceCell::const_new();
+const WARMUP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long `health_check` waits for `SELECT 1` before reporting the pool unhealthy
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Registered pools by name; `init`/`get` use [`DEFAULT_POOL_NAME`]
+static GLOBAL_POOLS: LazyLock<RwLock<HashMap<String, PostgreSQLPool>>> = LazyLock::new(Default::default);
 
 /// PostgreSQL connection pool wrapper
 #[derive(Clone)]
 pub struct PostgreSQLPool {
     pool: Arc<PgPool>,
+    name: Arc<str>,
+    failover_retry: bool,
+    acquire_slow_threshold: Option<Duration>,
+    dns_refresh: Option<Arc<DnsRefresh>>,
 }
 
 pub struct Success;
@@ -29,36 +51,87 @@ impl PostgreSQLPool {
     /// 
     /// This function will also create the schema if specified in config and it doesn't exist.
     pub async fn init(config: Option<&PostgreSQLConfig>) -> Result<Success> {
+        Self::init_named(DEFAULT_POOL_NAME, config).await
+    }
+
+    /// Connect a pool from `config` and register it under `name`, e.g. for an analytics database
+    ///
+    /// Retrieve it with `get_named(name)`. A pool without a configured `name` is labelled with
+    /// `name` in metrics and logs. Registering the same name twice is an error.
+    pub async fn init_named(name: &str, config: Option<&PostgreSQLConfig>) -> Result<Success> {
+        if Self::get_named(name).is_ok() {
+            return Err(already_initialized(name));
+        }
+        let pool = Self::connect_named(name, config).await?;
+        if let Some(db_config) = config
+            && db_config.auto_migrate.unwrap_or(false)
+        {
+            pool.run_migrations(Path::new(db_config.migrations_dir())).await?;
+        }
+
+        register(name, pool)?;
+        Ok(Success)
+    }
+
+    /// Initialize the default pool, or replace it with a fresh one built from `config`
+    ///
+    /// The new pool is connected before the swap, so a failed reconnect leaves the current
+    /// pool in place. `get()` hands out the new pool from then on. The previous pool is not
+    /// closed: holders of earlier clones, such as IAM, keep using it, and its connections
+    /// are released once the last clone is dropped.
+    pub async fn init_or_replace(config: Option<&PostgreSQLConfig>) -> Result<Self> {
+        let pool = Self::connect_named(DEFAULT_POOL_NAME, config).await?;
+        if replace(DEFAULT_POOL_NAME, pool.clone()).is_some() {
+            info!(pool = %pool.name, "Replaced PostgreSQL connection pool");
+        }
+        Ok(pool)
+    }
+
+    /// Create a pool from `config` without registering it, labelled `name` unless the config names it
+    async fn connect_named(name: &str, config: Option<&PostgreSQLConfig>) -> Result<Self> {
+        // Named before connecting, so only the pool registered as the default picks up `DATABASE_URL`
+        let named = config.map(|c| PostgreSQLConfig {
+            name: c.name.clone().or_else(|| Some(name.to_string())),
+            ..c.clone()
+        });
+        Self::connect(named.as_ref()).await
+    }
+
+    /// Create a pool from `config` without touching the registry
+    async fn connect(config: Option<&PostgreSQLConfig>) -> Result<Self> {
         let db_config = config.ok_or_else(|| {
-            PostgreSQLError::ConfigurationError("Database configuration is missing. Please configure database in config.toml".to_string())
+            PostgreSQLError::ConfigurationError(
+                "Database configuration is missing. Please configure database in config.toml".to_string(),
+            )
         })?;
-        
-        let pool = db_config.create_pool().await?;
-        
+
+        let (pool, dns_refresh) = db_config.create_pool_with_dns_refresh().await?;
+        let effective = db_config.effective_connection_config();
+
         // Create schema if specified and doesn't exist
         if let Some(schema_name) = db_config.schema.as_deref() {
             let schema_sql = format!("CREATE SCHEMA IF NOT EXISTS {}", schema_name);
             if let Err(e) = sqlx::query(&schema_sql).execute(&pool).await {
-                tracing::warn!("Failed to create schema '{}': {}", schema_name, e);
+                tracing::warn!(pool = %db_config.pool_name(), "Failed to create schema '{}': {}", schema_name, e);
                 // Don't fail initialization if schema creation fails (might already exist or permission issue)
             } else {
-                tracing::info!("Schema '{}' created or already exists", schema_name);
+                tracing::info!(pool = %db_config.pool_name(), "Schema '{}' created or already exists", schema_name);
             }
             
             // Set the search_path to use the schema
             let set_search_path = format!("SET search_path TO {}", schema_name);
             if let Err(e) = sqlx::query(&set_search_path).execute(&pool).await {
-                tracing::warn!("Failed to set search_path to '{}': {}", schema_name, e);
+                tracing::warn!(pool = %db_config.pool_name(), "Failed to set search_path to '{}': {}", schema_name, e);
             }
         }
         
-        let pool_arc = Arc::new(pool);
-        
-        GLOBAL_POOL
-            .set(pool_arc)
-            .map_err(|_| PostgreSQLError::ConfigurationError("Pool already initialized".to_string()))?;
-
-        Ok(Success)
+        Ok(Self {
+            pool: Arc::new(pool),
+            name: Arc::from(db_config.pool_name()),
+            failover_retry: effective.failover_retry,
+            acquire_slow_threshold: effective.acquire_slow_threshold,
+            dns_refresh,
+        })
     }
 
     /// Get the global PostgreSQL connection pool instance
@@ -66,11 +139,42 @@ impl PostgreSQLPool {
     /// Returns the pool instance if initialized, or an error if not initialized.
     /// Call `init()` first to initialize the pool.
     pub fn get() -> Result<Self> {
-        let pool = GLOBAL_POOL
-            .get()
-            .ok_or_else(|| PostgreSQLError::ConfigurationError("Pool not initialized. Call init() first.".to_string()))?;
-        
-        Ok(Self { pool: pool.clone() })
+        GLOBAL_POOLS
+            .read()
+            .unwrap()
+            .get(DEFAULT_POOL_NAME)
+            .cloned()
+            .ok_or_else(|| PostgreSQLError::ConfigurationError("Pool not initialized. Call init() first.".to_string()))
+    }
+
+    /// Get the pool registered under `name` by `init_named`
+    pub fn get_named(name: &str) -> Result<Self> {
+        let pools = GLOBAL_POOLS.read().unwrap();
+        pools.get(name).cloned().ok_or_else(|| {
+            let mut known: Vec<_> = pools.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            PostgreSQLError::ConfigurationError(format!(
+                "No PostgreSQL pool named '{}' (registered: [{}])",
+                name,
+                known.join(", ")
+            ))
+        })
+    }
+
+    /// Apply the migrations in `migrations_dir` that have not run yet
+    ///
+    /// Applied versions are tracked in `_sqlx_migrations`, so calling this on an
+    /// up-to-date database is a no-op.
+    pub async fn run_migrations(&self, migrations_dir: &Path) -> Result<()> {
+        let migrator = Migrator::new(migrations_dir).await.map_err(|e| {
+            PostgreSQLError::MigrationFailed(format!("failed to load migrations from {}: {}", migrations_dir.display(), e))
+        })?;
+        migrator
+            .run(&*self.pool)
+            .await
+            .map_err(|e| PostgreSQLError::MigrationFailed(e.to_string()))?;
+        info!(pool = %self.name, dir = %migrations_dir.display(), "Database migrations applied");
+        Ok(())
     }
 
     /// Get the underlying PgPool
@@ -78,22 +182,754 @@ impl PostgreSQLPool {
         &self.pool
     }
 
+    /// Name of this pool, used to label metrics, spans and log lines
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Close the pool, waiting for checked-out connections to be returned
+    ///
+    /// New acquisitions fail with `PoolError` from then on. Calling this again is a no-op.
+    pub async fn close(&self) {
+        if self.pool.is_closed() {
+            return;
+        }
+        info!(pool = %self.name, "Closing PostgreSQL connection pool");
+        self.pool.close().await;
+    }
+
+    /// Close every registered pool; does nothing when none was initialized
+    pub async fn close_global() {
+        let pools: Vec<_> = GLOBAL_POOLS.read().unwrap().values().cloned().collect();
+        for pool in pools {
+            pool.close().await;
+        }
+    }
+
+    /// Acquire a connection, warning if it takes longer than `acquire_slow_threshold`
+    pub async fn acquire(&self) -> Result<PoolConnection<Postgres>> {
+        self.acquire_raw()
+            .await
+            .map_err(|e| PostgreSQLError::ConnectionFailed(e.to_string()))
+    }
+
+    async fn acquire_raw(&self) -> std::result::Result<PoolConnection<Postgres>, sqlx::Error> {
+        let result = watch_acquire(&self.name, self.acquire_slow_threshold, || self.occupancy(), self.pool.acquire())
+            .await
+            .0;
+        self.refresh_dns_on_failure(result.is_err()).await;
+        result
+    }
+
+    async fn begin_raw(&self) -> std::result::Result<Transaction<'static, Postgres>, sqlx::Error> {
+        let result = watch_acquire(&self.name, self.acquire_slow_threshold, || self.occupancy(), self.pool.begin())
+            .await
+            .0;
+        self.refresh_dns_on_failure(result.is_err()).await;
+        result
+    }
+
+    /// A failed acquisition may mean the pinned address is gone, so look the host up again
+    async fn refresh_dns_on_failure(&self, failed: bool) {
+        if let Some(dns_refresh) = self.dns_refresh.as_ref().filter(|_| failed) {
+            dns_refresh.refresh().await;
+        }
+    }
+
+    fn occupancy(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+
+    /// Map a sqlx error, calling out a closed pool instead of reporting a failed query
+    fn query_error(&self, e: sqlx::Error) -> PostgreSQLError {
+        match e {
+            sqlx::Error::PoolClosed => PostgreSQLError::PoolError(format!("pool '{}' is closed", self.name)),
+            e => PostgreSQLError::QueryError(e.to_string()),
+        }
+    }
+
     /// Execute a query and return the number of affected rows
+    ///
+    /// If the write fails because the primary failed over (read-only or reset connection),
+    /// the connection is evicted and the query is retried once on a fresh connection.
     pub async fn execute(&self, query: &str) -> Result<u64> {
-        sqlx::query(query)
-            .execute(self.inner())
+        retry_once_on_failover(&self.name, self.failover_retry, || async move {
+            let mut conn = self.acquire_raw().await?;
+            match sqlx::query(query).execute(&mut *conn).await {
+                Ok(result) => Ok(result.rows_affected()),
+                Err(e) => {
+                    if is_failover_error(&e) {
+                        // Close instead of returning the connection to the pool
+                        let _ = conn.close().await;
+                    }
+                    Err(e)
+                }
+            }
+        })
+        .await
+        .map_err(|e| self.query_error(e))
+    }
+
+    /// Execute a query with bound parameters and return the number of affected rows
+    ///
+    /// Values travel as `$1`, `$2`, ... parameters rather than being formatted into `query`.
+    /// Failover handling is the same as `execute`.
+    pub async fn execute_with(&self, query: &str, args: PgArguments) -> Result<u64> {
+        retry_once_on_failover(&self.name, self.failover_retry, || {
+            let args = args.clone();
+            async move {
+                let mut conn = self.acquire_raw().await?;
+                match sqlx::query_with(query, args).execute(&mut *conn).await {
+                    Ok(result) => Ok(result.rows_affected()),
+                    Err(e) => {
+                        if is_failover_error(&e) {
+                            let _ = conn.close().await;
+                        }
+                        Err(e)
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|e| self.query_error(e))
+    }
+
+    /// Fetch exactly one row mapped into `T`; no rows is an error
+    pub async fn query_one<T>(&self, query: &str) -> Result<T>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut conn = self.acquire_raw().await.map_err(|e| self.query_error(e))?;
+        sqlx::query_as::<_, T>(query)
+            .fetch_one(&mut *conn)
             .await
-            .map_err(|e| PostgreSQLError::QueryError(e.to_string()))
-            .map(|r| r.rows_affected())
+            .map_err(|e| self.query_error(e))
     }
 
-    /// Check if the connection pool is healthy
-    pub async fn health_check(&self) -> Result<bool> {
-        sqlx::query("SELECT 1")
-            .execute(self.inner())
+    /// Fetch at most one row mapped into `T`
+    pub async fn query_optional<T>(&self, query: &str) -> Result<Option<T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut conn = self.acquire_raw().await.map_err(|e| self.query_error(e))?;
+        sqlx::query_as::<_, T>(query)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| self.query_error(e))
+    }
+
+    /// Fetch every row mapped into `T`
+    pub async fn query_all<T>(&self, query: &str) -> Result<Vec<T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut conn = self.acquire_raw().await.map_err(|e| self.query_error(e))?;
+        sqlx::query_as::<_, T>(query)
+            .fetch_all(&mut *conn)
             .await
-            .map_err(|e| PostgreSQLError::QueryError(e.to_string()))
-            .map(|_| true)
+            .map_err(|e| self.query_error(e))
     }
+
+    /// Run `f` inside a transaction, committing when it returns `Ok` and rolling back otherwise
+    ///
+    /// The error from `f` is returned as is after a successful rollback. Failures to begin,
+    /// commit or roll back are reported as `PostgreSQLError::TransactionError`.
+    pub async fn transaction<F, R>(&self, f: F) -> Result<R>
+    where
+        F: for<'c> FnOnce(&'c mut Transaction<'static, Postgres>) -> BoxFuture<'c, Result<R>> + Send,
+        R: Send,
+    {
+        let mut tx = self
+            .begin_raw()
+            .await
+            .map_err(|e| PostgreSQLError::TransactionError(format!("failed to begin: {e}")))?;
+
+        match f(&mut tx).await {
+            Ok(value) => {
+                tx.commit()
+                    .await
+                    .map_err(|e| PostgreSQLError::TransactionError(format!("failed to commit: {e}")))?;
+                Ok(value)
+            }
+            Err(e) => match tx.rollback().await {
+                Ok(()) => Err(e),
+                Err(rollback) => Err(PostgreSQLError::TransactionError(format!(
+                    "failed to roll back after error ({e}): {rollback}"
+                ))),
+            },
+        }
+    }
+
+    /// Run `f` inside a transaction, retrying on serialization failures and deadlocks
+    ///
+    /// The transaction is committed when `f` succeeds. When `f` fails with SQLSTATE
+    /// `40001` or `40P01` the transaction is rolled back and `f` is re-run with
+    /// exponential backoff, up to `max_retries` times. Any other error is returned immediately.
+    pub async fn transaction_retry<F, T>(&self, max_retries: u32, f: F) -> Result<T>
+    where
+        F: for<'c> FnMut(&'c mut PgConnection) -> BoxFuture<'c, std::result::Result<T, sqlx::Error>> + Send,
+        T: Send,
+    {
+        let mut state = (self.clone(), f);
+        retry_transient(&mut state, max_retries, |(pool, f)| {
+            Box::pin(async move {
+                let mut tx = pool.begin_raw().await?;
+                let value = f(&mut tx).await?;
+                tx.commit().await?;
+                Ok(value)
+            })
+        })
+        .await
+        .map_err(|e| self.query_error(e))
+    }
+
+    /// Record current pool occupancy, labelled with the pool name, without querying the server
+    pub fn record_stats(&self) {
+        record_pool_stats(&self.name, self.pool.size(), self.pool.num_idle());
+    }
+
+    /// Check if the connection pool is healthy, giving up after [`DEFAULT_HEALTH_CHECK_TIMEOUT`]
+    ///
+    /// Also records the check latency and current pool occupancy, labelled with the pool name.
+    pub async fn health_check(&self) -> Result<bool> {
+        self.health_check_timeout(DEFAULT_HEALTH_CHECK_TIMEOUT).await
+    }
+
+    /// Check if the connection pool is healthy, returning `Ok(false)` if the probe takes longer than `timeout`
+    #[instrument(skip(self), fields(pool = %self.name))]
+    pub async fn health_check_timeout(&self, timeout: Duration) -> Result<bool> {
+        self.probe("SELECT 1", timeout).await
+    }
+
+    async fn probe(&self, query: &str, timeout: Duration) -> Result<bool> {
+        let start = Instant::now();
+        let result = match tokio::time::timeout(timeout, sqlx::query(query).execute(self.inner())).await {
+            Ok(result) => result.map_err(|e| self.query_error(e)).map(|_| true),
+            Err(_) => {
+                tracing::warn!(pool = %self.name, "PostgreSQL health check timed out after {:?}", timeout);
+                Ok(false)
+            }
+        };
+
+        record_health_check(&self.name, start.elapsed(), matches!(result, Ok(true)));
+        record_pool_stats(&self.name, self.pool.size(), self.pool.num_idle());
+        if let Err(e) = &result {
+            tracing::warn!(pool = %self.name, "PostgreSQL health check failed: {}", e);
+        }
+        result
+    }
+
+    /// Stream the payloads of `NOTIFY`s on `channel`, reconnecting with the default backoff if the connection drops
+    ///
+    /// The channel is subscribed before this returns. For several channels, a reload hook or a
+    /// custom backoff, use [`crate::spawn_listener`].
+    pub async fn listen(&self, channel: &str) -> Result<NotificationStream> {
+        listener::listen(self, channel, ReconnectBackoff::default()).await
+    }
+
+    /// Check whether `schema.table` exists
+    ///
+    /// Names are passed as bound parameters to `information_schema.tables`, never formatted into SQL.
+    pub async fn table_exists(&self, schema: &str, table: &str) -> Result<bool> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.tables WHERE table_schema = $1 AND table_name = $2)",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_one(self.inner())
+        .await
+        .map_err(|e| self.query_error(e))
+    }
+}
+
+fn already_initialized(name: &str) -> PostgreSQLError {
+    PostgreSQLError::ConfigurationError(format!("Pool '{}' already initialized", name))
+}
+
+/// Add `pool` to the registry under `name`, refusing to overwrite an existing entry
+fn register(name: &str, pool: PostgreSQLPool) -> Result<()> {
+    let mut pools = GLOBAL_POOLS.write().unwrap();
+    if pools.contains_key(name) {
+        return Err(already_initialized(name));
+    }
+    pools.insert(name.to_string(), pool);
+    Ok(())
 }
 
+/// Register `pool` under `name`, returning the pool it replaced without closing it
+fn replace(name: &str, pool: PostgreSQLPool) -> Option<PostgreSQLPool> {
+    GLOBAL_POOLS.write().unwrap().insert(name.to_string(), pool)
+}
+
+/// Await a connection acquisition, warning when it takes longer than `threshold`
+///
+/// Without a threshold the future is awaited directly, with no timing overhead.
+/// Returns the acquisition result and whether it was reported as slow.
+pub(crate) async fn watch_acquire<F, T, S>(pool_name: &str, threshold: Option<Duration>, occupancy: S, acquire: F) -> (T, bool)
+where
+    F: Future<Output = T>,
+    S: FnOnce() -> (u32, usize),
+{
+    let Some(threshold) = threshold else {
+        return (acquire.await, false);
+    };
+
+    let start = Instant::now();
+    let value = acquire.await;
+    let elapsed = start.elapsed();
+    if elapsed < threshold {
+        return (value, false);
+    }
+
+    let (size, idle) = occupancy();
+    warn!(
+        pool = %pool_name,
+        elapsed_ms = elapsed.as_millis() as u64,
+        size,
+        idle,
+        "Slow PostgreSQL connection acquisition: {:?} (threshold {:?})",
+        elapsed,
+        threshold
+    );
+    (value, true)
+}
+
+/// Wait until `current_size` reports at least `min_connections`, or `timeout` elapses
+///
+/// Returns `true` when the pool warmed up in time.
+pub(crate) async fn wait_for_warmup<F>(current_size: F, min_connections: u32, timeout: Duration) -> bool
+where
+    F: Fn() -> u32,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if current_size() >= min_connections {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(WARMUP_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_warmup_waits_for_min_connections() {
+        // Each poll observes one more established connection
+        let size = AtomicU32::new(0);
+        let start = Instant::now();
+
+        let warmed = wait_for_warmup(|| size.fetch_add(1, Ordering::SeqCst), 3, Duration::from_secs(5)).await;
+
+        assert!(warmed);
+        assert_eq!(size.load(Ordering::SeqCst), 4);
+        assert!(start.elapsed() >= WARMUP_POLL_INTERVAL * 3);
+    }
+
+    async fn connect_test_pool() -> PostgreSQLPool {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        PostgreSQLPool {
+            pool: Arc::new(PgPool::connect(&url).await.unwrap()),
+            name: Arc::from("default"),
+            failover_retry: false,
+            acquire_slow_threshold: None,
+            dns_refresh: None,
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Probe {
+        id: i32,
+        label: String,
+    }
+
+    impl<'r> FromRow<'r, PgRow> for Probe {
+        fn from_row(row: &'r PgRow) -> std::result::Result<Self, sqlx::Error> {
+            use sqlx::Row;
+            Ok(Self {
+                id: row.try_get("id")?,
+                label: row.try_get("label")?,
+            })
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_typed_queries_map_rows() {
+        let pool = connect_test_pool().await;
+
+        let row: Probe = pool.query_one("SELECT 1::INT4 AS id, 'one' AS label").await.unwrap();
+        assert_eq!(
+            row,
+            Probe {
+                id: 1,
+                label: "one".to_string()
+            }
+        );
+
+        let none: Option<Probe> = pool
+            .query_optional("SELECT 1::INT4 AS id, 'one' AS label WHERE false")
+            .await
+            .unwrap();
+        assert_eq!(none, None);
+
+        let rows: Vec<Probe> = pool
+            .query_all("SELECT g::INT4 AS id, 'row' || g AS label FROM generate_series(1, 3) g ORDER BY g")
+            .await
+            .unwrap();
+        assert_eq!(rows.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(rows[2].label, "row3");
+
+        let err = pool.query_one::<Probe>("SELECT 1::INT4 AS id").await.unwrap_err();
+        assert!(matches!(err, PostgreSQLError::QueryError(_)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_execute_with_binds_parameters() {
+        use sqlx::Arguments;
+
+        let pool = connect_test_pool().await;
+        pool.execute("DROP TABLE IF EXISTS execute_with_probe").await.unwrap();
+        pool.execute("CREATE TABLE execute_with_probe (id INT4, label TEXT)")
+            .await
+            .unwrap();
+
+        // The quote is stored verbatim instead of terminating the statement
+        let mut args = PgArguments::default();
+        args.add(7_i32).unwrap();
+        args.add("it's bound").unwrap();
+        let inserted = pool
+            .execute_with("INSERT INTO execute_with_probe (id, label) VALUES ($1, $2)", args)
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+
+        let row: Probe = pool.query_one("SELECT id, label FROM execute_with_probe").await.unwrap();
+        assert_eq!(
+            row,
+            Probe {
+                id: 7,
+                label: "it's bound".to_string()
+            }
+        );
+
+        pool.execute("DROP TABLE execute_with_probe").await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_transaction_rolls_back_on_error() {
+        let pool = connect_test_pool().await;
+        pool.execute("DROP TABLE IF EXISTS transaction_probe").await.unwrap();
+        pool.execute("CREATE TABLE transaction_probe (id INT4)").await.unwrap();
+
+        let result: Result<()> = pool
+            .transaction(|tx| {
+                Box::pin(async move {
+                    for id in [1, 2] {
+                        sqlx::query("INSERT INTO transaction_probe (id) VALUES ($1)")
+                            .bind(id)
+                            .execute(&mut **tx)
+                            .await
+                            .map_err(|e| PostgreSQLError::QueryError(e.to_string()))?;
+                    }
+                    Err(PostgreSQLError::QueryError("forced failure".to_string()))
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(PostgreSQLError::QueryError(msg)) if msg == "forced failure"));
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transaction_probe")
+            .fetch_one(pool.inner())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // The same statements commit when the closure succeeds
+        pool.transaction(|tx| {
+            Box::pin(async move {
+                sqlx::query("INSERT INTO transaction_probe (id) VALUES (1), (2)")
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| PostgreSQLError::QueryError(e.to_string()))?;
+                Ok(())
+            })
+        })
+        .await
+        .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transaction_probe")
+            .fetch_one(pool.inner())
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        pool.execute("DROP TABLE transaction_probe").await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_table_exists_detects_created_table() {
+        let pool = connect_test_pool().await;
+
+        pool.execute("DROP TABLE IF EXISTS table_exists_probe").await.unwrap();
+        assert!(!pool.table_exists("public", "table_exists_probe").await.unwrap());
+
+        pool.execute("CREATE TABLE table_exists_probe (id INT)").await.unwrap();
+        assert!(pool.table_exists("public", "table_exists_probe").await.unwrap());
+        // A quote in the name is matched literally rather than breaking the query
+        assert!(!pool.table_exists("public", "table_exists_probe' OR '1'='1").await.unwrap());
+
+        pool.execute("DROP TABLE table_exists_probe").await.unwrap();
+    }
+
+    /// Pool settings taken from `DATABASE_URL`
+    fn config_from_database_url() -> PostgreSQLConfig {
+        let url = url::Url::parse(&std::env::var("DATABASE_URL").expect("DATABASE_URL must be set")).unwrap();
+        let toml = format!(
+            "host = {:?}\nport = {}\nuser = {:?}\npassword = {:?}\ndatabase = {:?}\n",
+            url.host_str().unwrap_or("localhost"),
+            url.port().unwrap_or(5432),
+            urlencoding::decode(url.username()).unwrap(),
+            urlencoding::decode(url.password().unwrap_or("")).unwrap(),
+            urlencoding::decode(url.path().trim_start_matches('/')).unwrap(),
+        );
+        nebulafx_tomlx::load_config_from_str(&toml).unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_listen_receives_notify_from_another_connection() {
+        use futures::StreamExt;
+
+        let pool = connect_test_pool().await;
+        let mut notifications = pool.listen("nebulafx_listen_test").await.unwrap();
+
+        pool.execute("NOTIFY nebulafx_listen_test, 'bucket-created'").await.unwrap();
+
+        let received = tokio::time::timeout(Duration::from_secs(5), notifications.next())
+            .await
+            .unwrap();
+        assert_eq!(received.unwrap().unwrap(), "bucket-created");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_statement_timeout_cancels_slow_queries() {
+        let mut config = config_from_database_url();
+        config.connection = Some(nebulafx_tomlx::load_config_from_str("statement_timeout = \"100ms\"").unwrap());
+        let pool = config.create_pool().await.unwrap();
+
+        let err = sqlx::query("SELECT pg_sleep(1)").execute(&pool).await.unwrap_err();
+        assert!(err.to_string().contains("statement timeout"), "{err}");
+
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout").fetch_one(&pool).await.unwrap();
+        assert_eq!(timeout, "100ms");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    #[serial]
+    async fn test_init_or_replace_swaps_the_global_pool() {
+        let config = config_from_database_url();
+
+        let first = PostgreSQLPool::init_or_replace(Some(&config)).await.unwrap();
+        assert!(first.health_check().await.unwrap());
+
+        let second = PostgreSQLPool::init_or_replace(Some(&config)).await.unwrap();
+        // `get()` hands out the new pool while clones of the previous one keep working
+        assert!(Arc::ptr_eq(&PostgreSQLPool::get().unwrap().pool, &second.pool));
+        assert!(PostgreSQLPool::get().unwrap().health_check().await.unwrap());
+        assert!(!first.inner().is_closed());
+        assert!(first.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_execute_after_close_reports_closed_pool() {
+        let pool = connect_test_pool().await;
+        assert_eq!(pool.execute("SELECT 1").await.unwrap(), 1);
+
+        pool.close().await;
+        pool.close().await;
+        assert!(pool.inner().is_closed());
+
+        let err = pool.execute("SELECT 1").await.unwrap_err();
+        assert!(matches!(err, PostgreSQLError::PoolError(_)));
+        assert!(err.to_string().contains("pool 'default' is closed"));
+    }
+
+    /// Unconnected pool labelled `name`, enough to exercise the registry
+    fn lazy_pool(name: &str) -> PostgreSQLPool {
+        PostgreSQLPool {
+            pool: Arc::new(PgPool::connect_lazy("postgresql://localhost/unused").unwrap()),
+            name: Arc::from(name),
+            failover_retry: false,
+            acquire_slow_threshold: None,
+            dns_refresh: None,
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_named_pools_are_registered_independently() {
+        register("registry-primary", lazy_pool("primary")).unwrap();
+        register("registry-analytics", lazy_pool("analytics")).unwrap();
+
+        assert_eq!(PostgreSQLPool::get_named("registry-primary").unwrap().name(), "primary");
+        assert_eq!(PostgreSQLPool::get_named("registry-analytics").unwrap().name(), "analytics");
+
+        let err = register("registry-primary", lazy_pool("again")).unwrap_err();
+        assert!(err.to_string().contains("'registry-primary' already initialized"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_replaced_pool_stays_open_for_existing_clones() {
+        let first = lazy_pool("replaced");
+        let held = first.inner().clone();
+        assert!(replace("registry-replaced", first).is_none());
+
+        let previous = replace("registry-replaced", lazy_pool("replacement")).unwrap();
+        assert_eq!(previous.name(), "replaced");
+        assert_eq!(PostgreSQLPool::get_named("registry-replaced").unwrap().name(), "replacement");
+        assert!(!held.is_closed());
+    }
+
+    #[test]
+    fn test_unknown_pool_name_is_reported() {
+        let Err(err) = PostgreSQLPool::get_named("no-such-pool") else {
+            panic!("no pool is registered under that name");
+        };
+        assert!(matches!(err, PostgreSQLError::ConfigurationError(_)));
+        assert!(err.to_string().contains("No PostgreSQL pool named 'no-such-pool'"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    #[serial]
+    async fn test_init_named_connects_two_pools() {
+        let config = config_from_database_url();
+        PostgreSQLPool::init_named("it-primary", Some(&config)).await.unwrap();
+        PostgreSQLPool::init_named("it-analytics", Some(&config)).await.unwrap();
+
+        let primary = PostgreSQLPool::get_named("it-primary").unwrap();
+        let analytics = PostgreSQLPool::get_named("it-analytics").unwrap();
+        assert_eq!(primary.name(), "it-primary");
+        assert_eq!(analytics.name(), "it-analytics");
+        assert!(!Arc::ptr_eq(&primary.pool, &analytics.pool));
+        assert!(primary.health_check().await.unwrap());
+        assert!(analytics.health_check().await.unwrap());
+
+        assert!(PostgreSQLPool::init_named("it-primary", Some(&config)).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_slow_health_check_times_out() {
+        let pool = connect_test_pool().await;
+        assert!(pool.health_check_timeout(Duration::from_secs(5)).await.unwrap());
+
+        let start = Instant::now();
+        let healthy = pool.probe("SELECT pg_sleep(5)", Duration::from_millis(200)).await.unwrap();
+        assert!(!healthy);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_close_global_closes_every_registered_pool() {
+        register("registry-close-primary", lazy_pool("primary")).unwrap();
+        register("registry-close-analytics", lazy_pool("analytics")).unwrap();
+
+        PostgreSQLPool::close_global().await;
+        assert!(
+            PostgreSQLPool::get_named("registry-close-primary")
+                .unwrap()
+                .inner()
+                .is_closed()
+        );
+        assert!(
+            PostgreSQLPool::get_named("registry-close-analytics")
+                .unwrap()
+                .inner()
+                .is_closed()
+        );
+
+        // Closing again is a no-op
+        PostgreSQLPool::close_global().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_run_migrations_creates_table() {
+        let pool = connect_test_pool().await;
+        pool.execute("DROP TABLE IF EXISTS migration_probe").await.unwrap();
+        pool.execute("DELETE FROM _sqlx_migrations WHERE description = 'create migration probe'")
+            .await
+            .ok();
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("20240101000000_create_migration_probe.sql"),
+            "CREATE TABLE migration_probe (id INT PRIMARY KEY);",
+        )
+        .unwrap();
+
+        pool.run_migrations(dir.path()).await.unwrap();
+        assert!(pool.table_exists("public", "migration_probe").await.unwrap());
+        // Already-applied migrations are skipped
+        pool.run_migrations(dir.path()).await.unwrap();
+
+        pool.execute("DROP TABLE migration_probe").await.unwrap();
+        pool.execute("DELETE FROM _sqlx_migrations WHERE description = 'create migration probe'")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_migrations_dir_is_a_migration_error() {
+        let pool = lazy_pool("default");
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = pool.run_migrations(&dir.path().join("missing")).await.unwrap_err();
+        assert!(matches!(err, PostgreSQLError::MigrationFailed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_slow_acquire_is_reported_on_saturated_pool() {
+        // A single-permit semaphore stands in for a pool with every connection checked out
+        let pool = Arc::new(tokio::sync::Semaphore::new(1));
+        let held = pool.clone().acquire_owned().await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(held);
+        });
+
+        let (permit, slow) = watch_acquire("default", Some(Duration::from_millis(10)), || (1, 0), pool.acquire()).await;
+
+        assert!(permit.is_ok());
+        assert!(slow);
+    }
+
+    #[tokio::test]
+    async fn test_fast_or_unwatched_acquire_is_not_reported() {
+        let (_, slow) = watch_acquire("default", Some(Duration::from_secs(1)), || (1, 1), async {}).await;
+        assert!(!slow);
+
+        let (_, slow) = watch_acquire("default", None, || unreachable!(), tokio::time::sleep(Duration::from_millis(20))).await;
+        assert!(!slow);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_gives_up_after_timeout() {
+        let warmed = wait_for_warmup(|| 1, 10, Duration::from_millis(120)).await;
+        assert!(!warmed);
+    }
+}
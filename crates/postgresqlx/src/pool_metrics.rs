@@ -0,0 +1,105 @@
+use crate::EffectiveConnectionConfig;
+use metrics::{counter, gauge, histogram};
+use std::time::Duration;
+
+pub(crate) const POOL_LABEL: &str = "pool";
+
+/// Record the resolved pool limits, labelled by pool name
+pub(crate) fn record_pool_config(pool_name: &str, config: &EffectiveConnectionConfig) {
+    let labels = [(POOL_LABEL, pool_name.to_string())];
+    gauge!("nebulafx_db_pool_max_connections", &labels).set(config.max_connections as f64);
+    gauge!("nebulafx_db_pool_min_connections", &labels).set(config.min_connections as f64);
+}
+
+/// Record current pool occupancy, labelled by pool name
+pub(crate) fn record_pool_stats(pool_name: &str, size: u32, idle: usize) {
+    let labels = [(POOL_LABEL, pool_name.to_string())];
+    gauge!("nebulafx_db_pool_connections", &labels).set(size as f64);
+    gauge!("nebulafx_db_pool_idle_connections", &labels).set(idle as f64);
+}
+
+/// Record the outcome and latency of a health check, labelled by pool name
+pub(crate) fn record_health_check(pool_name: &str, elapsed: Duration, healthy: bool) {
+    let labels = [(POOL_LABEL, pool_name.to_string())];
+    histogram!("nebulafx_db_health_check_seconds", &labels).record(elapsed.as_secs_f64());
+    if !healthy {
+        counter!("nebulafx_db_health_check_failures_total", &labels).increment(1);
+    }
+}
+
+/// Record a `LISTEN` connection being re-established, labelled by pool name
+pub(crate) fn record_listener_reconnect(pool_name: &str) {
+    counter!("nebulafx_db_listener_reconnects_total", POOL_LABEL => pool_name.to_string()).increment(1);
+}
+
+/// Record a connection evicted after a failover error, labelled by pool name
+pub(crate) fn record_failover_eviction(pool_name: &str) {
+    counter!("nebulafx_db_failover_evictions_total", POOL_LABEL => pool_name.to_string()).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    use std::sync::Mutex;
+
+    /// Recorder that remembers the keys of registered metrics
+    #[derive(Default)]
+    struct KeyRecorder {
+        keys: Mutex<Vec<Key>>,
+    }
+
+    impl Recorder for KeyRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            self.keys.lock().unwrap().push(key.clone());
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            self.keys.lock().unwrap().push(key.clone());
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            self.keys.lock().unwrap().push(key.clone());
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_pool_metrics_carry_pool_name_label() {
+        let recorder = KeyRecorder::default();
+        let config = EffectiveConnectionConfig {
+            max_connections: 20,
+            min_connections: 2,
+            acquire_timeout: Duration::from_secs(5),
+            max_lifetime: Duration::from_secs(3600),
+            idle_timeout: Duration::from_secs(900),
+            warmup_timeout: None,
+            failover_retry: true,
+            connect_retries: 0,
+            connect_retry_interval: Duration::from_secs(1),
+            acquire_slow_threshold: None,
+            statement_timeout: None,
+        };
+
+        metrics::with_local_recorder(&recorder, || {
+            record_pool_config("replica", &config);
+            record_health_check("replica", Duration::from_millis(3), false);
+        });
+
+        let keys = recorder.keys.lock().unwrap();
+        assert!(!keys.is_empty());
+        for key in keys.iter() {
+            assert!(
+                key.labels().any(|l| l.key() == POOL_LABEL && l.value() == "replica"),
+                "metric {} is missing the pool label",
+                key.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::pool_metrics::record_failover_eviction;
+use futures::future::BoxFuture;
+use std::future::Future;
+use std::io::ErrorKind as IoErrorKind;
+use std::time::Duration;
+use tracing::warn;
+
+/// SQLSTATE codes for errors that are safe to retry by re-running the whole transaction
+const RETRYABLE_SQLSTATES: [&str; 2] = [
+    "40001", // serialization_failure
+    "40P01", // deadlock_detected
+];
+
+/// SQLSTATE raised when writing through a connection to a demoted (read-only) primary
+const READ_ONLY_TRANSACTION_SQLSTATE: &str = "25006";
+
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(20);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Check whether an error is a transient serialization failure or deadlock
+pub(crate) fn is_retryable_error(err: &sqlx::Error) -> bool {
+    err.as_database_error()
+        .and_then(|db_err| db_err.code())
+        .is_some_and(|code| RETRYABLE_SQLSTATES.contains(&code.as_ref()))
+}
+
+/// Check whether an error indicates the connection points at a failed-over primary
+///
+/// This covers writes rejected as read-only and connections reset by the old primary.
+pub(crate) fn is_failover_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(io_err) => matches!(
+            io_err.kind(),
+            IoErrorKind::ConnectionReset | IoErrorKind::ConnectionAborted | IoErrorKind::BrokenPipe
+        ),
+        _ => err
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .is_some_and(|code| code == READ_ONLY_TRANSACTION_SQLSTATE),
+    }
+}
+
+/// Exponential backoff for the given retry attempt (1-based), capped at `RETRY_MAX_DELAY`
+pub(crate) fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(1u32 << attempt.saturating_sub(1).min(16))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// Run `attempt` until it succeeds, fails with a non-retryable error, or `max_retries` is exhausted
+///
+/// State is threaded through `state` rather than captured so that each attempt can borrow it mutably.
+pub(crate) async fn retry_transient<S, T, F>(state: &mut S, max_retries: u32, mut attempt: F) -> Result<T, sqlx::Error>
+where
+    F: for<'s> FnMut(&'s mut S) -> BoxFuture<'s, Result<T, sqlx::Error>>,
+{
+    let mut retries = 0;
+    loop {
+        match attempt(state).await {
+            Ok(value) => return Ok(value),
+            Err(e) if retries < max_retries && is_retryable_error(&e) => {
+                retries += 1;
+                let delay = retry_backoff(retries);
+                warn!("Transient transaction failure (retry {}/{} in {:?}): {}", retries, max_retries, delay, e);
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run `connect` until it succeeds or has been retried `max_retries` times, sleeping `interval` in between
+///
+/// The last error is returned once every attempt has failed.
+pub(crate) async fn retry_connect<T, F, Fut>(
+    pool_name: &str,
+    max_retries: u32,
+    interval: Duration,
+    mut connect: F,
+) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let attempts = max_retries.saturating_add(1);
+    let mut attempt = 1;
+    loop {
+        match connect().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts => {
+                warn!(
+                    pool = %pool_name,
+                    "Failed to connect to PostgreSQL (attempt {}/{}), retrying in {:?}: {}",
+                    attempt,
+                    attempts,
+                    interval,
+                    e
+                );
+                attempt += 1;
+                tokio::time::sleep(interval).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run `attempt`, retrying once on a fresh connection if it hit a failed-over primary
+///
+/// `attempt` is expected to close (rather than return to the pool) any connection that
+/// produced a failover error, so the retry acquires a connection to the new primary.
+pub(crate) async fn retry_once_on_failover<T, F, Fut>(pool_name: &str, enabled: bool, mut attempt: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    match attempt().await {
+        Err(e) if enabled && is_failover_error(&e) => {
+            record_failover_eviction(pool_name);
+            warn!(pool = %pool_name, "Evicted connection after failover error, retrying on a fresh connection: {}", e);
+            attempt().await
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::borrow::Cow;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct SqlStateError(&'static str);
+
+    impl fmt::Display for SqlStateError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "simulated error with SQLSTATE {}", self.0)
+        }
+    }
+
+    impl std::error::Error for SqlStateError {}
+
+    impl DatabaseError for SqlStateError {
+        fn message(&self) -> &str {
+            "simulated error"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn sqlstate_error(code: &'static str) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(SqlStateError(code)))
+    }
+
+    #[test]
+    fn test_is_retryable_error() {
+        assert!(is_retryable_error(&sqlstate_error("40001")));
+        assert!(is_retryable_error(&sqlstate_error("40P01")));
+        assert!(!is_retryable_error(&sqlstate_error("23505")));
+        assert!(!is_retryable_error(&sqlx::Error::RowNotFound));
+    }
+
+    #[test]
+    fn test_retry_backoff_grows_and_caps() {
+        assert_eq!(retry_backoff(1), Duration::from_millis(20));
+        assert_eq!(retry_backoff(2), Duration::from_millis(40));
+        assert_eq!(retry_backoff(3), Duration::from_millis(80));
+        assert_eq!(retry_backoff(30), RETRY_MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_succeeds_after_serialization_failure() {
+        let mut attempts = 0u32;
+        let result = retry_transient(&mut attempts, 3, |attempts| {
+            Box::pin(async move {
+                *attempts += 1;
+                if *attempts == 1 {
+                    Err(sqlstate_error("40001"))
+                } else {
+                    Ok(42)
+                }
+            })
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_propagates_non_retryable_error() {
+        let mut attempts = 0u32;
+        let result: Result<(), _> = retry_transient(&mut attempts, 3, |attempts| {
+            Box::pin(async move {
+                *attempts += 1;
+                Err(sqlstate_error("23505"))
+            })
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_gives_up_after_max_retries() {
+        let mut attempts = 0u32;
+        let result: Result<(), _> = retry_transient(&mut attempts, 2, |attempts| {
+            Box::pin(async move {
+                *attempts += 1;
+                Err(sqlstate_error("40P01"))
+            })
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_connect_gives_up_on_unreachable_host() {
+        // Nothing listens on port 1, so every attempt is refused
+        let options = PgConnectOptions::new().host("127.0.0.1").port(1);
+        let mut attempts = 0u32;
+        let result = retry_connect("default", 2, Duration::from_millis(10), || {
+            attempts += 1;
+            PgPoolOptions::new()
+                .acquire_timeout(Duration::from_secs(2))
+                .connect_with(options.clone())
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_connect_stops_after_success() {
+        let mut attempts = 0u32;
+        let result = retry_connect("default", 5, Duration::from_millis(1), || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(sqlx::Error::PoolTimedOut)
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_is_failover_error() {
+        assert!(is_failover_error(&sqlstate_error("25006")));
+        assert!(is_failover_error(&sqlx::Error::Io(std::io::Error::from(IoErrorKind::ConnectionReset))));
+        assert!(!is_failover_error(&sqlstate_error("40001")));
+        assert!(!is_failover_error(&sqlx::Error::RowNotFound));
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_on_read_only_primary() {
+        let mut attempts = 0u32;
+        let result = retry_once_on_failover("default", true, || {
+            attempts += 1;
+            let attempt = attempts;
+            async move { if attempt == 1 { Err(sqlstate_error("25006")) } else { Ok(7) } }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_retry_disabled_or_other_errors() {
+        let mut attempts = 0u32;
+        let result: Result<(), _> = retry_once_on_failover("default", false, || {
+            attempts += 1;
+            async { Err(sqlstate_error("25006")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0u32;
+        let result: Result<(), _> = retry_once_on_failover("default", true, || {
+            attempts += 1;
+            async { Err(sqlstate_error("23505")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -8,15 +8,50 @@ pub enum TomlConfigError {
     #[error("Failed to parse TOML: {0}")]
     Parse(#[from] toml::de::Error),
 
+    #[error("Failed to parse {path} at line {line}, column {col}: {message}")]
+    ParseAt {
+        path: String,
+        line: usize,
+        col: usize,
+        message: String,
+    },
+
+    #[error("Configuration is not valid UTF-8: {0}")]
+    Encoding(#[from] std::str::Utf8Error),
+
+    #[error("Failed to parse YAML: {0}")]
+    Yaml(serde_yaml_ng::Error),
+
+    #[error("Failed to parse JSON: {0}")]
+    ParseJson(serde_json::Error),
+
+    #[error("Unsupported configuration format '{0}', expected toml, yaml, yml or json")]
+    UnsupportedFormat(String),
+
     #[error("Failed to serialize TOML: {0}")]
     Serialize(#[from] toml::ser::Error),
 
+    #[error("Failed to serialize JSON: {0}")]
+    Json(serde_json::Error),
+
     #[error("Configuration file not found: {0}")]
     NotFound(String),
 
     #[error("Invalid configuration path: {0}")]
     InvalidPath(String),
 
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+
+    #[error("Invalid configuration: {}", .0.join("; "))]
+    Validation(Vec<String>),
+
+    #[error("Environment variable {0} is referenced by the config but not set")]
+    MissingEnvVar(String),
+
+    #[error("Failed to watch configuration file: {0}")]
+    Watch(#[from] notify::Error),
+
     #[error("Config already initialized")] 
     AlreadyInitialized,
 }
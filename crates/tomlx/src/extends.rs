@@ -0,0 +1,79 @@
+use crate::error::{Result, TomlConfigError};
+use toml::{Table, Value};
+
+/// Key naming another table, by dotted path, whose keys this table inherits
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Expand every `extends = "<table.path>"` key in `root`
+///
+/// The referenced table is copied as a base and the extending table's own keys are
+/// overlaid on it, recursively for nested tables. Chains are followed and cycles rejected.
+pub(crate) fn resolve_extends(root: &Table) -> Result<Table> {
+    expand(root, "", root, &mut Vec::new())
+}
+
+fn expand(root: &Table, path: &str, table: &Table, chain: &mut Vec<String>) -> Result<Table> {
+    let mut own = Table::new();
+    for (key, value) in table {
+        if key == EXTENDS_KEY {
+            continue;
+        }
+        let value = match value {
+            Value::Table(child) => Value::Table(expand(root, &join(path, key), child, chain)?),
+            other => other.clone(),
+        };
+        own.insert(key.clone(), value);
+    }
+
+    let Some(target) = table.get(EXTENDS_KEY) else {
+        return Ok(own);
+    };
+    let target = target
+        .as_str()
+        .ok_or_else(|| TomlConfigError::Invalid(format!("{} must be a table path string", join(path, EXTENDS_KEY))))?;
+
+    if chain.iter().any(|p| p == path) {
+        chain.push(path.to_string());
+        return Err(TomlConfigError::Invalid(format!("extends cycle: {}", chain.join(" -> "))));
+    }
+    chain.push(path.to_string());
+    let base = expand(root, target, lookup(root, path, target)?, chain)?;
+    chain.pop();
+
+    Ok(overlay(base, own))
+}
+
+fn lookup<'a>(root: &'a Table, from: &str, target: &str) -> Result<&'a Table> {
+    let mut table = root;
+    for segment in target.split('.') {
+        table = table
+            .get(segment)
+            .and_then(Value::as_table)
+            .ok_or_else(|| TomlConfigError::Invalid(format!("{from} extends '{target}', which is not a table")))?;
+    }
+    Ok(table)
+}
+
+/// Overlay `own` on `base`, merging tables present in both
+pub(crate) fn overlay(mut base: Table, own: Table) -> Table {
+    for (key, value) in own {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_child)), Value::Table(own_child)) => {
+                let merged = overlay(std::mem::take(base_child), own_child);
+                *base_child = merged;
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+    base
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
@@ -0,0 +1,25 @@
+use crate::error::{Result, TomlConfigError};
+use std::path::Path;
+
+/// Syntax of a configuration file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FileFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl FileFormat {
+    /// Format for `path`; files without an extension are read as TOML
+    pub(crate) fn from_path(path: &Path) -> Result<Self> {
+        let Some(ext) = path.extension() else {
+            return Ok(FileFormat::Toml);
+        };
+        match ext.to_string_lossy().to_ascii_lowercase().as_str() {
+            "toml" => Ok(FileFormat::Toml),
+            "yaml" | "yml" => Ok(FileFormat::Yaml),
+            "json" => Ok(FileFormat::Json),
+            other => Err(TomlConfigError::UnsupportedFormat(other.to_string())),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::{Result, TomlConfigError};
+use toml::{Table, Value};
+
+/// Expand `${VAR}` and `${VAR:-default}` in one string against the process environment
+///
+/// The default applies when the variable is unset or empty; `$${` produces a literal `${`.
+/// Any other `$` is kept as written.
+pub fn interpolate_env(content: &str) -> Result<String> {
+    interpolate_with(content, |name| std::env::var(name).ok())
+}
+
+/// Expand the env references in every string value of a parsed `table`
+///
+/// Keys and comments are left alone, and an expanded value is never parsed again, so it
+/// may hold quotes, backslashes or newlines.
+pub(crate) fn interpolate_table(table: &mut Table) -> Result<()> {
+    table.iter_mut().try_for_each(|(_, value)| interpolate_value(value))
+}
+
+fn interpolate_value(value: &mut Value) -> Result<()> {
+    match value {
+        Value::String(s) if s.contains('$') => *s = interpolate_env(s)?,
+        Value::Array(items) => items.iter_mut().try_for_each(interpolate_value)?,
+        Value::Table(table) => interpolate_table(table)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_with(content: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(after) = after.strip_prefix("${") {
+            expanded.push_str("${");
+            rest = after;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let end = body
+                .find('}')
+                .ok_or_else(|| TomlConfigError::Invalid(format!("unterminated '${{' in '${{{}'", first_line(body))))?;
+            let (name, default) = match body[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&body[..end], None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(TomlConfigError::Invalid(format!("invalid environment variable name '{name}'")));
+            }
+
+            let value = match (lookup(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+                (Some(value), _) => value,
+                (None, Some(default)) => default.to_string(),
+                (None, None) => return Err(TomlConfigError::MissingEnvVar(name.to_string())),
+            };
+            expanded.push_str(&value);
+            rest = &body[end + 1..];
+        } else {
+            // A lone `$` is kept as written
+            expanded.push('$');
+            rest = after;
+        }
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or_default()
+}
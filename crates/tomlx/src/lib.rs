@@ -1,8 +1,28 @@
 mod error;
+mod extends;
+mod format;
+mod interpolate;
 mod loader;
+mod log_level;
+mod print;
+mod redact;
+mod save;
+mod validate;
+mod watch;
 
 pub use error::{TomlConfigError, Result};
-pub use loader::{load_config_from_path, load_config_from_str};
+pub use extends::EXTENDS_KEY;
+pub use interpolate::interpolate_env;
+pub use loader::{
+    load_config_from_path, load_config_from_reader, load_config_from_slice, load_config_from_str, load_config_merged,
+    load_config_redacted, load_config_validated,
+};
+pub use log_level::LogLevel;
+pub use print::{CONFIG_PRINT_FORMAT_ENV, PrintFormat, render_config, render_config_with, to_toml_string};
+pub use redact::{DEFAULT_REDACT_KEYS, REDACT_KEYS_KEY, REDACTED, Redactor, redact_config, redact_json};
+pub use save::{save_config, save_config_commented};
+pub use validate::Validate;
+pub use watch::{ConfigWatcher, WATCH_DEBOUNCE, watch_config};
 
 pub fn load_config<T>(path: impl AsRef<std::path::Path>, if_print: bool) -> Result<T> where T: serde::de::DeserializeOwned + serde::Serialize {
     load_config_from_path(path, if_print)
@@ -1,33 +1,217 @@
 use crate::error::{Result, TomlConfigError};
+use crate::extends::{overlay, resolve_extends};
+use crate::format::FileFormat;
+use crate::interpolate::interpolate_table;
+use crate::print::{PrintFormat, render_config_with};
+use crate::redact::Redactor;
+use crate::validate::Validate;
+use serde::Deserialize;
 use std::path::Path;
 
-pub fn load_config_from_path<T>(path: impl AsRef<Path>, if_print: bool) -> Result<T> where T: serde::de::DeserializeOwned + serde::Serialize {
+/// Load a configuration file, choosing the parser by extension
+///
+/// `.toml` (or no extension), `.yaml`/`.yml` and `.json` are accepted; the other formats
+/// go through the same `extends`, env expansion and redaction as TOML.
+pub fn load_config_from_path<T>(path: impl AsRef<Path>, if_print: bool) -> Result<T>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
     let path = path.as_ref();
+    let (config, redactor) = load_with_redactor::<T>(path)?;
+
+    if if_print {
+        print_loaded(&path.display().to_string(), &config, &redactor);
+    }
     
-    if !path.exists() {
-        return Err(TomlConfigError::NotFound(
-            path.display().to_string(),
-        ));
+    Ok(config)
+}
+
+/// Load a configuration and run its [`Validate`] checks
+///
+/// All problems are returned together in [`TomlConfigError::Validation`].
+pub fn load_config_validated<T>(path: impl AsRef<Path>, if_print: bool) -> Result<T>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize + Validate,
+{
+    let config: T = load_config_from_path(path, if_print)?;
+    config.validate().map_err(TomlConfigError::Validation)?;
+    Ok(config)
+}
+
+/// Load several files as one configuration, later files overriding earlier ones
+///
+/// Tables present in more than one file are merged key by key, recursively. Any other
+/// value, arrays included, is replaced as a whole by the later file rather than
+/// concatenated. `extends` keys are resolved after merging, so an override file can
+/// extend tables defined in the base. Each file's env references are expanded first.
+pub fn load_config_merged<T>(paths: &[&Path], if_print: bool) -> Result<T>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    if paths.is_empty() {
+        return Err(TomlConfigError::InvalidPath("no configuration files given".to_string()));
+    }
+    let mut merged = toml::Table::new();
+    for path in paths {
+        merged = overlay(merged, read_table(path)?);
     }
 
-    let content = std::fs::read_to_string(path).map_err(|e| TomlConfigError::Io(e))?;
-    let config: T = load_config_from_str(&content)?;
-    
+    let table = resolve_extends(&merged)?;
+    let redactor = Redactor::from_table(&table);
+    let config: T = toml::Value::Table(table).try_into().map_err(TomlConfigError::Parse)?;
+
     if if_print {
-        match serde_json::to_string_pretty(&config) {
-            Ok(json) => {
-                println!("Loaded configuration from {} (as JSON):\n{}", path.display(), json);
-            }
-            Err(e) => {
-                println!("Loaded configuration from {} (failed to serialize as JSON: {}):\n{}", path.display(), e, content);
-            }
-        }
+        let sources: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+        print_loaded(&sources.join(" + "), &config, &redactor);
     }
-    
+
     Ok(config)
 }
 
+/// Load a configuration and render it in `format` with sensitive values masked
+///
+/// Only the rendering is redacted; the returned config keeps the real values.
+pub fn load_config_redacted<T>(path: impl AsRef<Path>, format: PrintFormat) -> Result<(T, String)>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    let (config, redactor) = load_with_redactor::<T>(path.as_ref())?;
+    let rendered = render_config_with(&config, format, &redactor)?;
+    Ok((config, rendered))
+}
+
 pub fn load_config_from_str<T>(content: &str) -> Result<T> where T: serde::de::DeserializeOwned {
-    let config: T = toml::from_str(content).map_err(|e| TomlConfigError::Parse(e))?;
+    let table = parse_and_resolve(content)?;
+    let config: T = toml::Value::Table(table).try_into().map_err(TomlConfigError::Parse)?;
     Ok(config)
 }
+
+/// Load a configuration from raw bytes, such as a config embedded in the binary
+pub fn load_config_from_slice<T>(bytes: &[u8]) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    load_config_from_str(std::str::from_utf8(bytes)?)
+}
+
+/// Load a configuration read to the end from `reader`, such as a network response body
+pub fn load_config_from_reader<T, R>(mut reader: R) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    load_config_from_slice(&bytes)
+}
+
+/// Parse `path` along with the redactor built from its `redact_keys`
+pub(crate) fn load_with_redactor<T>(path: &Path) -> Result<(T, Redactor)>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let table = resolve_extends(&read_table(path)?)?;
+    let redactor = Redactor::from_table(&table);
+    let config: T = toml::Value::Table(table).try_into().map_err(TomlConfigError::Parse)?;
+    Ok((config, redactor))
+}
+
+fn print_loaded<T>(source: &str, config: &T, redactor: &Redactor)
+where
+    T: serde::Serialize,
+{
+    let format = PrintFormat::from_env();
+    match render_config_with(config, format, redactor) {
+        Ok(rendered) => {
+            println!(
+                "Loaded configuration from {} (as {}):\n{}",
+                source,
+                format.as_str().to_uppercase(),
+                rendered
+            );
+        }
+        Err(e) => {
+            println!(
+                "Loaded configuration from {} (failed to render as {}: {})",
+                source,
+                format.as_str().to_uppercase(),
+                e
+            );
+        }
+    }
+}
+
+/// Read `path` in the format of its extension with env references expanded, leaving `extends` unresolved
+fn read_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Err(TomlConfigError::NotFound(path.display().to_string()));
+    }
+    let format = FileFormat::from_path(path)?;
+
+    let content = std::fs::read_to_string(path).map_err(TomlConfigError::Io)?;
+    let mut table = match format {
+        FileFormat::Toml => content.parse().map_err(|e| parse_error_at(path, &content, e))?,
+        FileFormat::Yaml => table_without_nulls(serde_yaml_ng::from_str(&content).map_err(TomlConfigError::Yaml)?)?,
+        FileFormat::Json => table_without_nulls(serde_json::from_str(&content).map_err(TomlConfigError::ParseJson)?)?,
+    };
+    interpolate_table(&mut table)?;
+    Ok(table)
+}
+
+/// Convert a YAML or JSON document to a table, dropping the `null` values TOML cannot hold
+///
+/// A key set to `null` reads the same as a key that is not there.
+fn table_without_nulls(mut document: serde_json::Value) -> Result<toml::Table> {
+    strip_nulls(&mut document);
+    toml::Table::deserialize(document).map_err(TomlConfigError::ParseJson)
+}
+
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => {
+            items.retain(|v| !v.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+/// Attach the file, position and offending line to a parse error that carries a span
+fn parse_error_at(path: &Path, content: &str, e: toml::de::Error) -> TomlConfigError {
+    let Some(span) = e.span() else {
+        return TomlConfigError::Parse(e);
+    };
+    let start = span.start.min(content.len());
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[start..].find('\n').map_or(content.len(), |i| start + i);
+    let line = content[..start].matches('\n').count() + 1;
+    let col = content[line_start..start].chars().count() + 1;
+
+    let text = content[line_start..line_end].trim_end_matches('\r');
+    let gutter = " ".repeat(line.to_string().len());
+    let message = format!(
+        "{}\n{gutter} |\n{line} | {text}\n{gutter} | {}^",
+        e.message().trim_end(),
+        " ".repeat(col - 1)
+    );
+    TomlConfigError::ParseAt {
+        path: path.display().to_string(),
+        line,
+        col,
+        message,
+    }
+}
+
+fn parse_table(content: &str) -> Result<toml::Table> {
+    let mut table = content.parse().map_err(TomlConfigError::Parse)?;
+    interpolate_table(&mut table)?;
+    Ok(table)
+}
+
+fn parse_and_resolve(content: &str) -> Result<toml::Table> {
+    resolve_extends(&parse_table(content)?)
+}
@@ -0,0 +1,97 @@
+use crate::error::{Result, TomlConfigError};
+use crate::redact::Redactor;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable selecting the format used when printing a loaded configuration
+pub const CONFIG_PRINT_FORMAT_ENV: &str = "NEBULAFX_CONFIG_PRINT_FORMAT";
+
+/// Representation used when printing a loaded configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrintFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl PrintFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrintFormat::Json => "json",
+            PrintFormat::Toml => "toml",
+        }
+    }
+
+    /// Read the format from `NEBULAFX_CONFIG_PRINT_FORMAT`, defaulting to JSON when unset or invalid
+    pub fn from_env() -> Self {
+        std::env::var(CONFIG_PRINT_FORMAT_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for PrintFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PrintFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(PrintFormat::Json),
+            "toml" => Ok(PrintFormat::Toml),
+            other => Err(format!("invalid print format '{other}', expected one of: json, toml")),
+        }
+    }
+}
+
+/// Serialize a value as pretty-printed TOML
+pub fn to_toml_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    Ok(toml::to_string_pretty(value)?)
+}
+
+/// Render a configuration in the given format with secret values redacted
+pub fn render_config<T>(config: &T, format: PrintFormat) -> Result<String>
+where
+    T: Serialize,
+{
+    render_config_with(config, format, &Redactor::default())
+}
+
+/// Render a configuration in the given format, redacting the keys `redactor` matches
+pub fn render_config_with<T>(config: &T, format: PrintFormat, redactor: &Redactor) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut value = serde_json::to_value(config).map_err(TomlConfigError::Json)?;
+    redactor.redact_json(&mut value);
+
+    match format {
+        PrintFormat::Json => serde_json::to_string_pretty(&value).map_err(TomlConfigError::Json),
+        PrintFormat::Toml => {
+            // TOML has no null, so unset options are omitted as they would be in the source file
+            strip_nulls(&mut value);
+            to_toml_string(&value)
+        }
+    }
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
@@ -0,0 +1,142 @@
+/// Replacement for secret values in printed configuration
+pub const REDACTED: &str = "****";
+
+/// Top-level config key listing extra sensitive key names
+pub const REDACT_KEYS_KEY: &str = "redact_keys";
+
+/// Key names that mark a value as secret unless the config adds more
+pub const DEFAULT_REDACT_KEYS: [&str; 4] = ["password", "secret", "key", "token"];
+
+/// Replaces the values of sensitive keys in a configuration tree
+///
+/// A key is sensitive when it ends with one of the configured names, compared segment by
+/// segment, so `secret_key`, `DB_PASSWORD` and `AccessToken` match but `key_file` does not.
+/// Tables are walked rather than replaced, so a `[secrets]` section keeps its layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redactor {
+    fragments: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::with_keys(std::iter::empty::<&str>())
+    }
+}
+
+impl Redactor {
+    /// Redact the default key fragments plus `extra`
+    pub fn with_keys<I, S>(extra: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut fragments: Vec<String> = DEFAULT_REDACT_KEYS.iter().map(|k| k.to_string()).collect();
+        for key in extra {
+            let key = key.as_ref().trim().to_ascii_lowercase();
+            if !key.is_empty() && !fragments.contains(&key) {
+                fragments.push(key);
+            }
+        }
+        Self { fragments }
+    }
+
+    /// Build from the `redact_keys` array at the top of a config table, ignoring non-string entries
+    pub fn from_table(table: &toml::Table) -> Self {
+        let extra = table
+            .get(REDACT_KEYS_KEY)
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_str);
+        Self::with_keys(extra)
+    }
+
+    /// Lowercased key names this redactor matches
+    pub fn keys(&self) -> &[String] {
+        &self.fragments
+    }
+
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        let segments = key_segments(key);
+        self.fragments.iter().any(|fragment| {
+            let fragment = key_segments(fragment);
+            !fragment.is_empty() && segments.ends_with(&fragment)
+        })
+    }
+
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        use serde_json::Value;
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if key == REDACT_KEYS_KEY {
+                        continue;
+                    }
+                    if self.is_sensitive(key) && !value.is_null() && !value.is_object() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_json(item)),
+            _ => {}
+        }
+    }
+
+    pub fn redact_toml(&self, value: &mut toml::Value) {
+        use toml::Value;
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table.iter_mut() {
+                    // The key list itself names fields, it holds no secrets
+                    if key == REDACT_KEYS_KEY {
+                        continue;
+                    }
+                    if self.is_sensitive(key) && !value.is_table() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_toml(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_toml(item)),
+            _ => {}
+        }
+    }
+}
+
+/// Lowercase words of `key`, split at `_`, `-`, `.` and camelCase boundaries
+fn key_segments(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut after_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                segments.push(std::mem::take(&mut current));
+            }
+            after_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && after_lower {
+            segments.push(std::mem::take(&mut current));
+        }
+        after_lower = !c.is_uppercase();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Redact a JSON config tree with the default sensitive keys
+pub fn redact_json(value: &mut serde_json::Value) {
+    Redactor::default().redact_json(value)
+}
+
+/// Redact a TOML config tree with the default sensitive keys
+pub fn redact_config(value: &mut toml::Value) {
+    Redactor::default().redact_toml(value)
+}
@@ -0,0 +1,99 @@
+use crate::error::{Result, TomlConfigError};
+use crate::print::to_toml_string;
+use std::io::Write;
+use std::path::Path;
+
+/// Serialize `config` as pretty TOML and write it to `path` atomically
+///
+/// The content goes to a temporary file next to `path` that is synced and then renamed
+/// over it, so a crash leaves either the old file or the new one, never a truncated
+/// one. An existing file keeps its permissions.
+pub fn save_config<T>(path: impl AsRef<Path>, config: &T) -> Result<()>
+where
+    T: serde::Serialize,
+{
+    write_atomic(path.as_ref(), &to_toml_string(config)?)
+}
+
+/// Like [`save_config`], with `header` at the top of the file and a comment above each key
+///
+/// `comments` maps dotted key paths such as `server.port` to their comment; a table's own
+/// path, such as `server`, comments its header. Multi-line comments become one `#` line each.
+pub fn save_config_commented<T>(path: impl AsRef<Path>, config: &T, header: &str, comments: &[(&str, &str)]) -> Result<()>
+where
+    T: serde::Serialize,
+{
+    let mut content = String::new();
+    push_comment(&mut content, header);
+    if !header.is_empty() {
+        content.push('\n');
+    }
+    content.push_str(&annotate(&to_toml_string(config)?, comments));
+    write_atomic(path.as_ref(), &content)
+}
+
+/// Insert the comment for each table header and top-level key line of pretty TOML output
+fn annotate(content: &str, comments: &[(&str, &str)]) -> String {
+    let comment_for = |key: &str| comments.iter().find(|(k, _)| *k == key).map(|(_, c)| *c);
+    let mut table = String::new();
+    let mut annotated = String::with_capacity(content.len());
+
+    for line in content.lines() {
+        // Array elements and continuation lines are indented; keys and headers are not
+        let key = if line.starts_with(' ') || line.starts_with('#') {
+            None
+        } else if let Some(header) = line.strip_prefix('[') {
+            table = header.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            Some(table.clone())
+        } else if let Some((key, _)) = line.split_once('=') {
+            let key = key.trim().trim_matches('"');
+            Some(if table.is_empty() {
+                key.to_string()
+            } else {
+                format!("{table}.{key}")
+            })
+        } else {
+            None
+        };
+
+        if let Some(comment) = key.as_deref().and_then(comment_for) {
+            push_comment(&mut annotated, comment);
+        }
+        annotated.push_str(line);
+        annotated.push('\n');
+    }
+    annotated
+}
+
+fn push_comment(out: &mut String, comment: &str) {
+    for line in comment.lines() {
+        out.push_str(if line.is_empty() { "#" } else { "# " });
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| TomlConfigError::InvalidPath(path.display().to_string()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp-{}", file_name.to_string_lossy(), std::process::id()));
+
+    let written = write_synced(&tmp_path, content.as_bytes()).and_then(|()| {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&tmp_path, metadata.permissions())?;
+        }
+        std::fs::rename(&tmp_path, path)
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(TomlConfigError::Io(e));
+    }
+    Ok(())
+}
+
+fn write_synced(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
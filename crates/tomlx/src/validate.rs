@@ -0,0 +1,7 @@
+/// Checks a loaded configuration must pass before it is used
+///
+/// Implementations report every problem rather than stopping at the first, so one
+/// failed start lists everything that needs fixing.
+pub trait Validate {
+    fn validate(&self) -> std::result::Result<(), Vec<String>>;
+}
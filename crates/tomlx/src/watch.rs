@@ -0,0 +1,92 @@
+use crate::error::Result;
+use crate::loader::load_with_redactor;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Quiet period after the last change before the file is re-parsed
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Handle for a running [`watch_config`]; watching stops when it is dropped
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    path: PathBuf,
+}
+
+impl ConfigWatcher {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl std::fmt::Debug for ConfigWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigWatcher").field("path", &self.path).finish()
+    }
+}
+
+/// Re-parse the config at `path` whenever it changes and pass the result to `on_change`
+///
+/// Changes are debounced by [`WATCH_DEBOUNCE`], so an editor's burst of writes yields one
+/// reload. The parent directory is watched rather than the file itself, which keeps
+/// working when editors replace the file through a rename. A file that fails to load
+/// is logged and skipped; the watcher keeps running and picks up the next change.
+/// The callback runs on a dedicated thread and is not invoked for the initial contents.
+pub fn watch_config<T, F>(path: impl AsRef<Path>, on_change: F) -> Result<ConfigWatcher>
+where
+    T: serde::de::DeserializeOwned,
+    F: Fn(T) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path.file_name().map(|name| name.to_os_string());
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+        Ok(event) => {
+            let relevant = (event.kind.is_create() || event.kind.is_modify())
+                && event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+            if relevant {
+                // The receiver only goes away once the watcher is dropped
+                let _ = tx.send(());
+            }
+        }
+        Err(e) => warn!("config watch error: {}", e),
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    let reload_path = path.clone();
+    std::thread::Builder::new()
+        .name("tomlx-config-watch".to_string())
+        .spawn(move || {
+            // Ends when the watcher, and with it the sender, is dropped
+            while rx.recv().is_ok() {
+                loop {
+                    match rx.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                match load_with_redactor::<T>(&reload_path) {
+                    Ok((config, _)) => {
+                        info!("reloaded configuration from {}", reload_path.display());
+                        on_change(config);
+                    }
+                    Err(e) => {
+                        warn!("ignoring change to {}: {}", reload_path.display(), e);
+                    }
+                }
+            }
+        })?;
+
+    Ok(ConfigWatcher { _watcher: watcher, path })
+}
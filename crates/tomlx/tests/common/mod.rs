@@ -0,0 +1,39 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Each test binary uses only part of this module
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// Config files written to a per-test directory that is removed on drop
+pub struct ConfigDir(TempDir);
+
+impl ConfigDir {
+    pub fn new() -> Self {
+        Self(tempfile::Builder::new().prefix("nebulafx-tomlx-").tempdir().unwrap())
+    }
+
+    /// Path of `name` in the directory, whether or not it exists yet
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.0.path().join(name)
+    }
+
+    pub fn write(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nebulafx_tomlx::{TomlConfigError, load_config_from_str};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ListenersConfig {
+    listeners: Listeners,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Listeners {
+    public: Listener,
+    internal: Listener,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Listener {
+    host: String,
+    port: u16,
+    tls: Tls,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Tls {
+    enabled: bool,
+    cert_file: String,
+}
+
+#[test]
+fn test_table_extends_another_and_overrides_a_field() {
+    let toml = r#"
+        [listeners.public]
+        host = "0.0.0.0"
+        port = 9000
+        [listeners.public.tls]
+        enabled = true
+        cert_file = "public.pem"
+
+        [listeners.internal]
+        extends = "listeners.public"
+        port = 9001
+        [listeners.internal.tls]
+        cert_file = "internal.pem"
+    "#;
+
+    let config: ListenersConfig = load_config_from_str(toml).unwrap();
+    let internal = config.listeners.internal;
+    assert_eq!(internal.host, "0.0.0.0");
+    assert_eq!(internal.port, 9001);
+    // Nested tables are merged rather than replaced
+    assert!(internal.tls.enabled);
+    assert_eq!(internal.tls.cert_file, "internal.pem");
+    assert_eq!(config.listeners.public.port, 9000);
+}
+
+#[test]
+fn test_extends_chains_are_followed() {
+    let toml = r#"
+        [base]
+        host = "10.0.0.1"
+        port = 1
+
+        [middle]
+        extends = "base"
+        port = 2
+
+        [leaf]
+        extends = "middle"
+    "#;
+
+    #[derive(Debug, Deserialize)]
+    struct Chain {
+        leaf: Endpoint,
+    }
+    #[derive(Debug, Deserialize)]
+    struct Endpoint {
+        host: String,
+        port: u16,
+    }
+
+    let config: Chain = load_config_from_str(toml).unwrap();
+    assert_eq!(config.leaf.host, "10.0.0.1");
+    assert_eq!(config.leaf.port, 2);
+}
+
+#[test]
+fn test_extends_cycle_is_rejected() {
+    let toml = r#"
+        [a]
+        extends = "b"
+        [b]
+        extends = "a"
+    "#;
+
+    match load_config_from_str::<toml::Table>(toml) {
+        Err(TomlConfigError::Invalid(msg)) => assert_eq!(msg, "extends cycle: a -> b -> a"),
+        other => panic!("expected cycle error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_extends_unknown_table_is_rejected() {
+    let err = load_config_from_str::<toml::Table>("[a]\nextends = \"missing\"\n").unwrap_err();
+    assert!(err.to_string().contains("a extends 'missing', which is not a table"));
+}
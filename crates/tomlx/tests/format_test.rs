@@ -0,0 +1,127 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{TomlConfigError, load_config_from_path};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct TestConfig {
+    name: String,
+    server: ServerConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct ServerConfig {
+    host: String,
+    port: u16,
+    tls: bool,
+    domains: Vec<String>,
+}
+
+const TOML: &str = r#"
+name = "nebulafx"
+
+[server]
+host = "0.0.0.0"
+port = 9000
+tls = true
+domains = ["a.example.com", "b.example.com"]
+"#;
+
+const YAML: &str = r#"
+name: nebulafx
+server:
+  host: 0.0.0.0
+  port: 9000
+  tls: true
+  domains:
+    - a.example.com
+    - b.example.com
+"#;
+
+const JSON: &str = r#"{
+  "name": "nebulafx",
+  "server": {
+    "host": "0.0.0.0",
+    "port": 9000,
+    "tls": true,
+    "domains": ["a.example.com", "b.example.com"]
+  }
+}"#;
+
+#[test]
+fn test_same_config_from_every_format() {
+    let dir = ConfigDir::new();
+    let from_toml: TestConfig = load_config_from_path(dir.write("config.toml", TOML), false).unwrap();
+    let from_yaml: TestConfig = load_config_from_path(dir.write("config.yaml", YAML), false).unwrap();
+    let from_yml: TestConfig = load_config_from_path(dir.write("config.yml", YAML), false).unwrap();
+    let from_json: TestConfig = load_config_from_path(dir.write("config.json", JSON), false).unwrap();
+
+    assert_eq!(from_toml.server.domains.len(), 2);
+    assert_eq!(from_yaml, from_toml);
+    assert_eq!(from_yml, from_toml);
+    assert_eq!(from_json, from_toml);
+}
+
+#[test]
+fn test_extension_is_case_insensitive() {
+    let dir = ConfigDir::new();
+    let config: TestConfig = load_config_from_path(dir.write("config.YAML", YAML), false).unwrap();
+    assert_eq!(config.server.port, 9000);
+}
+
+#[test]
+fn test_unsupported_extension_rejected() {
+    let dir = ConfigDir::new();
+    let result: Result<TestConfig, TomlConfigError> = load_config_from_path(dir.write("config.ini", TOML), false);
+    match result {
+        Err(TomlConfigError::UnsupportedFormat(ext)) => assert_eq!(ext, "ini"),
+        other => panic!("Expected UnsupportedFormat error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_null_values_read_as_missing() {
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Optional {
+        name: String,
+        region: Option<String>,
+        tags: Vec<String>,
+    }
+
+    let dir = ConfigDir::new();
+    let from_yaml: Optional =
+        load_config_from_path(dir.write("config.yaml", "name: nebulafx\nregion: ~\ntags: [a, null]\nextra:\n"), false).unwrap();
+    assert_eq!(
+        (from_yaml.name.as_str(), from_yaml.region, from_yaml.tags),
+        ("nebulafx", None, vec!["a".to_string()])
+    );
+
+    let from_json: Optional =
+        load_config_from_path(dir.write("config.json", r#"{"name": "nebulafx", "region": null, "tags": []}"#), false).unwrap();
+    assert_eq!(from_json.region, None);
+}
+
+#[test]
+fn test_invalid_yaml_and_json_reported() {
+    let dir = ConfigDir::new();
+    let result: Result<TestConfig, TomlConfigError> = load_config_from_path(dir.write("config.yaml", "server: [\n"), false);
+    assert!(matches!(result, Err(TomlConfigError::Yaml(_))), "got {result:?}");
+
+    let result: Result<TestConfig, TomlConfigError> = load_config_from_path(dir.write("config.json", "{\"server\": "), false);
+    assert!(matches!(result, Err(TomlConfigError::ParseJson(_))), "got {result:?}");
+}
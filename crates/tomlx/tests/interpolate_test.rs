@@ -0,0 +1,122 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nebulafx_tomlx::{TomlConfigError, interpolate_env, load_config_from_str};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct DatabaseConfig {
+    database: Database,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Database {
+    host: String,
+    password: String,
+}
+
+const CONFIG: &str = r#"
+[database]
+host = "${NEBULAFX_TOMLX_TEST_HOST:-localhost}"
+password = "${NEBULAFX_TOMLX_TEST_PASSWORD}"
+"#;
+
+#[test]
+fn test_env_vars_are_expanded() {
+    temp_env::with_vars(
+        [
+            ("NEBULAFX_TOMLX_TEST_HOST", Some("db.internal")),
+            ("NEBULAFX_TOMLX_TEST_PASSWORD", Some("hunter2")),
+        ],
+        || {
+            let config: DatabaseConfig = load_config_from_str(CONFIG).unwrap();
+            assert_eq!(config.database.host, "db.internal");
+            assert_eq!(config.database.password, "hunter2");
+        },
+    );
+}
+
+#[test]
+fn test_defaults_apply_when_unset_or_empty() {
+    temp_env::with_vars(
+        [
+            ("NEBULAFX_TOMLX_TEST_HOST", None),
+            ("NEBULAFX_TOMLX_TEST_PASSWORD", Some("hunter2")),
+        ],
+        || {
+            let config: DatabaseConfig = load_config_from_str(CONFIG).unwrap();
+            assert_eq!(config.database.host, "localhost");
+        },
+    );
+    temp_env::with_var("NEBULAFX_TOMLX_TEST_EMPTY", Some(""), || {
+        assert_eq!(interpolate_env("${NEBULAFX_TOMLX_TEST_EMPTY:-fallback}").unwrap(), "fallback");
+        assert_eq!(interpolate_env("${NEBULAFX_TOMLX_TEST_EMPTY}").unwrap(), "");
+    });
+}
+
+#[test]
+fn test_missing_variable_without_default_is_an_error() {
+    temp_env::with_var_unset("NEBULAFX_TOMLX_TEST_PASSWORD", || {
+        let err = load_config_from_str::<DatabaseConfig>(CONFIG).unwrap_err();
+        assert!(matches!(err, TomlConfigError::MissingEnvVar(ref name) if name == "NEBULAFX_TOMLX_TEST_PASSWORD"));
+    });
+}
+
+#[test]
+fn test_escaped_reference_and_other_dollars_are_kept() {
+    temp_env::with_var_unset("NEBULAFX_TOMLX_TEST_UNSET", || {
+        assert_eq!(
+            interpolate_env("$${NEBULAFX_TOMLX_TEST_UNSET:-x}").unwrap(),
+            "${NEBULAFX_TOMLX_TEST_UNSET:-x}"
+        );
+        assert_eq!(interpolate_env("pa$$word costs $5").unwrap(), "pa$$word costs $5");
+    });
+}
+
+#[test]
+fn test_values_with_toml_syntax_are_inserted_as_is() {
+    let password = "pa\"ss\\word\nhost = \"evil\"";
+    temp_env::with_vars(
+        [
+            ("NEBULAFX_TOMLX_TEST_HOST", Some("db.internal")),
+            ("NEBULAFX_TOMLX_TEST_PASSWORD", Some(password)),
+        ],
+        || {
+            let config: DatabaseConfig = load_config_from_str(CONFIG).unwrap();
+            assert_eq!(config.database.host, "db.internal");
+            assert_eq!(config.database.password, password);
+        },
+    );
+}
+
+#[test]
+fn test_references_outside_values_are_not_expanded() {
+    temp_env::with_vars(
+        [
+            ("NEBULAFX_TOMLX_TEST_PASSWORD", Some("hunter2")),
+            ("NEBULAFX_TOMLX_TEST_UNSET", None),
+        ],
+        || {
+            let content = format!("# password = \"${{NEBULAFX_TOMLX_TEST_UNSET}}\"\n{CONFIG}");
+            let config: DatabaseConfig = load_config_from_str(&content).unwrap();
+            assert_eq!(config.database.password, "hunter2");
+        },
+    );
+}
+
+#[test]
+fn test_malformed_references_are_rejected() {
+    assert!(matches!(interpolate_env("a = \"${UNTERMINATED\""), Err(TomlConfigError::Invalid(_))));
+    assert!(matches!(interpolate_env("a = \"${BAD-NAME}\""), Err(TomlConfigError::Invalid(_))));
+}
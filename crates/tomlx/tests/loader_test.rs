@@ -12,8 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use nebulafx_tomlx::{load_config_from_str, TomlConfigError};
-use serde::Deserialize;
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{
+    TomlConfigError, load_config_from_path, load_config_from_reader, load_config_from_slice, load_config_from_str,
+};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, PartialEq)]
 struct TestConfig {
@@ -21,12 +26,17 @@ struct TestConfig {
     database: DatabaseConfig,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 struct ServerConfig {
     host: String,
     port: u16,
 }
 
+#[derive(Debug, Deserialize)]
+struct ServerOnly {
+    server: ServerConfig,
+}
+
 #[derive(Debug, Deserialize, PartialEq)]
 struct DatabaseConfig {
     host: String,
@@ -148,6 +158,60 @@ host = "0.0.0.0"  # missing closing bracket
     }
 }
 
+#[test]
+fn test_load_config_from_path_reports_parse_position() {
+    #[derive(Debug, Deserialize, Serialize)]
+    struct FileConfig {
+        server: ServerConfig,
+    }
+
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "[server]\nhost = \"0.0.0.0\"\nport = 90 00\n");
+
+    let result: Result<FileConfig, TomlConfigError> = load_config_from_path(&path, false);
+    match result {
+        Err(TomlConfigError::ParseAt {
+            path: reported,
+            line,
+            message,
+            ..
+        }) => {
+            assert_eq!(reported, path.display().to_string());
+            assert_eq!(line, 3);
+            assert!(message.contains("3 | port = 90 00"), "missing snippet in {message}");
+        }
+        other => panic!("Expected ParseAt error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_error_shows_the_file_as_written() {
+    #[derive(Debug, Deserialize, Serialize)]
+    struct FileConfig {
+        server: ServerConfig,
+    }
+
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "[server]\nhost = \"${NEBULAFX_TOMLX_TEST_SECRET_HOST}\" port = 9000\n");
+
+    let result: Result<FileConfig, TomlConfigError> =
+        temp_env::with_var("NEBULAFX_TOMLX_TEST_SECRET_HOST", Some("very-secret-value"), || {
+            load_config_from_path(&path, false)
+        });
+    match result {
+        Err(TomlConfigError::ParseAt { line, col, message, .. }) => {
+            // Counted on the line as written, before the reference is expanded
+            assert_eq!((line, col), (2, 45));
+            assert!(
+                message.contains("2 | host = \"${NEBULAFX_TOMLX_TEST_SECRET_HOST}\" port = 9000"),
+                "{message}"
+            );
+            assert!(!message.contains("very-secret-value"), "{message}");
+        }
+        other => panic!("Expected ParseAt error, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_load_config_error_missing_field() {
     let incomplete_toml = r#"
@@ -220,3 +284,39 @@ port = 6379
     assert_eq!(config.app.cache.port, 6379);
 }
 
+#[test]
+fn test_load_config_from_slice() {
+    let bytes = b"[server]\nhost = \"0.0.0.0\"\nport = 9000\n";
+    let config: ServerOnly = load_config_from_slice(bytes).unwrap();
+    assert_eq!(
+        config.server,
+        ServerConfig {
+            host: "0.0.0.0".to_string(),
+            port: 9000
+        }
+    );
+}
+
+#[test]
+fn test_load_config_from_reader() {
+    let reader = std::io::Cursor::new("[server]\nhost = \"127.0.0.1\"\nport = 9100\n");
+    let config: ServerOnly = load_config_from_reader(reader).unwrap();
+    assert_eq!(
+        config.server,
+        ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 9100
+        }
+    );
+}
+
+#[test]
+fn test_load_config_rejects_invalid_utf8() {
+    let bytes = b"[server]\nhost = \"\xff\xfe\"\nport = 9000\n";
+
+    let result: Result<ServerOnly, TomlConfigError> = load_config_from_slice(bytes);
+    assert!(matches!(result, Err(TomlConfigError::Encoding(_))), "got {result:?}");
+
+    let result: Result<ServerOnly, TomlConfigError> = load_config_from_reader(&bytes[..]);
+    assert!(matches!(result, Err(TomlConfigError::Encoding(_))), "got {result:?}");
+}
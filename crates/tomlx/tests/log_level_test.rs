@@ -0,0 +1,62 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nebulafx_tomlx::{LogLevel, TomlConfigError, load_config_from_str};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct LevelConfig {
+    logger_level: Option<LogLevel>,
+}
+
+#[test]
+fn test_log_level_valid_names() {
+    let cases = [
+        ("trace", LogLevel::Trace),
+        ("debug", LogLevel::Debug),
+        ("info", LogLevel::Info),
+        ("warn", LogLevel::Warn),
+        ("error", LogLevel::Error),
+    ];
+
+    for (name, expected) in cases {
+        let config: LevelConfig = load_config_from_str(&format!("logger_level = \"{name}\"")).unwrap();
+        assert_eq!(config.logger_level, Some(expected));
+        assert_eq!(expected.as_str(), name);
+    }
+}
+
+#[test]
+fn test_log_level_is_case_insensitive() {
+    let config: LevelConfig = load_config_from_str(r#"logger_level = "WARN""#).unwrap();
+    assert_eq!(config.logger_level, Some(LogLevel::Warn));
+
+    let config: LevelConfig = load_config_from_str(r#"logger_level = "Debug""#).unwrap();
+    assert_eq!(config.logger_level, Some(LogLevel::Debug));
+}
+
+#[test]
+fn test_log_level_missing_is_none() {
+    let config: LevelConfig = load_config_from_str("").unwrap();
+    assert_eq!(config.logger_level, None);
+}
+
+#[test]
+fn test_log_level_invalid_is_rejected() {
+    let result: Result<LevelConfig, TomlConfigError> = load_config_from_str(r#"logger_level = "trce""#);
+    match result {
+        Err(TomlConfigError::Parse(e)) => assert!(e.to_string().contains("invalid log level 'trce'")),
+        other => panic!("Expected Parse error, got {other:?}"),
+    }
+}
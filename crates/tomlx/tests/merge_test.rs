@@ -0,0 +1,87 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{TomlConfigError, load_config_merged};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TestConfig {
+    server: ServerConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ServerConfig {
+    host: String,
+    domains: Vec<String>,
+    listen: ListenConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ListenConfig {
+    address: String,
+    port: u16,
+}
+
+const BASE: &str = r#"
+[server]
+host = "0.0.0.0"
+domains = ["a.example", "b.example"]
+
+[server.listen]
+address = "0.0.0.0"
+port = 9000
+"#;
+
+#[test]
+fn test_override_changes_nested_port_and_keeps_siblings() {
+    let dir = ConfigDir::new();
+    let base = dir.write("config.toml", BASE);
+    let local = dir.write("config.local.toml", "[server.listen]\nport = 9100\n");
+
+    let config: TestConfig = load_config_merged(&[base.as_path(), local.as_path()], false).unwrap();
+    assert_eq!(config.server.listen.port, 9100);
+    assert_eq!(config.server.listen.address, "0.0.0.0");
+    assert_eq!(config.server.host, "0.0.0.0");
+}
+
+#[test]
+fn test_arrays_are_replaced_and_later_files_win() {
+    let dir = ConfigDir::new();
+    let base = dir.write("config.toml", BASE);
+    let first = dir.write("first.toml", "[server]\ndomains = [\"c.example\"]\nhost = \"10.0.0.1\"\n");
+    let second = dir.write("second.toml", "[server]\nhost = \"10.0.0.2\"\n");
+
+    let config: TestConfig = load_config_merged(&[base.as_path(), first.as_path(), second.as_path()], false).unwrap();
+    assert_eq!(config.server.domains, ["c.example"]);
+    assert_eq!(config.server.host, "10.0.0.2");
+}
+
+#[test]
+fn test_missing_file_or_empty_list_is_an_error() {
+    let dir = ConfigDir::new();
+    let base = dir.write("config.toml", BASE);
+    let missing = dir.join("absent.toml");
+
+    let err = load_config_merged::<TestConfig>(&[base.as_path(), missing.as_path()], false).unwrap_err();
+    assert!(matches!(err, TomlConfigError::NotFound(_)));
+    let no_paths: &[&Path] = &[];
+    assert!(matches!(
+        load_config_merged::<TestConfig>(no_paths, false),
+        Err(TomlConfigError::InvalidPath(_))
+    ));
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{PrintFormat, REDACTED, load_config_from_str, load_config_redacted, render_config};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct TestConfig {
+    server: ServerConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct ServerConfig {
+    host: String,
+    port: u16,
+    secret_key: Option<String>,
+    region: Option<String>,
+}
+
+fn sample() -> TestConfig {
+    TestConfig {
+        server: ServerConfig {
+            host: "0.0.0.0".to_string(),
+            port: 9000,
+            secret_key: Some("hunter2".to_string()),
+            region: None,
+        },
+    }
+}
+
+#[test]
+fn test_render_json_redacts_secrets() {
+    let rendered = render_config(&sample(), PrintFormat::Json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+    assert_eq!(value["server"]["host"], "0.0.0.0");
+    assert_eq!(value["server"]["secret_key"], REDACTED);
+    assert!(value["server"]["region"].is_null());
+    assert!(!rendered.contains("hunter2"));
+}
+
+#[test]
+fn test_render_toml_redacts_secrets_and_round_trips() {
+    let rendered = render_config(&sample(), PrintFormat::Toml).unwrap();
+    assert!(rendered.contains("[server]"));
+    assert!(!rendered.contains("hunter2"));
+
+    let parsed: TestConfig = load_config_from_str(&rendered).unwrap();
+    assert_eq!(parsed.server.port, 9000);
+    assert_eq!(parsed.server.secret_key.as_deref(), Some(REDACTED));
+    assert_eq!(parsed.server.region, None);
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct DatabaseFile {
+    database: Database,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Database {
+    host: String,
+    password: String,
+}
+
+#[test]
+fn test_loaded_config_prints_masked_but_keeps_values() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "[database]\nhost = \"db.internal\"\npassword = \"hunter2\"\n");
+    let loaded = load_config_redacted::<DatabaseFile>(&path, PrintFormat::Json);
+
+    let (config, rendered) = loaded.unwrap();
+    let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(value["database"]["password"], REDACTED);
+    assert_eq!(value["database"]["host"], "db.internal");
+    assert!(!rendered.contains("hunter2"));
+    assert_eq!(config.database.password, "hunter2");
+}
+
+#[test]
+fn test_print_format_parsing() {
+    assert_eq!("TOML".parse::<PrintFormat>().unwrap(), PrintFormat::Toml);
+    assert_eq!(" json ".parse::<PrintFormat>().unwrap(), PrintFormat::Json);
+    assert!("yaml".parse::<PrintFormat>().is_err());
+    assert_eq!(PrintFormat::default(), PrintFormat::Json);
+}
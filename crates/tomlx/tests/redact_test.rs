@@ -0,0 +1,132 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use nebulafx_tomlx::{REDACTED, Redactor, redact_config, redact_json};
+use serde_json::json;
+
+fn toml_value(source: &str) -> toml::Value {
+    toml::Value::Table(source.parse().unwrap())
+}
+
+#[test]
+fn test_redacts_nested_tables() {
+    let mut value = toml_value(
+        r#"
+        [server]
+        host = "0.0.0.0"
+        [server.auth]
+        root_password = "hunter2"
+        [server.auth.oidc]
+        client_secret = "abc"
+        "#,
+    );
+    redact_config(&mut value);
+
+    assert_eq!(value["server"]["host"].as_str(), Some("0.0.0.0"));
+    assert_eq!(value["server"]["auth"]["root_password"].as_str(), Some(REDACTED));
+    assert_eq!(value["server"]["auth"]["oidc"]["client_secret"].as_str(), Some(REDACTED));
+}
+
+#[test]
+fn test_redacts_arrays_of_tables() {
+    let mut value = toml_value(
+        r#"
+        [[notify.webhooks]]
+        endpoint = "https://a.example"
+        auth_token = "t1"
+
+        [[notify.webhooks]]
+        endpoint = "https://b.example"
+        auth_token = "t2"
+        "#,
+    );
+    redact_config(&mut value);
+
+    let hooks = value["notify"]["webhooks"].as_array().unwrap();
+    assert_eq!(hooks.len(), 2);
+    for hook in hooks {
+        assert_eq!(hook["auth_token"].as_str(), Some(REDACTED));
+        assert!(hook["endpoint"].as_str().unwrap().starts_with("https://"));
+    }
+}
+
+#[test]
+fn test_key_matching_ignores_case() {
+    let mut value = json!({ "DB_PASSWORD": "p", "Api": { "AccessToken": "t" }, "user": "admin" });
+    redact_json(&mut value);
+
+    assert_eq!(value["DB_PASSWORD"], REDACTED);
+    assert_eq!(value["Api"]["AccessToken"], REDACTED);
+    assert_eq!(value["user"], "admin");
+
+    let redactor = Redactor::with_keys(["Access_Key"]);
+    assert!(redactor.is_sensitive("ACCESS_KEY"));
+    assert!(redactor.is_sensitive("s3_access_key"));
+}
+
+#[test]
+fn test_only_keys_ending_in_a_sensitive_name_match() {
+    let redactor = Redactor::default();
+    assert!(redactor.is_sensitive("key"));
+    assert!(redactor.is_sensitive("secret_key"));
+    assert!(redactor.is_sensitive("x-amz-security-token"));
+    assert!(!redactor.is_sensitive("key_file"));
+    assert!(!redactor.is_sensitive("keyword"));
+    assert!(!redactor.is_sensitive("monkey"));
+    assert!(!redactor.is_sensitive("passwordless"));
+
+    let mut value = toml_value("[tls]\npath = \"/opt/tls\"\nkey_file = \"key.pem\"\ncert_file = \"cert.pem\"");
+    redact_config(&mut value);
+    assert_eq!(value["tls"]["key_file"].as_str(), Some("key.pem"));
+    assert_eq!(value["tls"]["cert_file"].as_str(), Some("cert.pem"));
+}
+
+#[test]
+fn test_secret_tables_are_walked_not_replaced() {
+    let mut value = json!({ "secrets": { "path": "/etc/nebulafx", "token": "t" }, "password": null });
+    redact_json(&mut value);
+
+    assert_eq!(value["secrets"]["path"], "/etc/nebulafx");
+    assert_eq!(value["secrets"]["token"], REDACTED);
+    assert!(value["password"].is_null());
+}
+
+#[test]
+fn test_redact_keys_from_config_extend_defaults() {
+    let table: toml::Table = r#"
+        redact_keys = ["access_key", "", "DSN"]
+        [server]
+        access_key = "admin"
+        secret_key = "s"
+        [database]
+        dsn = "postgres://u:p@h/db"
+        "#
+    .parse()
+    .unwrap();
+    let redactor = Redactor::from_table(&table);
+    assert_eq!(redactor.keys(), ["password", "secret", "key", "token", "access_key", "dsn"]);
+
+    let mut value = toml::Value::Table(table);
+    redactor.redact_toml(&mut value);
+    assert_eq!(value["server"]["access_key"].as_str(), Some(REDACTED));
+    assert_eq!(value["server"]["secret_key"].as_str(), Some(REDACTED));
+    assert_eq!(value["database"]["dsn"].as_str(), Some(REDACTED));
+    // The key list itself is left readable
+    assert_eq!(value["redact_keys"].as_array().unwrap().len(), 3);
+
+    // Without the config list only the defaults apply
+    let mut value = toml_value("[database]\ndsn = \"postgres://u:p@h/db\"");
+    redact_config(&mut value);
+    assert_eq!(value["database"]["dsn"].as_str(), Some("postgres://u:p@h/db"));
+}
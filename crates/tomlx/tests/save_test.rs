@@ -0,0 +1,115 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{load_config_from_path, save_config, save_config_commented};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct TestConfig {
+    name: String,
+    server: ServerConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct ServerConfig {
+    host: String,
+    port: u16,
+    volumes: Vec<String>,
+    tls: Option<bool>,
+}
+
+fn sample() -> TestConfig {
+    TestConfig {
+        name: "nebulafx".to_string(),
+        server: ServerConfig {
+            host: "0.0.0.0".to_string(),
+            port: 9000,
+            volumes: vec!["/data/a".to_string(), "/data/b".to_string()],
+            tls: Some(true),
+        },
+    }
+}
+
+#[test]
+fn test_save_config_round_trips() {
+    let dir = ConfigDir::new();
+    let path = dir.join("config.toml");
+    let config = sample();
+
+    save_config(&path, &config).unwrap();
+    let loaded: TestConfig = load_config_from_path(&path, false).unwrap();
+    assert_eq!(loaded, config);
+
+    let leftovers: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| name != "config.toml")
+        .collect();
+    assert!(leftovers.is_empty(), "temporary files left behind: {leftovers:?}");
+}
+
+#[test]
+fn test_save_config_overwrites_existing_file() {
+    let dir = ConfigDir::new();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "name = \"old\"\n").unwrap();
+
+    let mut config = sample();
+    config.server.tls = None;
+    save_config(&path, &config).unwrap();
+
+    let loaded: TestConfig = load_config_from_path(&path, false).unwrap();
+    assert_eq!(loaded, config);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_config_preserves_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = ConfigDir::new();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "name = \"old\"\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    save_config(&path, &sample()).unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn test_save_config_commented_annotates_keys() {
+    let dir = ConfigDir::new();
+    let path = dir.join("config.toml");
+    let config = sample();
+    let comments = [
+        ("name", "Display name"),
+        ("server", "Listener settings"),
+        ("server.port", "TCP port\n(default: 9000)"),
+        ("server.volumes", "Data directories"),
+    ];
+
+    save_config_commented(&path, &config, "Generated for a test", &comments).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("# Generated for a test\n\n# Display name\nname = "), "{content}");
+    assert!(content.contains("# Listener settings\n[server]\n"), "{content}");
+    assert!(content.contains("# TCP port\n# (default: 9000)\nport = 9000\n"), "{content}");
+    assert!(content.contains("# Data directories\nvolumes = "), "{content}");
+
+    let loaded: TestConfig = load_config_from_path(&path, false).unwrap();
+    assert_eq!(loaded, config);
+}
@@ -0,0 +1,82 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{TomlConfigError, Validate, load_config_validated};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TestConfig {
+    server: Option<ServerConfig>,
+    database: Option<DatabaseConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ServerConfig {
+    volumes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct DatabaseConfig {
+    url: String,
+}
+
+impl Validate for TestConfig {
+    fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        match &self.server {
+            None => problems.push("[server] section is missing".to_string()),
+            Some(server) if server.volumes.is_empty() => problems.push("server.volumes must not be empty".to_string()),
+            Some(_) => {}
+        }
+        if self.database.is_none() {
+            problems.push("[database] section is missing".to_string());
+        }
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+}
+
+#[test]
+fn test_validated_config_loads() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "[server]\nvolumes = [\"/data\"]\n[database]\nurl = \"sqlite://x\"\n");
+    let result: Result<TestConfig, TomlConfigError> = load_config_validated(&path, false);
+    assert_eq!(result.unwrap().server.unwrap().volumes, vec!["/data".to_string()]);
+}
+
+#[test]
+fn test_missing_server_section_lists_all_problems() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "name = \"nebulafx\"\n");
+    let result: Result<TestConfig, TomlConfigError> = load_config_validated(&path, false);
+    match result {
+        Err(TomlConfigError::Validation(problems)) => {
+            assert_eq!(problems, vec!["[server] section is missing", "[database] section is missing"]);
+        }
+        other => panic!("Expected Validation error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_validation_error_message_joins_problems() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", "[server]\nvolumes = []\n");
+    let err = load_config_validated::<TestConfig>(&path, false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid configuration: server.volumes must not be empty; [database] section is missing"
+    );
+}
@@ -0,0 +1,100 @@
+// Copyright 2024 NebulaFX Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common::ConfigDir;
+use nebulafx_tomlx::{WATCH_DEBOUNCE, watch_config};
+use serde::Deserialize;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct TestConfig {
+    server: ServerConfig,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ServerConfig {
+    host: String,
+    port: u16,
+}
+
+/// How long a test waits for a reload before failing
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Config directory for one test, removed on drop
+fn server_toml(port: u16) -> String {
+    format!("[server]\nhost = \"127.0.0.1\"\nport = {port}\n")
+}
+
+/// Wait for a reload carrying `port`, skipping any earlier reloads
+fn wait_for_port(rx: &mpsc::Receiver<TestConfig>, port: u16) -> TestConfig {
+    loop {
+        let config = rx.recv_timeout(RELOAD_TIMEOUT).expect("callback was not invoked");
+        if config.server.port == port {
+            return config;
+        }
+    }
+}
+
+#[test]
+fn test_watch_config_reloads_on_change() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", &server_toml(9000));
+
+    let (tx, rx) = mpsc::channel();
+    let _watcher = watch_config(&path, move |config: TestConfig| {
+        let _ = tx.send(config);
+    })
+    .unwrap();
+
+    dir.write("config.toml", &server_toml(9100));
+    let config = wait_for_port(&rx, 9100);
+    assert_eq!(config.server.host, "127.0.0.1");
+}
+
+#[test]
+fn test_watch_config_survives_parse_errors() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", &server_toml(9000));
+
+    let (tx, rx) = mpsc::channel();
+    let _watcher = watch_config(&path, move |config: TestConfig| {
+        let _ = tx.send(config);
+    })
+    .unwrap();
+
+    dir.write("config.toml", "[server\nport = ");
+    assert!(rx.recv_timeout(WATCH_DEBOUNCE * 3).is_err());
+
+    dir.write("config.toml", &server_toml(9200));
+    wait_for_port(&rx, 9200);
+}
+
+#[test]
+fn test_watch_config_stops_when_dropped() {
+    let dir = ConfigDir::new();
+    let path = dir.write("config.toml", &server_toml(9000));
+
+    let (tx, rx) = mpsc::channel();
+    let watcher = watch_config(&path, move |config: TestConfig| {
+        let _ = tx.send(config);
+    })
+    .unwrap();
+    drop(watcher);
+
+    dir.write("config.toml", &server_toml(9300));
+    assert!(rx.recv_timeout(WATCH_DEBOUNCE * 3).is_err());
+}
@@ -45,6 +45,49 @@ pub fn certs_error(err: String) -> Error {
     Error::other(err)
 }
 
+/// Expiry of the leaf certificate loaded for one name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertExpiry {
+    /// Key from [`load_all_certs_from_directory`]: `default` or the domain subdirectory
+    pub name: String,
+    pub subject: String,
+    /// Not-after time as a Unix timestamp
+    pub not_after: i64,
+}
+
+impl CertExpiry {
+    /// Whole days left at `now` (Unix seconds); negative once the certificate has expired
+    pub fn days_remaining(&self, now: i64) -> i64 {
+        (self.not_after - now).div_euclid(86_400)
+    }
+}
+
+/// Read the subject and not-after date of a DER certificate
+pub fn parse_cert_expiry(name: &str, cert: &CertificateDer<'_>) -> io::Result<CertExpiry> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref())
+        .map_err(|e| certs_error(format!("failed to parse certificate for {name}: {e}")))?;
+    Ok(CertExpiry {
+        name: name.to_string(),
+        subject: parsed.subject().to_string(),
+        not_after: parsed.validity().not_after.timestamp(),
+    })
+}
+
+/// Expiry of every certificate [`load_all_certs_from_directory`] finds, sorted by name
+pub fn load_cert_expiries(dir_path: &str) -> io::Result<Vec<CertExpiry>> {
+    let mut expiries = load_all_certs_from_directory(dir_path)?
+        .iter()
+        .map(|(name, (certs, _))| {
+            let leaf = certs
+                .first()
+                .ok_or_else(|| certs_error(format!("no certificate loaded for {name}")))?;
+            parse_cert_expiry(name, leaf)
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    expiries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(expiries)
+}
+
 /// Load all certificates and private keys in the directory
 /// This function loads all certificate and private key pairs from the specified directory.
 /// It looks for files named `nebulafx_cert.pem` and `nebulafx_key.pem` in each subdirectory.
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+const BYTE_UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
+
+/// Format a duration for logs, e.g. `850µs`, `340ms`, `1.2s`, `2.5m`, `1.0h`
+pub fn humanize_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if duration < Duration::from_millis(1) {
+        format!("{}µs", duration.as_micros())
+    } else if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else if secs < 60.0 {
+        format!("{secs:.1}s")
+    } else if secs < 3600.0 {
+        format!("{:.1}m", secs / 60.0)
+    } else {
+        format!("{:.1}h", secs / 3600.0)
+    }
+}
+
+/// Format a byte count using 1024-based units, e.g. `512B`, `1.5KB`, `5.0GB`
+pub fn humanize_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", BYTE_UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_duration() {
+        assert_eq!(humanize_duration(Duration::ZERO), "0µs");
+        assert_eq!(humanize_duration(Duration::from_micros(850)), "850µs");
+        assert_eq!(humanize_duration(Duration::from_millis(1)), "1ms");
+        assert_eq!(humanize_duration(Duration::from_millis(340)), "340ms");
+        assert_eq!(humanize_duration(Duration::from_millis(999)), "999ms");
+        assert_eq!(humanize_duration(Duration::from_millis(1200)), "1.2s");
+        assert_eq!(humanize_duration(Duration::from_secs(59)), "59.0s");
+        assert_eq!(humanize_duration(Duration::from_secs(150)), "2.5m");
+        assert_eq!(humanize_duration(Duration::from_secs(3600)), "1.0h");
+    }
+
+    #[test]
+    fn test_humanize_bytes() {
+        assert_eq!(humanize_bytes(0), "0B");
+        assert_eq!(humanize_bytes(512), "512B");
+        assert_eq!(humanize_bytes(1023), "1023B");
+        assert_eq!(humanize_bytes(1024), "1.0KB");
+        assert_eq!(humanize_bytes(1536), "1.5KB");
+        assert_eq!(humanize_bytes(10 * 1024 * 1024), "10.0MB");
+        assert_eq!(humanize_bytes(5 * 1024 * 1024 * 1024), "5.0GB");
+        assert_eq!(humanize_bytes(2 * 1024u64.pow(4)), "2.0TB");
+        assert_eq!(humanize_bytes(u64::MAX), "16384.0PB");
+    }
+}
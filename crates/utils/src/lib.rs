@@ -66,6 +66,9 @@ mod notify;
 #[cfg(feature = "sys")]
 pub mod sys;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(feature = "sys")]
 pub use sys::user_agent::*;
 
@@ -74,3 +77,6 @@ pub use notify::*;
 
 mod envs;
 pub use envs::*;
+
+mod humanize;
+pub use humanize::*;
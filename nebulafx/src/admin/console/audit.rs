@@ -0,0 +1,26 @@
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{Stream, StreamExt};
+use nebulafx_audit::{AuditTailFilter, subscribe_audit_tail};
+use std::convert::Infallible;
+use tracing::{debug, warn};
+
+/// Stream newly dispatched audit entries as server-sent events
+///
+/// Supports optional `bucket`, `principal` and `action` query filters. The stream
+/// ends on server shutdown; a client disconnect drops the subscription.
+pub(crate) async fn tail_audit_log(Query(filter): Query<AuditTailFilter>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    debug!(target: "nebulafx::console::audit", ?filter, "Audit tail subscriber connected");
+
+    let stream = subscribe_audit_tail(filter).map(|entry| {
+        let event = Event::default().event("audit").json_data(&*entry).unwrap_or_else(|e| {
+            warn!(target: "nebulafx::console::audit", "Failed to serialize audit entry: {}", e);
+            Event::default().event("error").data(e.to_string())
+        });
+        Ok(event)
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
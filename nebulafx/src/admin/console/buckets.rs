@@ -0,0 +1,358 @@
+use async_trait::async_trait;
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use flate2::{Compression, write::GzEncoder};
+use http::{StatusCode, header};
+use nebulafx_common::data_usage::DataUsageInfo;
+use nebulafx_ecstore::config::com::read_config;
+use nebulafx_ecstore::data_usage::DATA_USAGE_OBJ_NAME_PATH;
+use nebulafx_ecstore::error::StorageError;
+use nebulafx_ecstore::new_object_layer_fn;
+use nebulafx_ecstore::store::ECStore;
+use nebulafx_ecstore::store_api::{BucketInfo, BucketOptions, ObjectInfo, ObjectInfoOrErr, StorageAPI, WalkOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Write;
+use std::sync::Arc;
+use time::format_description::well_known::Rfc3339;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Objects buffered between the store walk and the encoder
+const EXPORT_WALK_CAPACITY: usize = 256;
+/// Compressed chunks buffered for the client
+const EXPORT_CHUNK_CAPACITY: usize = 4;
+/// Compressed bytes accumulated before a chunk is sent
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExportQuery {
+    #[serde(default)]
+    prefix: String,
+}
+
+/// One line of the NDJSON export
+#[derive(Debug, Serialize)]
+struct ExportEntry<'a> {
+    key: &'a str,
+    size: i64,
+    etag: Option<&'a str>,
+    last_modified: Option<String>,
+    storage_class: Option<&'a str>,
+    version_id: Option<String>,
+}
+
+impl<'a> From<&'a ObjectInfo> for ExportEntry<'a> {
+    fn from(info: &'a ObjectInfo) -> Self {
+        Self {
+            key: &info.name,
+            size: info.size,
+            etag: info.etag.as_deref(),
+            last_modified: info.mod_time.and_then(|t| t.format(&Rfc3339).ok()),
+            storage_class: info.storage_class.as_deref(),
+            version_id: info.version_id.map(|v| v.to_string()),
+        }
+    }
+}
+
+/// What the bucket summary reads from the object store
+#[async_trait]
+trait BucketSource: Send + Sync {
+    async fn buckets(&self) -> Result<Vec<BucketInfo>, StorageError>;
+
+    /// Usage persisted by the last scanner cycle; never computed on demand
+    async fn recorded_usage(&self) -> Option<DataUsageInfo>;
+}
+
+#[async_trait]
+impl BucketSource for Arc<ECStore> {
+    async fn buckets(&self) -> Result<Vec<BucketInfo>, StorageError> {
+        self.list_bucket(&BucketOptions {
+            no_metadata: true,
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn recorded_usage(&self) -> Option<DataUsageInfo> {
+        let buf = read_config(self.clone(), &DATA_USAGE_OBJ_NAME_PATH).await.ok()?;
+        serde_json::from_slice(&buf)
+            .inspect_err(|e| debug!(target: "nebulafx::console::buckets", "Ignoring unreadable data usage: {}", e))
+            .ok()
+    }
+}
+
+/// One bucket in the summary; totals are absent until the scanner has recorded usage for it
+#[derive(Debug, Serialize)]
+struct BucketSummary {
+    name: String,
+    created: Option<String>,
+    objects: Option<u64>,
+    size: Option<u64>,
+}
+
+/// List buckets with their creation date and the object count and size from the last scan
+pub(crate) async fn list_buckets() -> Response {
+    let Some(store) = new_object_layer_fn() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "storage is not initialized").into_response();
+    };
+    bucket_summary(&store).await
+}
+
+async fn bucket_summary(source: &dyn BucketSource) -> Response {
+    let buckets = match source.buckets().await {
+        Ok(buckets) => buckets,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    let usage = source.recorded_usage().await;
+
+    let buckets: Vec<BucketSummary> = buckets
+        .into_iter()
+        .map(|bucket| {
+            let recorded = usage.as_ref().and_then(|u| u.buckets_usage.get(&bucket.name));
+            BucketSummary {
+                created: bucket.created.and_then(|t| t.format(&Rfc3339).ok()),
+                objects: recorded.map(|u| u.objects_count),
+                size: recorded.map(|u| u.size),
+                name: bucket.name,
+            }
+        })
+        .collect();
+    let usage_updated = usage
+        .and_then(|u| u.last_update)
+        .and_then(|t| time::OffsetDateTime::from(t).format(&Rfc3339).ok());
+
+    Json(json!({ "buckets": buckets, "usage_updated": usage_updated })).into_response()
+}
+
+/// Stream the bucket's object listing as gzip-compressed NDJSON
+pub(crate) async fn export_bucket(Path(bucket): Path<String>, Query(query): Query<ExportQuery>) -> Response {
+    let Some(store) = new_object_layer_fn() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "storage is not initialized").into_response();
+    };
+
+    match store.get_bucket_info(&bucket, &BucketOptions::default()).await {
+        Ok(_) => {}
+        Err(StorageError::BucketNotFound(_)) => {
+            return (StatusCode::NOT_FOUND, format!("bucket not found: {bucket}")).into_response();
+        }
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+
+    // Stop walking on shutdown as well as on client disconnect
+    let cancel = nebulafx_ecstore::global::get_background_services_cancel_token()
+        .map(CancellationToken::child_token)
+        .unwrap_or_default();
+
+    let (walk_tx, walk_rx) = mpsc::channel(EXPORT_WALK_CAPACITY);
+    let (chunk_tx, chunk_rx) = mpsc::channel(EXPORT_CHUNK_CAPACITY);
+
+    info!(target: "nebulafx::console::buckets", bucket = %bucket, prefix = %query.prefix, "Exporting bucket listing");
+    {
+        let cancel = cancel.clone();
+        let bucket = bucket.clone();
+        let opts = WalkOptions {
+            latest_only: true,
+            ..Default::default()
+        };
+        tokio::spawn(async move {
+            if let Err(e) = store.walk(cancel, &bucket, &query.prefix, walk_tx, opts).await {
+                warn!(target: "nebulafx::console::buckets", bucket = %bucket, "Bucket export walk failed: {}", e);
+            }
+        });
+    }
+    tokio::spawn(encode_export(walk_rx, chunk_tx, cancel));
+
+    Response::builder()
+        .status(StatusCode::OK)
+        // A gzip file to download, not an encoding for clients to strip
+        .header(header::CONTENT_TYPE, "application/gzip")
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{bucket}.ndjson.gz\""))
+        .body(Body::from_stream(ReceiverStream::new(chunk_rx)))
+        .unwrap()
+}
+
+/// Gzip listed objects as NDJSON into bounded chunks until the walk ends or the client goes away
+async fn encode_export(
+    mut objects: mpsc::Receiver<ObjectInfoOrErr>,
+    chunks: mpsc::Sender<std::io::Result<Bytes>>,
+    cancel: CancellationToken,
+) {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(EXPORT_CHUNK_SIZE), Compression::default());
+    let mut exported = 0usize;
+
+    loop {
+        let next = tokio::select! {
+            next = objects.recv() => next,
+            _ = cancel.cancelled() => {
+                debug!(target: "nebulafx::console::buckets", "Bucket export cancelled");
+                return;
+            }
+        };
+        let Some(entry) = next else { break };
+
+        if let Some(err) = entry.err {
+            warn!(target: "nebulafx::console::buckets", "Skipping object in bucket export: {}", err);
+            continue;
+        }
+        let Some(info) = entry.item else { continue };
+
+        let written = serde_json::to_writer(&mut encoder, &ExportEntry::from(&info))
+            .map_err(std::io::Error::other)
+            .and_then(|_| encoder.write_all(b"\n"));
+        if let Err(e) = written {
+            let _ = chunks.send(Err(e)).await;
+            cancel.cancel();
+            return;
+        }
+        exported += 1;
+
+        if encoder.get_ref().len() >= EXPORT_CHUNK_SIZE {
+            let chunk = Bytes::from(std::mem::take(encoder.get_mut()));
+            if chunks.send(Ok(chunk)).await.is_err() {
+                // Client disconnected; stop the walk
+                cancel.cancel();
+                return;
+            }
+        }
+    }
+
+    match encoder.finish() {
+        Ok(tail) => {
+            let _ = chunks.send(Ok(Bytes::from(tail))).await;
+            debug!(target: "nebulafx::console::buckets", exported, "Bucket export finished");
+        }
+        Err(e) => {
+            let _ = chunks.send(Err(e)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn object(name: &str, size: i64) -> ObjectInfoOrErr {
+        ObjectInfoOrErr {
+            item: Some(ObjectInfo {
+                bucket: "seeded".to_string(),
+                name: name.to_string(),
+                size,
+                etag: Some(format!("etag-{name}")),
+                ..Default::default()
+            }),
+            err: None,
+        }
+    }
+
+    /// Two buckets, with scanner usage recorded for the first only
+    struct StubStore;
+
+    #[async_trait]
+    impl BucketSource for StubStore {
+        async fn buckets(&self) -> Result<Vec<BucketInfo>, StorageError> {
+            Ok(vec![
+                BucketInfo {
+                    name: "photos".to_string(),
+                    created: Some(time::macros::datetime!(2024-03-01 12:00 UTC)),
+                    ..Default::default()
+                },
+                BucketInfo {
+                    name: "logs".to_string(),
+                    ..Default::default()
+                },
+            ])
+        }
+
+        async fn recorded_usage(&self) -> Option<DataUsageInfo> {
+            let mut usage = DataUsageInfo::default();
+            usage.buckets_usage.insert(
+                "photos".to_string(),
+                nebulafx_common::data_usage::BucketUsageInfo {
+                    objects_count: 3,
+                    size: 4096,
+                    ..Default::default()
+                },
+            );
+            Some(usage)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bucket_summary_from_store() {
+        let resp = bucket_summary(&StubStore).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(
+            summary["buckets"],
+            json!([
+                {"name": "photos", "created": "2024-03-01T12:00:00Z", "objects": 3, "size": 4096},
+                {"name": "logs", "created": null, "objects": null, "size": null},
+            ])
+        );
+        assert!(summary["usage_updated"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_summary_unavailable_without_store() {
+        assert_eq!(list_buckets().await.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_export_seeded_bucket_as_gzip_ndjson() {
+        let (walk_tx, walk_rx) = mpsc::channel(EXPORT_WALK_CAPACITY);
+        let (chunk_tx, mut chunk_rx) = mpsc::channel(EXPORT_CHUNK_CAPACITY);
+
+        for (name, size) in [("a.txt", 1), ("dir/b.bin", 2048), ("dir/c.json", 17)] {
+            walk_tx.send(object(name, size)).await.unwrap();
+        }
+        drop(walk_tx);
+
+        tokio::spawn(encode_export(walk_rx, chunk_tx, CancellationToken::new()));
+
+        let mut compressed = Vec::new();
+        while let Some(chunk) = chunk_rx.recv().await {
+            compressed.extend_from_slice(&chunk.unwrap());
+        }
+
+        let mut ndjson = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut ndjson).unwrap();
+
+        let lines: Vec<serde_json::Value> = ndjson.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["key"], "a.txt");
+        assert_eq!(lines[1]["size"], 2048);
+        assert_eq!(lines[2]["etag"], "etag-dir/c.json");
+    }
+
+    #[tokio::test]
+    async fn test_export_stops_when_client_disconnects() {
+        let (walk_tx, walk_rx) = mpsc::channel(EXPORT_WALK_CAPACITY);
+        let (chunk_tx, chunk_rx) = mpsc::channel(EXPORT_CHUNK_CAPACITY);
+        let cancel = CancellationToken::new();
+        drop(chunk_rx);
+
+        // Enough poorly compressible entries to fill at least one chunk
+        tokio::spawn(async move {
+            for i in 0..20_000 {
+                if walk_tx.send(object(&uuid::Uuid::new_v4().to_string(), i)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        encode_export(walk_rx, chunk_tx, cancel.clone()).await;
+        assert!(cancel.is_cancelled());
+    }
+}
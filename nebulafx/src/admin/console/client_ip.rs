@@ -0,0 +1,131 @@
+use super::ip_filter::parse_cidrs;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use http::{Extensions, HeaderMap};
+use ipnetwork::IpNetwork;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tracing::{debug, error};
+
+/// Reverse proxies from `NEBULAFX_CONSOLE_TRUSTED_PROXIES` whose `X-Forwarded-For` names the client
+///
+/// Empty by default, so the forwarded headers are ignored unless an operator lists the proxies.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TrustedProxies(Arc<Vec<IpNetwork>>);
+
+impl TrustedProxies {
+    pub(crate) fn new(proxies: Vec<IpNetwork>) -> Self {
+        Self(Arc::new(proxies))
+    }
+
+    /// Proxies from the environment; a list that does not parse trusts none of them
+    pub(crate) fn from_env() -> Self {
+        let var = nebulafx_config::ENV_CONSOLE_TRUSTED_PROXIES;
+        let Some(value) = std::env::var(var).ok().filter(|v| !v.trim().is_empty()) else {
+            return Self::default();
+        };
+        match parse_cidrs(var, &value) {
+            Ok(proxies) => {
+                debug!(?proxies, "Console trusted proxies configured");
+                Self::new(proxies)
+            }
+            Err(e) => {
+                error!("{}, ignoring forwarded client addresses", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn is_trusted(&self, peer: IpAddr) -> bool {
+        let peer = peer.to_canonical();
+        self.0.iter().any(|net| net.contains(peer))
+    }
+
+    /// The forwarded address when `peer` is a trusted proxy, else `peer` itself
+    ///
+    /// `None` for requests over the console Unix socket, which have no peer address.
+    pub(crate) fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        let peer = peer?.to_canonical();
+        if self.is_trusted(peer) {
+            forwarded_ip(headers).or(Some(peer))
+        } else {
+            Some(peer)
+        }
+    }
+}
+
+/// The client address resolved by [`resolve_client_ip`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ClientIp(pub(crate) Option<IpAddr>);
+
+impl ClientIp {
+    /// The address stored on the request, `None` when unknown
+    pub(crate) fn get(extensions: &Extensions) -> Option<IpAddr> {
+        extensions.get::<ClientIp>().and_then(|client| client.0)
+    }
+}
+
+/// The client address reported by a proxy in `X-Forwarded-For` and similar headers
+fn forwarded_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    nebulafx_utils::http::get_source_ip_from_headers(headers).and_then(|addr| {
+        let addr = addr.trim();
+        addr.parse::<IpAddr>()
+            .ok()
+            .or_else(|| addr.parse::<SocketAddr>().ok().map(|a| a.ip()))
+            .map(|ip| ip.to_canonical())
+    })
+}
+
+/// Store the client address as [`ClientIp`] for the IP filter, the rate limiter and the access log
+pub(crate) async fn resolve_client_ip(State(proxies): State<TrustedProxies>, mut req: Request, next: Next) -> Response {
+    let peer = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+    let client = proxies.client_ip(req.headers(), peer);
+    req.extensions_mut().insert(ClientIp(client));
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_forwarded_address_only_taken_from_trusted_proxies() {
+        let proxies = TrustedProxies::new(parse_cidrs("TEST_CIDRS", "10.0.0.0/24").unwrap());
+        let headers = forwarded_for("203.0.113.7");
+
+        assert_eq!(proxies.client_ip(&headers, Some(ip("10.0.0.9"))), Some(ip("203.0.113.7")));
+        assert_eq!(proxies.client_ip(&headers, Some(ip("::ffff:10.0.0.9"))), Some(ip("203.0.113.7")));
+        // Private and loopback peers are not trusted unless listed
+        assert_eq!(proxies.client_ip(&headers, Some(ip("10.0.1.9"))), Some(ip("10.0.1.9")));
+        assert_eq!(proxies.client_ip(&headers, Some(ip("127.0.0.1"))), Some(ip("127.0.0.1")));
+        // A trusted proxy without the header is the client
+        assert_eq!(proxies.client_ip(&HeaderMap::new(), Some(ip("10.0.0.9"))), Some(ip("10.0.0.9")));
+        // Unix socket requests have no address, forwarded or not
+        assert_eq!(proxies.client_ip(&headers, None), None);
+    }
+
+    #[test]
+    fn test_no_proxies_trusted_by_default() {
+        temp_env::with_var_unset(nebulafx_config::ENV_CONSOLE_TRUSTED_PROXIES, || {
+            let proxies = TrustedProxies::from_env();
+            let headers = forwarded_for("203.0.113.7");
+            assert_eq!(proxies.client_ip(&headers, Some(ip("127.0.0.1"))), Some(ip("127.0.0.1")));
+        });
+        temp_env::with_var(nebulafx_config::ENV_CONSOLE_TRUSTED_PROXIES, Some("127.0.0.1/33"), || {
+            assert!(TrustedProxies::from_env().0.is_empty());
+        });
+    }
+}
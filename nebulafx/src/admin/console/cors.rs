@@ -0,0 +1,256 @@
+use arc_swap::ArcSwap;
+use axum::{
+    Json,
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{HeaderValue, Method, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tower::{Layer, ServiceExt};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{info, warn};
+
+/// Methods, headers and preflight cache time shared by every console CORS configuration
+///
+/// Methods come from `NEBULAFX_CONSOLE_CORS_ALLOWED_METHODS` and the cache time from
+/// `NEBULAFX_CONSOLE_CORS_MAX_AGE`; invalid values fall back to the defaults.
+pub(crate) fn base_cors_layer() -> CorsLayer {
+    let methods = match std::env::var(nebulafx_config::ENV_CONSOLE_CORS_ALLOWED_METHODS) {
+        Ok(value) => parse_cors_methods(&value).unwrap_or_else(|e| {
+            warn!("{}, using the default methods", e);
+            default_cors_methods()
+        }),
+        Err(_) => default_cors_methods(),
+    };
+    let max_age = match std::env::var(nebulafx_config::ENV_CONSOLE_CORS_MAX_AGE) {
+        Ok(value) => value.trim().parse::<u64>().unwrap_or_else(|e| {
+            warn!("invalid {} '{value}': {e}, using the default", nebulafx_config::ENV_CONSOLE_CORS_MAX_AGE);
+            nebulafx_config::DEFAULT_CONSOLE_CORS_MAX_AGE
+        }),
+        Err(_) => nebulafx_config::DEFAULT_CONSOLE_CORS_MAX_AGE,
+    };
+    cors_layer(methods, Duration::from_secs(max_age))
+}
+
+/// `DEFAULT_CONSOLE_CORS_ALLOWED_METHODS`
+fn default_cors_methods() -> Vec<Method> {
+    parse_cors_methods(nebulafx_config::DEFAULT_CONSOLE_CORS_ALLOWED_METHODS).expect("default console CORS methods parse")
+}
+
+fn cors_layer(methods: Vec<Method>, max_age: Duration) -> CorsLayer {
+    let layer = CorsLayer::new().allow_methods(methods).allow_headers(Any);
+    if max_age.is_zero() { layer } else { layer.max_age(max_age) }
+}
+
+/// Parse a comma-separated method list such as `GET,POST`
+fn parse_cors_methods(value: &str) -> Result<Vec<Method>, String> {
+    let methods = value
+        .split(',')
+        .map(str::trim)
+        .filter(|method| !method.is_empty())
+        .map(|method| {
+            Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|e| format!("invalid {} entry '{method}': {e}", nebulafx_config::ENV_CONSOLE_CORS_ALLOWED_METHODS))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if methods.is_empty() {
+        return Err(format!("{} lists no methods", nebulafx_config::ENV_CONSOLE_CORS_ALLOWED_METHODS));
+    }
+    Ok(methods)
+}
+
+/// Parse one allowed origin as a header value
+pub(crate) fn parse_origin(origin: &str) -> Result<HeaderValue, String> {
+    origin
+        .parse::<HeaderValue>()
+        .map_err(|e| format!("invalid CORS origin '{origin}': {e}"))
+}
+
+/// Console CORS layer that can be replaced while the server is running
+#[derive(Clone)]
+pub(crate) struct ConsoleCors(Arc<ArcSwap<CorsLayer>>);
+
+impl ConsoleCors {
+    pub(crate) fn new(layer: CorsLayer) -> Self {
+        Self(Arc::new(ArcSwap::from_pointee(layer)))
+    }
+
+    /// Replace the allowed origins; the current layer is kept if any origin is invalid
+    ///
+    /// A single `*` allows any origin.
+    pub(crate) fn update(&self, origins: &[String]) -> Result<(), String> {
+        let layer = match origins {
+            [] => return Err("at least one CORS origin is required".to_string()),
+            [origin] if origin == "*" => base_cors_layer().allow_origin(Any),
+            origins => {
+                let origins = origins
+                    .iter()
+                    .map(|origin| parse_origin(origin.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                base_cors_layer().allow_origin(AllowOrigin::list(origins))
+            }
+        };
+        self.0.store(Arc::new(layer.expose_headers(Any)));
+        Ok(())
+    }
+}
+
+static CONSOLE_CORS: OnceLock<ConsoleCors> = OnceLock::new();
+
+/// Install `layer` as the console CORS configuration and return the shared handle
+pub(crate) fn init_console_cors(layer: CorsLayer) -> ConsoleCors {
+    let cors = CONSOLE_CORS.get_or_init(|| ConsoleCors::new(layer.clone()));
+    cors.0.store(Arc::new(layer));
+    cors.clone()
+}
+
+/// Apply whichever CORS layer is current when the request arrives
+pub(crate) async fn dynamic_cors(State(cors): State<ConsoleCors>, req: Request, next: Next) -> Response {
+    let layer = cors.0.load_full();
+    match layer.layer(next).oneshot(req).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CorsUpdate {
+    origins: Vec<String>,
+}
+
+/// Replace the console's allowed CORS origins without reloading the config
+pub(crate) async fn update_cors(Json(update): Json<CorsUpdate>) -> Response {
+    let Some(cors) = CONSOLE_CORS.get() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "console CORS is not initialized").into_response();
+    };
+
+    match cors.update(&update.origins) {
+        Ok(()) => {
+            info!(target: "nebulafx::console::cors", origins = ?update.origins, "Console CORS origins updated");
+            Json(json!({ "origins": update.origins })).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// Replace the console's allowed origins with a comma-separated list from a reloaded config
+pub(crate) fn reload_console_cors(origins: &str) -> Result<(), String> {
+    let cors = CONSOLE_CORS.get().ok_or("console CORS is not initialized")?;
+    let origins: Vec<String> = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(str::to_string)
+        .collect();
+    cors.update(&origins)?;
+    info!(target: "nebulafx::console::cors", origins = ?origins, "Console CORS origins reloaded");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use http::header::{
+        ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+    };
+
+    fn allowing(origin: &str) -> ConsoleCors {
+        ConsoleCors::new(base_cors_layer().allow_origin(AllowOrigin::list([parse_origin(origin).unwrap()])))
+    }
+
+    async fn allowed_origin(app: &Router, origin: &str) -> Option<HeaderValue> {
+        let req = Request::builder()
+            .uri("/")
+            .header(ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+    }
+
+    #[tokio::test]
+    async fn test_updated_origins_apply_to_later_requests() {
+        let cors = allowing("https://a.example");
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(cors.clone(), dynamic_cors));
+
+        assert_eq!(allowed_origin(&app, "https://a.example").await.unwrap(), "https://a.example");
+        assert!(allowed_origin(&app, "https://b.example").await.is_none());
+
+        cors.update(&["https://b.example".to_string()]).unwrap();
+        assert_eq!(allowed_origin(&app, "https://b.example").await.unwrap(), "https://b.example");
+        assert!(allowed_origin(&app, "https://a.example").await.is_none());
+    }
+
+    async fn preflight(app: &Router, method: &str) -> http::HeaderMap {
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/")
+            .header(ORIGIN, "https://a.example")
+            .header(ACCESS_CONTROL_REQUEST_METHOD, method)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap().headers().clone()
+    }
+
+    #[tokio::test]
+    async fn test_preflight_carries_max_age_and_configured_methods() {
+        let cors = ConsoleCors::new(
+            cors_layer(parse_cors_methods("get, options").unwrap(), Duration::from_secs(3600))
+                .allow_origin(AllowOrigin::list([parse_origin("https://a.example").unwrap()])),
+        );
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }).delete(|| async { "deleted" }))
+            .layer(middleware::from_fn_with_state(cors, dynamic_cors));
+
+        let headers = preflight(&app, "GET").await;
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "3600");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET,OPTIONS");
+
+        // Browsers refuse methods the preflight does not list
+        let headers = preflight(&app, "DELETE").await;
+        assert!(!headers[ACCESS_CONTROL_ALLOW_METHODS].to_str().unwrap().contains("DELETE"));
+    }
+
+    #[tokio::test]
+    async fn test_cors_method_and_max_age_defaults() {
+        assert_eq!(
+            default_cors_methods(),
+            [Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS]
+        );
+        assert!(parse_cors_methods(" , ").is_err());
+        assert!(parse_cors_methods("GET,BAD METHOD").unwrap_err().contains("BAD METHOD"));
+
+        // Zero leaves the header out, so browsers apply their own short default
+        let layer = temp_env::with_var(nebulafx_config::ENV_CONSOLE_CORS_MAX_AGE, Some("0"), base_cors_layer);
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(ConsoleCors::new(layer.allow_origin(Any)), dynamic_cors));
+        let headers = preflight(&app, "GET").await;
+        assert!(headers.get(ACCESS_CONTROL_MAX_AGE).is_none());
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET,POST,PUT,DELETE,OPTIONS");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_origin_keeps_previous_config() {
+        let cors = allowing("https://a.example");
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(cors.clone(), dynamic_cors));
+
+        let err = cors
+            .update(&["https://b.example".to_string(), "bad\norigin".to_string()])
+            .unwrap_err();
+        assert!(err.contains("bad\norigin"));
+        assert!(cors.update(&[]).is_err());
+
+        assert_eq!(allowed_origin(&app, "https://a.example").await.unwrap(), "https://a.example");
+        assert!(allowed_origin(&app, "https://b.example").await.is_none());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config::get_config;
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use nebulafx_postgresqlx::{PostgreSQLConfig, PostgreSQLPool};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info};
+
+/// Set while a reconnect is rebuilding the pool
+static RECONNECTING: AtomicBool = AtomicBool::new(false);
+
+/// Clears `RECONNECTING` when the reconnect finishes, even on error
+struct ReconnectGuard;
+
+impl ReconnectGuard {
+    fn try_acquire() -> Option<Self> {
+        RECONNECTING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self)
+    }
+}
+
+impl Drop for ReconnectGuard {
+    fn drop(&mut self) {
+        RECONNECTING.store(false, Ordering::Release);
+    }
+}
+
+/// Open a new Postgres pool from the current config and hand it out in place of the current one
+pub(crate) async fn reconnect_database() -> Response {
+    let Some(config) = get_config().database.as_ref() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "database is not configured").into_response();
+    };
+    reconnect(config).await
+}
+
+async fn reconnect(config: &PostgreSQLConfig) -> Response {
+    let Some(_guard) = ReconnectGuard::try_acquire() else {
+        return (StatusCode::CONFLICT, "a database reconnect is already in progress").into_response();
+    };
+
+    match PostgreSQLPool::init_or_replace(Some(config)).await {
+        Ok(pool) => {
+            info!(target: "nebulafx::console::database", pool = %pool.name(), "Database pool reconnected from console");
+            Json(json!({
+                "pool": pool.name(),
+                "size": pool.inner().size(),
+                "idle": pool.inner().num_idle(),
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            error!(target: "nebulafx::console::database", "Failed to reconnect database pool: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrent_reconnect_is_rejected() {
+        let config: PostgreSQLConfig = nebulafx_tomlx::load_config_from_str("host = \"localhost\"").unwrap();
+        let _guard = ReconnectGuard::try_acquire().unwrap();
+
+        let response = reconnect(&config).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL reachable via DATABASE_URL"]
+    async fn test_pool_is_usable_after_reconnect() {
+        // The default pool connects to `DATABASE_URL` when it is set
+        let config = PostgreSQLConfig::default();
+        PostgreSQLPool::init_or_replace(Some(&config)).await.unwrap();
+        let before = PostgreSQLPool::get().unwrap();
+
+        let response = reconnect(&config).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Clones taken before the reconnect, like the one IAM holds, keep working
+        assert!(before.health_check().await.unwrap());
+        assert!(PostgreSQLPool::get().unwrap().health_check().await.unwrap());
+        // The guard is released once the reconnect finishes
+        assert!(ReconnectGuard::try_acquire().is_some());
+    }
+}
@@ -0,0 +1,164 @@
+use axum::Json;
+use http::StatusCode;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// Error responses counted per status code within one second
+type Bucket = (u64, BTreeMap<u16, u64>);
+
+/// Sliding-window counts of 4xx/5xx console responses
+///
+/// Responses are bucketed per second and buckets older than an hour are dropped,
+/// so memory stays bounded regardless of traffic.
+pub(crate) struct ErrorStats {
+    started: Instant,
+    buckets: Mutex<VecDeque<Bucket>>,
+}
+
+/// Error counts over one window
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub(crate) struct ErrorWindow {
+    client_errors: u64,
+    server_errors: u64,
+    by_status: BTreeMap<u16, u64>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct ErrorRates {
+    last_minute: ErrorWindow,
+    last_hour: ErrorWindow,
+}
+
+impl ErrorStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            buckets: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Count `status` if it is a client or server error
+    pub(crate) fn record(&self, status: StatusCode) {
+        self.record_at(status, Instant::now());
+    }
+
+    pub(crate) fn snapshot(&self) -> ErrorRates {
+        self.snapshot_at(Instant::now())
+    }
+
+    pub(crate) fn reset(&self) {
+        self.buckets.lock().unwrap().clear();
+    }
+
+    fn second(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.started).as_secs()
+    }
+
+    fn record_at(&self, status: StatusCode, at: Instant) {
+        if !status.is_client_error() && !status.is_server_error() {
+            return;
+        }
+        let second = self.second(at);
+        let mut buckets = self.buckets.lock().unwrap();
+        match buckets.back_mut() {
+            Some((last, counts)) if *last == second => *counts.entry(status.as_u16()).or_default() += 1,
+            _ => buckets.push_back((second, BTreeMap::from([(status.as_u16(), 1)]))),
+        }
+        while buckets
+            .front()
+            .is_some_and(|(s, _)| second.saturating_sub(*s) >= HOUR.as_secs())
+        {
+            buckets.pop_front();
+        }
+    }
+
+    fn snapshot_at(&self, at: Instant) -> ErrorRates {
+        let now = self.second(at);
+        let buckets = self.buckets.lock().unwrap();
+        let window = |span: Duration| {
+            let mut window = ErrorWindow::default();
+            for (_, counts) in buckets.iter().filter(|(s, _)| now.saturating_sub(*s) < span.as_secs()) {
+                for (&status, &count) in counts {
+                    *window.by_status.entry(status).or_default() += count;
+                    if status >= 500 {
+                        window.server_errors += count;
+                    } else {
+                        window.client_errors += count;
+                    }
+                }
+            }
+            window
+        };
+        ErrorRates {
+            last_minute: window(MINUTE),
+            last_hour: window(HOUR),
+        }
+    }
+}
+
+static ERROR_STATS: OnceLock<ErrorStats> = OnceLock::new();
+
+/// Error counts shared by the console middleware and the debug endpoint
+pub(crate) fn error_stats() -> &'static ErrorStats {
+    ERROR_STATS.get_or_init(ErrorStats::new)
+}
+
+/// Report console error counts over the last minute and hour
+pub(crate) async fn error_rates() -> Json<ErrorRates> {
+    Json(error_stats().snapshot())
+}
+
+/// Clear the console error counts
+pub(crate) async fn reset_error_rates() -> StatusCode {
+    error_stats().reset();
+    info!(target: "nebulafx::console::errors", "Console error counts reset");
+    StatusCode::NO_CONTENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_responses_are_counted_per_window() {
+        let stats = ErrorStats::new();
+        let start = stats.started;
+
+        // Outside the hour window
+        stats.record_at(StatusCode::INTERNAL_SERVER_ERROR, start);
+        // Inside the hour, outside the minute
+        stats.record_at(StatusCode::NOT_FOUND, start + Duration::from_secs(1800));
+        stats.record_at(StatusCode::BAD_GATEWAY, start + Duration::from_secs(1800));
+        // Inside the minute
+        let now = start + Duration::from_secs(3650);
+        stats.record_at(StatusCode::OK, now);
+        stats.record_at(StatusCode::NOT_FOUND, now);
+        stats.record_at(StatusCode::NOT_FOUND, now);
+        stats.record_at(StatusCode::INTERNAL_SERVER_ERROR, now);
+
+        let rates = stats.snapshot_at(now);
+        assert_eq!(rates.last_minute.client_errors, 2);
+        assert_eq!(rates.last_minute.server_errors, 1);
+        assert_eq!(rates.last_minute.by_status, BTreeMap::from([(404, 2), (500, 1)]));
+
+        assert_eq!(rates.last_hour.client_errors, 3);
+        assert_eq!(rates.last_hour.server_errors, 2);
+        assert_eq!(rates.last_hour.by_status, BTreeMap::from([(404, 3), (500, 1), (502, 1)]));
+    }
+
+    #[test]
+    fn test_reset_clears_counts() {
+        let stats = ErrorStats::new();
+        stats.record(StatusCode::FORBIDDEN);
+        assert_eq!(stats.snapshot().last_minute.client_errors, 1);
+
+        stats.reset();
+        assert_eq!(stats.snapshot().last_minute, ErrorWindow::default());
+    }
+}
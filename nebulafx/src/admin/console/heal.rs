@@ -0,0 +1,42 @@
+use axum::{
+    Json,
+    extract::Path,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use nebulafx_ahm::{Error as AhmError, get_heal_manager};
+use serde_json::json;
+use tracing::{error, info};
+
+/// List heal tasks currently running in the heal manager
+pub(crate) async fn list_heal_jobs() -> Response {
+    let Some(heal_manager) = get_heal_manager() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "heal manager is not initialized").into_response();
+    };
+
+    let jobs = heal_manager.list_active_tasks().await;
+    Json(json!({
+        "count": jobs.len(),
+        "jobs": jobs,
+    }))
+    .into_response()
+}
+
+/// Cancel a running heal task by ID
+pub(crate) async fn cancel_heal_job(Path(id): Path<String>) -> Response {
+    let Some(heal_manager) = get_heal_manager() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "heal manager is not initialized").into_response();
+    };
+
+    match heal_manager.cancel_task(&id).await {
+        Ok(()) => {
+            info!(target: "nebulafx::console::heal", job_id = %id, "Heal job cancelled from console");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(AhmError::TaskNotFound { .. }) => (StatusCode::NOT_FOUND, format!("heal job not found: {id}")).into_response(),
+        Err(e) => {
+            error!(target: "nebulafx::console::heal", job_id = %id, "Failed to cancel heal job: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
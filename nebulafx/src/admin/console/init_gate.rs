@@ -0,0 +1,120 @@
+use super::CONSOLE_PREFIX;
+use crate::config::get_config;
+use crate::server::{IamState, iam_readiness, startup_timeline};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{StatusCode, header::RETRY_AFTER};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Console paths, relative to `CONSOLE_PREFIX`, that answer while startup is in progress
+const INIT_GATE_EXEMPT: [&str; 2] = ["/livez", "/readyz"];
+
+/// Holds console requests back until the server can authenticate them
+#[derive(Clone)]
+pub(crate) struct InitGate {
+    ready: Arc<dyn Fn() -> bool + Send + Sync>,
+    /// Latched once `ready` first holds, so later requests skip the check
+    opened: Arc<AtomicBool>,
+}
+
+impl InitGate {
+    pub(crate) fn new(ready: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            ready: Arc::new(ready),
+            opened: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Gate from `console.auth_required_during_init`, or `None` when the flag is off
+    pub(crate) fn from_config() -> Option<Self> {
+        let required = get_config()
+            .console
+            .as_ref()
+            .and_then(|c| c.auth_required_during_init)
+            .unwrap_or(false);
+        required.then(|| Self::new(server_ready))
+    }
+
+    fn is_open(&self) -> bool {
+        if self.opened.load(Ordering::Acquire) {
+            return true;
+        }
+        let ready = (self.ready)();
+        if ready {
+            self.opened.store(true, Ordering::Release);
+        }
+        ready
+    }
+}
+
+/// Startup has completed and IAM is loaded, or not configured, so signed requests can be verified
+fn server_ready() -> bool {
+    let iam_ready = matches!(iam_readiness().current_state(), IamState::Ready | IamState::Disabled);
+    iam_ready && startup_timeline().report().status == "completed"
+}
+
+fn is_exempt(path: &str) -> bool {
+    path.strip_prefix(CONSOLE_PREFIX)
+        .is_some_and(|relative| INIT_GATE_EXEMPT.contains(&relative))
+}
+
+/// Answer 503 for every non-exempt console route until the gate opens
+pub(crate) async fn init_gate(State(gate): State<InitGate>, req: Request, next: Next) -> Response {
+    if !is_exempt(req.uri().path()) && !gate.is_open() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(RETRY_AFTER, "1")],
+            "console is unavailable until the server finishes starting",
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
+
+/// Liveness probe; answers as soon as the console is listening
+pub(crate) async fn livez() -> &'static str {
+    "ok"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    async fn status(app: &Router, path: &str) -> StatusCode {
+        let req = Request::builder().uri(path).body(Body::empty()).unwrap();
+        app.clone().oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_routes_unavailable_until_ready() {
+        let ready = Arc::new(AtomicBool::new(false));
+        let gate = InitGate::new({
+            let ready = ready.clone();
+            move || ready.load(Ordering::SeqCst)
+        });
+        let app = Router::new()
+            .route(&format!("{CONSOLE_PREFIX}/config.json"), get(|| async { "{}" }))
+            .route(&format!("{CONSOLE_PREFIX}/livez"), get(livez))
+            .layer(middleware::from_fn_with_state(gate, init_gate));
+
+        // Simulated init window
+        assert_eq!(
+            status(&app, &format!("{CONSOLE_PREFIX}/config.json")).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(status(&app, &format!("{CONSOLE_PREFIX}/livez")).await, StatusCode::OK);
+
+        ready.store(true, Ordering::SeqCst);
+        assert_eq!(status(&app, &format!("{CONSOLE_PREFIX}/config.json")).await, StatusCode::OK);
+
+        // The gate stays open once the server has been ready
+        ready.store(false, Ordering::SeqCst);
+        assert_eq!(status(&app, &format!("{CONSOLE_PREFIX}/config.json")).await, StatusCode::OK);
+    }
+}
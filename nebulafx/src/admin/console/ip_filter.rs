@@ -0,0 +1,178 @@
+use super::client_ip::ClientIp;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use ipnetwork::IpNetwork;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::{debug, error};
+
+/// Console allow and deny lists from `NEBULAFX_CONSOLE_ALLOW_CIDRS` and `NEBULAFX_CONSOLE_DENY_CIDRS`
+#[derive(Debug, Default)]
+pub(crate) struct IpFilter {
+    /// `None` admits every client not denied; an empty list admits none
+    allow: Option<Vec<IpNetwork>>,
+    deny: Vec<IpNetwork>,
+}
+
+impl IpFilter {
+    pub(crate) fn new(allow: Option<Vec<IpNetwork>>, deny: Vec<IpNetwork>) -> Self {
+        Self { allow, deny }
+    }
+
+    /// Filter from the environment, or `None` when neither list is set
+    ///
+    /// A list that does not parse refuses every client rather than leaving the console open.
+    pub(crate) fn from_env() -> Option<Arc<Self>> {
+        let allow = read_cidrs(nebulafx_config::ENV_CONSOLE_ALLOW_CIDRS);
+        let deny = read_cidrs(nebulafx_config::ENV_CONSOLE_DENY_CIDRS);
+        if allow.is_none() && deny.is_none() {
+            return None;
+        }
+        let filter = match (allow.transpose(), deny.transpose()) {
+            (Ok(allow), Ok(deny)) => Self::new(allow, deny.unwrap_or_default()),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("{}, refusing all console clients", e);
+                Self::new(Some(Vec::new()), Vec::new())
+            }
+        };
+        debug!(?filter, "Console IP filter configured");
+        Some(Arc::new(filter))
+    }
+
+    fn is_allowed(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        if self.deny.iter().any(|net| net.contains(ip)) {
+            return false;
+        }
+        self.allow
+            .as_ref()
+            .is_none_or(|allow| allow.iter().any(|net| net.contains(ip)))
+    }
+}
+
+/// The list in `var`, or `None` when it is unset or blank
+fn read_cidrs(var: &str) -> Option<Result<Vec<IpNetwork>, String>> {
+    let value = std::env::var(var).ok().filter(|v| !v.trim().is_empty())?;
+    Some(parse_cidrs(var, &value))
+}
+
+/// Parse comma-separated CIDR ranges; a bare address is a single-host range
+pub(super) fn parse_cidrs(var: &str, value: &str) -> Result<Vec<IpNetwork>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|cidr| !cidr.is_empty())
+        .map(|cidr| {
+            cidr.parse::<IpNetwork>()
+                .map_err(|e| format!("invalid {var} entry '{cidr}': {e}"))
+        })
+        .collect()
+}
+
+/// Answer 403 to clients outside the allow list or inside the deny list
+pub(crate) async fn ip_filter(State(filter): State<Arc<IpFilter>>, req: Request, next: Next) -> Response {
+    // Requests over the console Unix socket have no address to check
+    if let Some(client) = ClientIp::get(req.extensions()) {
+        if !filter.is_allowed(client) {
+            debug!(%client, path = %req.uri().path(), "Console request refused by IP filter");
+            return (StatusCode::FORBIDDEN, "console access is not allowed from this address").into_response();
+        }
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::console::client_ip::{TrustedProxies, resolve_client_ip};
+    use axum::{Router, body::Body, extract::ConnectInfo, middleware, routing::get};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    fn cidrs(value: &str) -> Vec<IpNetwork> {
+        parse_cidrs("TEST_CIDRS", value).unwrap()
+    }
+
+    async fn status(filter: IpFilter, peer: Option<&str>, forwarded_for: Option<&str>) -> StatusCode {
+        let proxies = TrustedProxies::new(cidrs("127.0.0.1"));
+        let app = Router::new()
+            .route("/nebulafx/console/config.json", get(|| async { "{}" }))
+            .layer(middleware::from_fn_with_state(Arc::new(filter), ip_filter))
+            .layer(middleware::from_fn_with_state(proxies, resolve_client_ip));
+        let mut req = Request::builder().uri("/nebulafx/console/config.json");
+        if let Some(forwarded_for) = forwarded_for {
+            req = req.header("x-forwarded-for", forwarded_for);
+        }
+        let mut req = req.body(Body::empty()).unwrap();
+        if let Some(peer) = peer {
+            req.extensions_mut().insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        }
+        app.oneshot(req).await.unwrap().status()
+    }
+
+    fn loopback_only() -> IpFilter {
+        IpFilter::new(Some(cidrs("127.0.0.0/8, ::1")), Vec::new())
+    }
+
+    #[tokio::test]
+    async fn test_loopback_allowed_everything_else_denied() {
+        assert_eq!(status(loopback_only(), Some("127.0.0.1:50000"), None).await, StatusCode::OK);
+        assert_eq!(status(loopback_only(), Some("[::1]:50000"), None).await, StatusCode::OK);
+        assert_eq!(status(loopback_only(), Some("[::ffff:127.0.0.1]:50000"), None).await, StatusCode::OK);
+        assert_eq!(status(loopback_only(), Some("10.0.0.1:50000"), None).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(loopback_only(), Some("203.0.113.7:50000"), None).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_address_only_trusted_from_proxies() {
+        // The trusted local proxy relaying an outside client
+        assert_eq!(
+            status(loopback_only(), Some("127.0.0.1:50000"), Some("203.0.113.7")).await,
+            StatusCode::FORBIDDEN
+        );
+        // A private peer that is not a listed proxy cannot claim to be local
+        let filter = IpFilter::new(Some(cidrs("127.0.0.0/8, 10.0.0.1")), Vec::new());
+        assert_eq!(status(filter, Some("10.0.0.2:50000"), Some("10.0.0.1")).await, StatusCode::FORBIDDEN);
+        // An outside client claiming to be local
+        assert_eq!(
+            status(loopback_only(), Some("203.0.113.7:50000"), Some("127.0.0.1")).await,
+            StatusCode::FORBIDDEN
+        );
+        // Unix socket requests have no peer address
+        assert_eq!(status(loopback_only(), None, None).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_deny_list_overrides_allow_list() {
+        let filter = IpFilter::new(Some(cidrs("10.0.0.0/8")), cidrs("10.0.13.0/24"));
+        assert_eq!(status(filter, Some("10.0.13.5:50000"), None).await, StatusCode::FORBIDDEN);
+
+        let filter = IpFilter::new(None, cidrs("198.51.100.1"));
+        assert_eq!(status(filter, Some("198.51.100.1:50000"), None).await, StatusCode::FORBIDDEN);
+        let filter = IpFilter::new(None, cidrs("198.51.100.1"));
+        assert_eq!(status(filter, Some("198.51.100.2:50000"), None).await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_cidr_lists_parsed_from_env() {
+        temp_env::with_vars_unset(
+            [
+                nebulafx_config::ENV_CONSOLE_ALLOW_CIDRS,
+                nebulafx_config::ENV_CONSOLE_DENY_CIDRS,
+            ],
+            || assert!(IpFilter::from_env().is_none()),
+        );
+        temp_env::with_var(nebulafx_config::ENV_CONSOLE_ALLOW_CIDRS, Some("127.0.0.0/8,,10.1.2.3"), || {
+            let filter = IpFilter::from_env().unwrap();
+            assert_eq!(filter.allow, Some(cidrs("127.0.0.0/8,10.1.2.3/32")));
+        });
+        temp_env::with_var(nebulafx_config::ENV_CONSOLE_DENY_CIDRS, Some("10.0.0.0/33"), || {
+            let filter = IpFilter::from_env().unwrap();
+            assert!(!filter.is_allowed("127.0.0.1".parse().unwrap()));
+        });
+    }
+}
@@ -0,0 +1,66 @@
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use http::{StatusCode, header};
+use metrics::{counter, histogram};
+use nebulafx_obs::{metrics_registry, render_prometheus};
+use nebulafx_postgresqlx::PostgreSQLPool;
+use std::time::Duration;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Count a console request by status and record its latency
+pub(crate) fn record_console_request(status: StatusCode, duration: Duration) {
+    counter!("nebulafx_console_requests_total", "status" => status.as_u16().to_string()).increment(1);
+    histogram!("nebulafx_console_request_duration_seconds").record(duration.as_secs_f64());
+}
+
+/// Pool occupancy is otherwise only refreshed by health checks
+fn refresh_pool_stats() {
+    if let Ok(pool) = PostgreSQLPool::get() {
+        pool.record_stats();
+    }
+}
+
+/// Current metrics in the Prometheus text format
+pub(crate) async fn prometheus_metrics() -> Response {
+    refresh_pool_stats();
+    let body = render_prometheus(&metrics_registry().snapshot());
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], body).into_response()
+}
+
+/// Current metrics as JSON, keyed by metric name with each family's type and samples
+pub(crate) async fn json_metrics() -> Response {
+    refresh_pool_stats();
+    Json(metrics_registry().snapshot()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, extract::Request, routing::get};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_prometheus_endpoint_exports_console_requests() {
+        let _ = nebulafx_obs::install_metrics_registry();
+        record_console_request(StatusCode::NOT_FOUND, Duration::from_millis(12));
+
+        let app = Router::new().route("/nebulafx/console/metrics", get(prometheus_metrics));
+        let req = Request::builder()
+            .uri("/nebulafx/console/metrics")
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], PROMETHEUS_CONTENT_TYPE);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE nebulafx_console_requests_total counter"), "{text}");
+        assert!(text.contains("nebulafx_console_requests_total{status=\"404\"}"), "{text}");
+        assert!(text.contains("nebulafx_console_request_duration_seconds_count"), "{text}");
+    }
+}
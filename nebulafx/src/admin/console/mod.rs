@@ -0,0 +1,1127 @@
+mod audit;
+mod buckets;
+mod client_ip;
+mod cors;
+mod database;
+mod errors;
+mod heal;
+mod init_gate;
+mod ip_filter;
+mod metrics;
+mod notifications;
+mod rate_limit;
+mod runtime;
+mod scanner;
+mod support;
+
+use shadow_rs::shadow;
+shadow!(build);
+use crate::admin::router::Extra;
+use crate::config::{LogLevel, get_config};
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Request, State},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+};
+use axum_extra::extract::Host;
+use axum_server::tls_rustls::RustlsConfig;
+pub(crate) use cors::reload_console_cors;
+use cors::{ConsoleCors, base_cors_layer, init_console_cors, parse_origin};
+use http::{HeaderMap, HeaderName, StatusCode, Uri};
+use nebulafx_utils::{humanize_bytes, humanize_duration};
+// use mime_guess::from_path; // 已移除：不再需要 MIME 类型检测（静态文件已移除）
+// use rust_embed::RustEmbed; // 已移除：前端独立运行，不再嵌入静态文件
+use nebulafx_config::{NEUBULAFX_TLS_CERT, NEUBULAFX_TLS_KEY};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    io::Result,
+    net::{IpAddr, SocketAddr},
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer};
+use tower_http::timeout::TimeoutLayer;
+use tower_http::trace::TraceLayer;
+use tracing::{Level, debug, error, info, instrument, warn};
+
+pub(crate) const CONSOLE_PREFIX: &str = "/nebulafx/console";
+const NEUBULAFX_ADMIN_PREFIX: &str = "/nebulafx/admin/v3";
+
+// 已移除静态文件嵌入功能：前端独立运行，不再嵌入到后端二进制中
+// 如果需要静态文件服务，请使用独立的前端服务器（如 Nuxt.js 开发服务器或 Nginx）
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct Config {
+    #[serde(skip)]
+    port: u16,
+    api: Api,
+    s3: S3,
+    release: Release,
+    doc: String,
+}
+
+impl Config {
+    fn new(local_ip: IpAddr, port: u16, region: &str, version: &str, date: &str) -> Self {
+        Config {
+            port,
+            api: Api {
+                base_url: format!("http://{local_ip}:{port}/{NEUBULAFX_ADMIN_PREFIX}"),
+            },
+            s3: S3 {
+                endpoint: format!("http://{local_ip}:{port}"),
+                region: region.to_owned(),
+            },
+            release: Release {
+                version: version.to_string(),
+                date: date.to_string(),
+            },
+            doc: "https://nebulafx.com/docs/".to_string(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn version_info(&self) -> String {
+        format!(
+            "RELEASE.{}@{} (rust {} {})",
+            self.release.date.clone(),
+            self.release.version.clone().trim_start_matches('@'),
+            build::RUST_VERSION,
+            build::BUILD_TARGET
+        )
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn version(&self) -> String {
+        self.release.version.clone()
+    }
+
+
+    /// Region advertised to the console for S3 requests
+    pub(crate) fn region(&self) -> &str {
+        &self.s3.region
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn doc(&self) -> String {
+        self.doc.clone()
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct Api {
+    #[serde(rename = "baseURL")]
+    base_url: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct S3 {
+    endpoint: String,
+    region: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct Release {
+    version: String,
+    date: String,
+}
+
+
+pub(crate) static CONSOLE_CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// When the console was initialized, the reference for the uptime in `/health`
+static CONSOLE_STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Seconds since [`init_console_cfg`], or 0 before it runs
+fn uptime_secs() -> u64 {
+    CONSOLE_STARTED_AT.get().map_or(0, |started| started.elapsed().as_secs())
+}
+
+/// Version and build details of this binary
+pub(crate) fn build_info() -> serde_json::Value {
+    json!({
+        "version": build::PKG_VERSION,
+        "tag": build::TAG,
+        "commit": build::COMMIT_HASH,
+        "commit_date": build::COMMIT_DATE_3339,
+        "build_time": build::BUILD_TIME_3339,
+        "rust_version": build::RUST_VERSION,
+        "target": build::BUILD_TARGET,
+    })
+}
+
+#[allow(clippy::const_is_empty)]
+pub(crate) fn init_console_cfg(local_ip: IpAddr, port: u16) {
+    CONSOLE_STARTED_AT.get_or_init(Instant::now);
+    CONSOLE_CONFIG.get_or_init(|| {
+        let ver = {
+            if !build::TAG.is_empty() {
+                build::TAG.to_string()
+            } else if !build::SHORT_COMMIT.is_empty() {
+                format!("@{}", build::SHORT_COMMIT)
+            } else {
+                build::PKG_VERSION.to_string()
+            }
+        };
+
+        let region = nebulafx_ecstore::global::get_global_region().unwrap_or_else(|| crate::config::DEFAULT_REGION.to_string());
+        Config::new(local_ip, port, &region, ver.as_str(), build::COMMIT_DATE_3339)
+    });
+}
+
+// fn is_socket_addr_or_ip_addr(host: &str) -> bool {
+//     host.parse::<SocketAddr>().is_ok() || host.parse::<IpAddr>().is_ok()
+// }
+
+#[instrument(fields(host))]
+pub async fn config_handler(uri: Uri, Host(host): Host, headers: HeaderMap) -> impl IntoResponse {
+    // Get the scheme from the headers or use the URI scheme
+    let scheme = headers
+        .get(HeaderName::from_static("x-forwarded-proto"))
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_else(|| uri.scheme().map(|s| s.as_str()).unwrap_or("http"));
+
+    let raw_host = uri.host().unwrap_or(host.as_str());
+    let host_for_url = if let Ok(socket_addr) = raw_host.parse::<SocketAddr>() {
+        // Successfully parsed, it's in IP:Port format.
+        // For IPv6, we need to enclose it in brackets to form a valid URL.
+        let ip = socket_addr.ip();
+        if ip.is_ipv6() { format!("[{ip}]") } else { format!("{ip}") }
+    } else if let Ok(ip) = raw_host.parse::<IpAddr>() {
+        // Pure IP (no ports)
+        if ip.is_ipv6() { format!("[{ip}]") } else { ip.to_string() }
+    } else {
+        // The domain name may not be able to resolve directly to IP, remove the port
+        raw_host.split(':').next().unwrap_or(raw_host).to_string()
+    };
+
+    // Make a copy of the current configuration
+    let mut cfg = match CONSOLE_CONFIG.get() {
+        Some(cfg) => cfg.clone(),
+        None => {
+            error!("Console configuration not initialized");
+            return Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from("Console configuration not initialized"))
+                .unwrap();
+        }
+    };
+
+    let url = format!("{}://{}:{}", scheme, host_for_url, cfg.port);
+    cfg.api.base_url = format!("{url}{NEUBULAFX_ADMIN_PREFIX}");
+    cfg.s3.endpoint = url;
+
+    Response::builder()
+        .header("content-type", "application/json")
+        .status(StatusCode::OK)
+        .body(Body::from(cfg.to_json()))
+        .unwrap()
+}
+
+/// Target of the access log lines written when `NEBULAFX_CONSOLE_ACCESS_LOG_JSON` is on
+const ACCESS_LOG_JSON_TARGET: &str = "nebulafx::console::access_json";
+
+/// Access-log settings for the console middleware
+#[derive(Debug, Clone)]
+struct AccessLogSettings {
+    level: LogLevel,
+    exclude_paths: Arc<HashSet<String>>,
+    /// Write one JSON object per request instead of tracing fields
+    json: bool,
+}
+
+impl AccessLogSettings {
+    fn from_config() -> Self {
+        let console = get_config().console.as_ref();
+        let level = console.and_then(|c| c.access_log_level).unwrap_or(LogLevel::Info);
+        let exclude_paths = console
+            .and_then(|c| c.access_log_exclude_paths.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let json = std::env::var(nebulafx_config::ENV_CONSOLE_ACCESS_LOG_JSON)
+            .ok()
+            .and_then(|v| v.trim().parse::<bool>().ok())
+            .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_ACCESS_LOG_JSON);
+
+        Self {
+            level,
+            exclude_paths: Arc::new(exclude_paths),
+            json,
+        }
+    }
+
+    /// Excluded paths match either the full request path or the path relative to `CONSOLE_PREFIX`
+    fn is_excluded(&self, path: &str) -> bool {
+        self.exclude_paths.contains(path)
+            || path
+                .strip_prefix(CONSOLE_PREFIX)
+                .is_some_and(|relative| self.exclude_paths.contains(relative))
+    }
+}
+
+/// Correlation id of a console request, taken from `X-Request-Id` or generated for it
+pub(crate) fn request_id(extensions: &http::Extensions) -> Option<&str> {
+    extensions.get::<RequestId>().and_then(|id| id.header_value().to_str().ok())
+}
+
+/// Keep a client's `X-Request-Id`, or assign a UUID, and echo it on the response
+///
+/// Handlers read it with [`request_id`] or `Extension<RequestId>`.
+fn with_request_id(app: Router) -> Router {
+    app.layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+/// One request in the JSON access log
+#[derive(Debug, Serialize)]
+struct AccessLogLine<'a> {
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    duration_ms: u64,
+    client_ip: Option<IpAddr>,
+    request_id: Option<&'a str>,
+    /// Known only for bodies of a fixed size
+    response_size: Option<u64>,
+}
+
+/// Console access logging middleware
+async fn console_logging_middleware(
+    State(settings): State<AccessLogSettings>,
+    req: Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let excluded = settings.is_excluded(req.uri().path());
+    let method = req.method().clone();
+    let uri = req.uri().clone();
+    let client_ip = client_ip::ClientIp::get(req.extensions());
+    let request_id = request_id(req.extensions()).map(str::to_string);
+    let start = std::time::Instant::now();
+    let response = next.run(req).await;
+    let duration = start.elapsed();
+    let status = response.status();
+    errors::error_stats().record(status);
+    // Excluded paths are still counted, only their log line is skipped
+    metrics::record_console_request(status, duration);
+    if excluded {
+        return response;
+    }
+
+    let json_line = settings.json.then(|| {
+        let line = AccessLogLine {
+            method: method.as_str(),
+            path: uri.path(),
+            status: status.as_u16(),
+            duration_ms: duration.as_millis() as u64,
+            client_ip,
+            request_id: request_id.as_deref(),
+            response_size: http_body::Body::size_hint(response.body()).exact(),
+        };
+        serde_json::to_string(&line).unwrap_or_default()
+    });
+
+    macro_rules! access_log {
+        ($level:expr) => {
+            match &json_line {
+                Some(line) => tracing::event!(target: ACCESS_LOG_JSON_TARGET, $level, "{}", line),
+                None => tracing::event!(
+                    target: "nebulafx::console::access",
+                    $level,
+                    method = %method,
+                    uri = %uri,
+                    status = %status,
+                    duration_ms = %duration.as_millis(),
+                    duration = %humanize_duration(duration),
+                    request_id = request_id.as_deref().unwrap_or_default(),
+                    "Console access"
+                ),
+            }
+        };
+    }
+
+    match settings.level {
+        LogLevel::Trace => access_log!(Level::TRACE),
+        LogLevel::Debug => access_log!(Level::DEBUG),
+        LogLevel::Info => access_log!(Level::INFO),
+        LogLevel::Warn => access_log!(Level::WARN),
+        LogLevel::Error => access_log!(Level::ERROR),
+    }
+
+    response
+}
+
+/// Reject console requests that were not signed with valid credentials
+async fn console_auth_middleware(req: Request, next: axum::middleware::Next) -> axum::response::Response {
+    let authenticated = req
+        .extensions()
+        .get::<Extra>()
+        .is_some_and(|extra| extra.credentials.is_some());
+
+    if !authenticated {
+        return (StatusCode::UNAUTHORIZED, "Signature is required").into_response();
+    }
+
+    next.run(req).await
+}
+
+/// Setup TLS configuration for console using axum-server, following endpoint TLS implementation logic
+#[instrument(skip(tls_path))]
+async fn _setup_console_tls_config(tls_path: Option<&String>) -> Result<Option<RustlsConfig>> {
+    let tls_path = match tls_path {
+        Some(path) if !path.is_empty() => path,
+        _ => {
+            debug!("TLS path is not provided, console starting with HTTP");
+            return Ok(None);
+        }
+    };
+
+    if tokio::fs::metadata(tls_path).await.is_err() {
+        debug!("TLS path does not exist, console starting with HTTP");
+        return Ok(None);
+    }
+
+    debug!("Found TLS directory for console, checking for certificates");
+
+    // Make sure to use a modern encryption suite
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    // 1. Attempt to load all certificates in the directory (multi-certificate support, for SNI)
+    if let Ok(cert_key_pairs) = nebulafx_utils::load_all_certs_from_directory(tls_path) {
+        if !cert_key_pairs.is_empty() {
+            debug!(
+                "Found {} certificates for console, creating SNI-aware multi-cert resolver",
+                cert_key_pairs.len()
+            );
+
+            // Create an SNI-enabled certificate resolver
+            let resolver = nebulafx_utils::create_multi_cert_resolver(cert_key_pairs)?;
+
+            // Configure the server to enable SNI support
+            let mut server_config =
+                crate::server::tls_server_builder(get_config().tls.as_ref())?.with_cert_resolver(Arc::new(resolver));
+
+            // Configure ALPN protocol priority
+            server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec(), b"http/1.0".to_vec()];
+
+            // Log SNI requests
+            if nebulafx_utils::tls_key_log() {
+                server_config.key_log = Arc::new(rustls::KeyLogFile::new());
+            }
+
+            info!(target: "nebulafx::console::tls", "Console TLS enabled with multi-certificate SNI support");
+            return Ok(Some(RustlsConfig::from_config(Arc::new(server_config))));
+        }
+    }
+
+    // 2. Revert to the traditional single-certificate mode
+    let key_path = format!("{tls_path}/{NEUBULAFX_TLS_KEY}");
+    let cert_path = format!("{tls_path}/{NEUBULAFX_TLS_CERT}");
+    if tokio::try_join!(tokio::fs::metadata(&key_path), tokio::fs::metadata(&cert_path)).is_ok() {
+        debug!("Found legacy single TLS certificate for console, starting with HTTPS");
+
+        return match RustlsConfig::from_pem_file(cert_path, key_path).await {
+            Ok(config) => {
+                info!(target: "nebulafx::console::tls", "Console TLS enabled with single certificate");
+                Ok(Some(config))
+            }
+            Err(e) => {
+                error!(target: "nebulafx::console::error", error = %e, "Failed to create TLS config for console");
+                Err(std::io::Error::other(e))
+            }
+        };
+    }
+
+    debug!("No valid TLS certificates found in the directory for console, starting with HTTP");
+    Ok(None)
+}
+
+/// Parse a body limit such as `1048576`, `10MB` or `1GiB` into bytes
+fn parse_body_limit(value: &str) -> std::result::Result<usize, String> {
+    let size = value
+        .trim()
+        .parse::<bytesize::ByteSize>()
+        .map_err(|e| format!("invalid {} '{value}': {e}", nebulafx_config::ENV_CONSOLE_BODY_LIMIT))?;
+    match usize::try_from(size.as_u64()) {
+        Ok(0) => Err(format!("{} must be greater than zero", nebulafx_config::ENV_CONSOLE_BODY_LIMIT)),
+        Ok(limit) => Ok(limit),
+        Err(_) => Err(format!("{} '{value}' is too large", nebulafx_config::ENV_CONSOLE_BODY_LIMIT)),
+    }
+}
+
+/// Get console configuration from environment variables
+fn get_console_config_from_env() -> (bool, u32, u64, String, usize) {
+    let rate_limit_enable = std::env::var(nebulafx_config::ENV_CONSOLE_RATE_LIMIT_ENABLE)
+        .unwrap_or_else(|_| nebulafx_config::DEFAULT_CONSOLE_RATE_LIMIT_ENABLE.to_string())
+        .parse::<bool>()
+        .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_RATE_LIMIT_ENABLE);
+
+    let rate_limit_rpm = std::env::var(nebulafx_config::ENV_CONSOLE_RATE_LIMIT_RPM)
+        .unwrap_or_else(|_| nebulafx_config::DEFAULT_CONSOLE_RATE_LIMIT_RPM.to_string())
+        .parse::<u32>()
+        .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_RATE_LIMIT_RPM);
+
+    let auth_timeout = std::env::var(nebulafx_config::ENV_CONSOLE_AUTH_TIMEOUT)
+        .unwrap_or_else(|_| nebulafx_config::DEFAULT_CONSOLE_AUTH_TIMEOUT.to_string())
+        .parse::<u64>()
+        .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_AUTH_TIMEOUT);
+    let cors_allowed_origins = std::env::var(nebulafx_config::ENV_CONSOLE_CORS_ALLOWED_ORIGINS)
+        .unwrap_or_else(|_| nebulafx_config::DEFAULT_CONSOLE_CORS_ALLOWED_ORIGINS.to_string())
+        .parse::<String>()
+        .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_CORS_ALLOWED_ORIGINS.to_string());
+
+    let body_limit = match std::env::var(nebulafx_config::ENV_CONSOLE_BODY_LIMIT) {
+        Ok(value) => parse_body_limit(&value).unwrap_or_else(|e| {
+            warn!("{}, using the default", e);
+            nebulafx_config::DEFAULT_CONSOLE_BODY_LIMIT
+        }),
+        Err(_) => nebulafx_config::DEFAULT_CONSOLE_BODY_LIMIT,
+    };
+
+    (rate_limit_enable, rate_limit_rpm, auth_timeout, cors_allowed_origins, body_limit)
+}
+
+pub fn is_console_path(path: &str) -> bool {
+    // 只检查 Console API 路径，不再提供静态文件服务
+    path.starts_with(CONSOLE_PREFIX)
+}
+
+/// Setup comprehensive middleware stack with tower-http features
+/// 注意：已移除静态文件服务，只保留 API 端点
+fn setup_console_middleware_stack(
+    cors: ConsoleCors,
+    rate_limit_enable: bool,
+    rate_limit_rpm: u32,
+    auth_timeout: u64,
+    body_limit: usize,
+) -> Router {
+    // 只注册 API 端点，不提供静态文件服务（前端独立运行）
+    let mut app = Router::new()
+        .route(&format!("{CONSOLE_PREFIX}/config.json"), get(config_handler))
+        .route(&format!("{CONSOLE_PREFIX}/health"), get(health_check))
+        .route(&format!("{CONSOLE_PREFIX}/livez"), get(init_gate::livez))
+        .route(&format!("{CONSOLE_PREFIX}/readyz"), get(readyz));
+
+    // Endpoints below require a signed request
+    let protected = Router::new()
+        .route(&format!("{CONSOLE_PREFIX}/metrics"), get(metrics::prometheus_metrics))
+        .route(&format!("{CONSOLE_PREFIX}/metrics.json"), get(metrics::json_metrics))
+        .route(&format!("{CONSOLE_PREFIX}/debug/runtime"), get(runtime::runtime_metrics))
+        .route(&format!("{CONSOLE_PREFIX}/debug/startup"), get(runtime::startup_report))
+        .route(
+            &format!("{CONSOLE_PREFIX}/debug/errors"),
+            get(errors::error_rates).delete(errors::reset_error_rates),
+        )
+        .route(&format!("{CONSOLE_PREFIX}/cors"), put(cors::update_cors))
+        .route(&format!("{CONSOLE_PREFIX}/db/reconnect"), post(database::reconnect_database))
+        .route(
+            &format!("{CONSOLE_PREFIX}/scanner/config"),
+            get(scanner::get_scanner_config)
+                .put(scanner::update_scanner_config)
+                .delete(scanner::clear_scanner_config),
+        )
+        .route(&format!("{CONSOLE_PREFIX}/heal/jobs"), get(heal::list_heal_jobs))
+        .route(&format!("{CONSOLE_PREFIX}/heal/jobs/{{id}}"), delete(heal::cancel_heal_job))
+        .route(&format!("{CONSOLE_PREFIX}/audit/tail"), get(audit::tail_audit_log))
+        .route(&format!("{CONSOLE_PREFIX}/buckets"), get(buckets::list_buckets))
+        .route(&format!("{CONSOLE_PREFIX}/buckets/{{bucket}}/export"), get(buckets::export_bucket))
+        .route(&format!("{CONSOLE_PREFIX}/support-bundle"), get(support::download_support_bundle))
+        .route(
+            &format!("{CONSOLE_PREFIX}/notifications/test"),
+            post(notifications::test_notification_target),
+        )
+        .route_layer(middleware::from_fn(console_auth_middleware));
+    app = app.merge(protected);
+
+    // Installed with the routes, so no route is reachable before the gate during init
+    if let Some(gate) = init_gate::InitGate::from_config() {
+        info!("Console routes other than /livez and /readyz return 503 until the server is ready");
+        app = app.layer(middleware::from_fn_with_state(gate, init_gate::init_gate));
+    }
+
+    // Add comprehensive middleware layers using tower-http features
+    app = app
+        .layer(CatchPanicLayer::new())
+        .layer(TraceLayer::new_for_http())
+        // Compress responses
+        .layer(CompressionLayer::new())
+        .layer(middleware::from_fn_with_state(
+            AccessLogSettings::from_config(),
+            console_logging_middleware,
+        ))
+        // CORS origins can be replaced at runtime through PUT /cors
+        .layer(middleware::from_fn_with_state(cors, cors::dynamic_cors))
+        // Add timeout layer - convert auth_timeout from seconds to Duration
+        .layer(TimeoutLayer::new(Duration::from_secs(auth_timeout)))
+        // Reject larger bodies with 413 (default 10 MiB, NEBULAFX_CONSOLE_BODY_LIMIT)
+        .layer(RequestBodyLimitLayer::new(body_limit));
+    debug!(
+        body_limit_bytes = body_limit,
+        body_limit = %humanize_bytes(body_limit as u64),
+        "Console request body limit configured"
+    );
+
+    // Outside everything but the IP filter, so limited clients are turned away before any other work
+    if rate_limit_enable {
+        info!("Console rate limiting enabled: {} requests per minute per client", rate_limit_rpm);
+        app = app.layer(middleware::from_fn_with_state(
+            rate_limit::RateLimiter::new(rate_limit_rpm),
+            rate_limit::rate_limit,
+        ));
+    }
+
+    // Refused addresses get 403 before they reach routing or use up rate limit tokens
+    if let Some(filter) = ip_filter::IpFilter::from_env() {
+        info!("Console IP filter enabled");
+        app = app.layer(middleware::from_fn_with_state(filter, ip_filter::ip_filter));
+    }
+
+    // Outermost, so the filter, the rate limiter and the access log agree on the client address
+    app = app.layer(middleware::from_fn_with_state(
+        client_ip::TrustedProxies::from_env(),
+        client_ip::resolve_client_ip,
+    ));
+
+    // Requests over the console socket arrive without the id the S3 listener assigns
+    with_request_id(app)
+}
+
+/// Readiness probe; 503 until the object store and IAM are initialized
+///
+/// IAM counts as ready when no `[database]` is configured, since it is never initialized then.
+/// Dependencies such as the database only degrade `/health`, so they do not take the
+/// console out of rotation.
+pub(crate) async fn readyz() -> Response {
+    readiness(storage_ready(), iam_ready())
+}
+
+/// IAM is loaded, or not configured at all
+fn iam_ready() -> bool {
+    get_config().database.is_none() || nebulafx_iam::get().is_ok()
+}
+
+fn readiness(storage: bool, iam: bool) -> Response {
+    let status = if storage && iam {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let state = |ready: bool| if ready { "ready" } else { "not_ready" };
+    (status, Json(json!({"storage": state(storage), "iam": state(iam)}))).into_response()
+}
+
+fn storage_ready() -> bool {
+    STORAGE_READINESS.is_connected(|| nebulafx_ecstore::new_object_layer_fn().is_some())
+}
+
+/// Console health check handler with comprehensive health information
+pub(crate) async fn health_check() -> Json<serde_json::Value> {
+    let mut health_status = "ok";
+    let mut details = json!({});
+
+    // Check storage backend health
+    if storage_ready() {
+        details["storage"] = json!({"status": "connected"});
+    } else {
+        health_status = "degraded";
+        details["storage"] = json!({"status": "disconnected"});
+    }
+
+    // Check IAM system health
+    match nebulafx_iam::get() {
+        Ok(_) => {
+            details["iam"] = json!({"status": "connected"});
+        }
+        Err(_) => {
+            health_status = "degraded";
+            // Lazy IAM init reports "initializing" until loading completes
+            let state = crate::server::iam_readiness().current_state();
+            let status = if state == crate::server::IamState::Initializing {
+                "initializing"
+            } else {
+                "disconnected"
+            };
+            details["iam"] = json!({"status": status, "state": state.as_str()});
+        }
+    }
+
+    // Check database pool health; Postgres is optional, so only probe when configured
+    let configured = get_config().database.is_some();
+    let probe = match nebulafx_postgresqlx::PostgreSQLPool::get() {
+        Ok(pool) if configured => Some(DatabaseProbe {
+            connected: pool.health_check().await.unwrap_or(false),
+            pool: pool.name().to_string(),
+            size: pool.inner().size(),
+            idle: pool.inner().num_idle(),
+        }),
+        _ => None,
+    };
+    let (database, degraded) = database_health(configured, probe);
+    if degraded {
+        health_status = "degraded";
+    }
+    details["database"] = database;
+
+    // Data/parity split of each pool, empty until the endpoints are resolved
+    details["erasure"] = json!(nebulafx_ecstore::global::get_global_erasure_layout());
+
+    // Free space of the tightest local drive, when a floor is configured
+    if let Some(guard) = crate::storage::free_space::free_space_guard() {
+        if !guard.allows_writes() {
+            health_status = "degraded";
+        }
+        details["disk"] = guard.status();
+    }
+
+    // Days to expiry of each served certificate, when TLS is enabled
+    if let Some(certs) = crate::server::tls_cert_status() {
+        if certs.iter().any(|cert| cert.status == crate::server::CertStatus::Expired) {
+            health_status = "degraded";
+        }
+        details["tls"] = json!(certs);
+    }
+
+    Json(json!({
+        "status": health_status,
+        "service": "nebulafx-console",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "details": details,
+        "last_shutdown": crate::server::last_shutdown_reason(),
+        "region": nebulafx_ecstore::global::get_global_region(),
+        "role": get_config().server.as_ref().and_then(|s| s.role).unwrap_or_default().as_str(),
+        "uptime": uptime_secs()
+    }))
+}
+
+/// How long a missing store is cached before health probes look it up again
+const STORAGE_LOOKUP_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Debounced view of whether the object layer is up, for health probes during startup
+///
+/// The store is set once and never removed, so the first successful lookup is latched.
+/// Until then a miss is cached for `STORAGE_LOOKUP_DEBOUNCE`, which bounds both the lookup
+/// rate under rapid probes and how long the store can be up before health reports it.
+struct StorageReadiness {
+    connected: AtomicBool,
+    last_miss: Mutex<Option<Instant>>,
+}
+
+impl StorageReadiness {
+    const fn new() -> Self {
+        Self {
+            connected: AtomicBool::new(false),
+            last_miss: Mutex::new(None),
+        }
+    }
+
+    fn is_connected(&self, lookup: impl FnOnce() -> bool) -> bool {
+        self.is_connected_at(Instant::now(), lookup)
+    }
+
+    fn is_connected_at(&self, now: Instant, lookup: impl FnOnce() -> bool) -> bool {
+        if self.connected.load(Ordering::Acquire) {
+            return true;
+        }
+        let mut last_miss = self.last_miss.lock().unwrap();
+        if last_miss.is_some_and(|at| now.saturating_duration_since(at) < STORAGE_LOOKUP_DEBOUNCE) {
+            return false;
+        }
+        if lookup() {
+            self.connected.store(true, Ordering::Release);
+            true
+        } else {
+            *last_miss = Some(now);
+            false
+        }
+    }
+}
+
+static STORAGE_READINESS: StorageReadiness = StorageReadiness::new();
+
+/// Result of probing the database pool for the health check
+struct DatabaseProbe {
+    connected: bool,
+    pool: String,
+    size: u32,
+    idle: usize,
+}
+
+/// Database health detail, and whether it degrades the overall status
+///
+/// `probe` is `None` when the pool is not initialized. Without a `[database]` section
+/// the database is reported as `not_configured` and never degrades the status.
+fn database_health(configured: bool, probe: Option<DatabaseProbe>) -> (serde_json::Value, bool) {
+    match (configured, probe) {
+        (false, _) => (json!({"status": "not_configured"}), false),
+        (true, Some(probe)) => (
+            json!({
+                "status": if probe.connected { "connected" } else { "disconnected" },
+                "pool": probe.pool,
+                "size": probe.size,
+                "idle": probe.idle,
+            }),
+            !probe.connected,
+        ),
+        (true, None) => (json!({"status": "disconnected"}), true),
+    }
+}
+
+/// Parse CORS allowed origins from configuration
+pub fn parse_cors_origins(origins: Option<&String>) -> CorsLayer {
+    let cors_layer = base_cors_layer();
+
+    match origins {
+        Some(origins_str) if origins_str == "*" => cors_layer.allow_origin(Any).expose_headers(Any),
+        Some(origins_str) => {
+            let origins: Vec<&str> = origins_str.split(',').map(|s| s.trim()).collect();
+            if origins.is_empty() {
+                warn!("Empty CORS origins provided, using permissive CORS");
+                cors_layer.allow_origin(Any).expose_headers(Any)
+            } else {
+                // Parse origins with proper error handling
+                let mut valid_origins = Vec::new();
+                for origin in origins {
+                    match parse_origin(origin) {
+                        Ok(header_value) => {
+                            valid_origins.push(header_value);
+                        }
+                        Err(e) => {
+                            warn!("{}", e);
+                        }
+                    }
+                }
+
+                if valid_origins.is_empty() {
+                    warn!("No valid CORS origins found, using permissive CORS");
+                    cors_layer.allow_origin(Any).expose_headers(Any)
+                } else {
+                    info!("Console CORS origins configured: {:?}", valid_origins);
+                    cors_layer.allow_origin(AllowOrigin::list(valid_origins)).expose_headers(Any)
+                }
+            }
+        }
+        None => {
+            debug!("No CORS origins configured for console, using permissive CORS");
+            cors_layer.allow_origin(Any)
+        }
+    }
+}
+
+pub(crate) fn make_console_server() -> Router {
+    let (rate_limit_enable, rate_limit_rpm, auth_timeout, cors_allowed_origins, body_limit) = get_console_config_from_env();
+    // String to Option<&String>
+    let cors_allowed_origins = if cors_allowed_origins.is_empty() {
+        None
+    } else {
+        Some(&cors_allowed_origins)
+    };
+    // Configure CORS based on settings
+    let cors_layer = parse_cors_origins(cors_allowed_origins);
+
+    // Build console router with enhanced middleware stack using tower-http features
+    setup_console_middleware_stack(init_console_cors(cors_layer), rate_limit_enable, rate_limit_rpm, auth_timeout, body_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_utils::testing::CapturedLogs;
+
+    fn settings_excluding(paths: &[&str]) -> AccessLogSettings {
+        AccessLogSettings {
+            level: LogLevel::Info,
+            exclude_paths: Arc::new(paths.iter().map(|p| p.to_string()).collect()),
+            json: false,
+        }
+    }
+
+    #[test]
+    fn test_access_log_excludes_relative_and_full_paths() {
+        let settings = settings_excluding(&["/livez", "/nebulafx/console/readyz"]);
+
+        assert!(settings.is_excluded("/nebulafx/console/livez"));
+        assert!(settings.is_excluded("/nebulafx/console/readyz"));
+        assert!(!settings.is_excluded("/nebulafx/console/config.json"));
+    }
+
+    #[test]
+    fn test_access_log_without_exclusions_logs_everything() {
+        let settings = settings_excluding(&[]);
+        assert!(!settings.is_excluded("/nebulafx/console/livez"));
+    }
+
+    #[tokio::test]
+    async fn test_excluded_path_produces_no_access_log_event() {
+        use tower::ServiceExt;
+
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .without_time()
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/nebulafx/console/livez", get(|| async { "ok" }))
+            .route("/nebulafx/console/config.json", get(|| async { "{}" }))
+            .layer(middleware::from_fn_with_state(
+                settings_excluding(&["/livez"]),
+                console_logging_middleware,
+            ));
+        let get_path = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+
+        let resp = app.clone().oneshot(get_path("/nebulafx/console/livez")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(logs.contents(), "");
+
+        let resp = app.oneshot(get_path("/nebulafx/console/config.json")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let output = logs.contents();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1, "{output}");
+        let line = lines[0].trim_start();
+        assert!(line.starts_with("INFO nebulafx::console::access: Console access"), "{output}");
+        assert!(line.contains("uri=/nebulafx/console/config.json"), "{output}");
+        assert!(line.contains("status=200 OK"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_preserved_or_generated_and_echoed() {
+        use tower::ServiceExt;
+
+        // The handler returns the id it sees, so the test can compare it with the echoed header
+        let app = with_request_id(Router::new().route(
+            "/nebulafx/console/config.json",
+            get(|req: Request| async move { request_id(req.extensions()).unwrap_or_default().to_string() }),
+        ));
+        let send = |id: Option<&'static str>| {
+            let mut req = Request::builder().uri("/nebulafx/console/config.json");
+            if let Some(id) = id {
+                req = req.header("x-request-id", id);
+            }
+            app.clone().oneshot(req.body(Body::empty()).unwrap())
+        };
+        let body = |resp: Response| async move {
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+
+        let resp = send(Some("client-id/42 abc")).await.unwrap();
+        assert_eq!(resp.headers()["x-request-id"], "client-id/42 abc");
+        assert_eq!(body(resp).await, "client-id/42 abc");
+
+        let resp = send(None).await.unwrap();
+        let generated = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&generated).is_ok(), "{generated}");
+        assert_eq!(body(resp).await, generated);
+    }
+
+    #[tokio::test]
+    async fn test_json_access_log_line() {
+        use tower::ServiceExt;
+
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .without_time()
+            .with_level(false)
+            .with_target(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let settings = AccessLogSettings {
+            json: true,
+            ..settings_excluding(&[])
+        };
+        let app = with_request_id(
+            Router::new()
+                .route("/nebulafx/console/config.json", get(|| async { "{\"ok\":true}" }))
+                .layer(middleware::from_fn_with_state(settings, console_logging_middleware))
+                .layer(middleware::from_fn_with_state(
+                    client_ip::TrustedProxies::default(),
+                    client_ip::resolve_client_ip,
+                )),
+        );
+        let mut req = Request::builder()
+            .uri("/nebulafx/console/config.json?x=1")
+            .header("x-request-id", "req-1")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut()
+            .insert(axum::extract::ConnectInfo("127.0.0.1:50000".parse::<SocketAddr>().unwrap()));
+        assert_eq!(app.oneshot(req).await.unwrap().status(), StatusCode::OK);
+
+        let output = logs.contents();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["method"], "GET");
+        assert_eq!(line["path"], "/nebulafx/console/config.json");
+        assert_eq!(line["status"], 200);
+        assert!(line["duration_ms"].is_u64());
+        assert_eq!(line["client_ip"], "127.0.0.1");
+        assert_eq!(line["request_id"], "req-1");
+        assert_eq!(line["response_size"], 11);
+    }
+
+    #[test]
+    fn test_body_limit_suffixes_parsed() {
+        assert_eq!(parse_body_limit("1048576"), Ok(1024 * 1024));
+        assert_eq!(parse_body_limit("10MB"), Ok(10_000_000));
+        assert_eq!(parse_body_limit(" 10 MiB "), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_body_limit("1GB"), Ok(1_000_000_000));
+        assert!(parse_body_limit("0").is_err());
+        assert!(parse_body_limit("lots").unwrap_err().contains("NEBULAFX_CONSOLE_BODY_LIMIT"));
+    }
+
+    #[test]
+    fn test_body_limit_read_from_env() {
+        temp_env::with_var(nebulafx_config::ENV_CONSOLE_BODY_LIMIT, Some("2MB"), || {
+            assert_eq!(get_console_config_from_env().4, 2_000_000);
+        });
+        temp_env::with_var(nebulafx_config::ENV_CONSOLE_BODY_LIMIT, Some("not-a-size"), || {
+            assert_eq!(get_console_config_from_env().4, nebulafx_config::DEFAULT_CONSOLE_BODY_LIMIT);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_body_over_limit_rejected_with_413() {
+        use axum::routing::post;
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                &format!("{CONSOLE_PREFIX}/cors"),
+                post(|body: String| async move { body.len().to_string() }),
+            )
+            .layer(RequestBodyLimitLayer::new(parse_body_limit("1KB").unwrap()));
+        let send = |size: usize| {
+            let req = Request::builder()
+                .method("POST")
+                .uri(format!("{CONSOLE_PREFIX}/cors"))
+                .header(http::header::CONTENT_LENGTH, size)
+                .body(Body::from(vec![b'a'; size]))
+                .unwrap();
+            app.clone().oneshot(req)
+        };
+
+        assert_eq!(send(1000).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send(1001).await.unwrap().status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn test_uptime_counts_from_console_start() {
+        CONSOLE_STARTED_AT.get_or_init(Instant::now);
+        std::thread::sleep(Duration::from_millis(20));
+        let first = uptime_secs();
+        std::thread::sleep(Duration::from_millis(20));
+        let second = uptime_secs();
+
+        // Wall-clock epoch seconds would be in the billions
+        assert!(second < 60, "uptime {second}s");
+        assert!(second >= first);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_unavailable_until_store_and_iam_are_up() {
+        let store = StorageReadiness::new();
+        let start = Instant::now();
+
+        let before = readiness(store.is_connected_at(start, || false), true);
+        assert_eq!(before.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(before.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["storage"], "not_ready");
+        assert_eq!(body["iam"], "ready");
+
+        // The store layer is set
+        let set = store.is_connected_at(start + STORAGE_LOOKUP_DEBOUNCE, || true);
+        assert_eq!(readiness(set, false).status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(readiness(set, true).status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_iam_ready_without_database() {
+        crate::config::init_test_config();
+        assert!(get_config().database.is_none());
+        assert!(iam_ready());
+    }
+
+    #[test]
+    fn test_storage_readiness_flips_once_store_is_available() {
+        let readiness = StorageReadiness::new();
+        let start = Instant::now();
+        let lookups = std::cell::Cell::new(0);
+        let lookup = |available: bool| {
+            let lookups = &lookups;
+            move || {
+                lookups.set(lookups.get() + 1);
+                available
+            }
+        };
+
+        assert!(!readiness.is_connected_at(start, lookup(false)));
+        // Rapid probes inside the debounce window reuse the miss
+        assert!(!readiness.is_connected_at(start + Duration::from_millis(50), lookup(true)));
+        assert_eq!(lookups.get(), 1);
+
+        // Once the window passes the store is looked up again and health flips to ok
+        assert!(readiness.is_connected_at(start + STORAGE_LOOKUP_DEBOUNCE, lookup(true)));
+        assert_eq!(lookups.get(), 2);
+
+        // Connected is latched, so later probes skip the lookup
+        assert!(readiness.is_connected_at(start + STORAGE_LOOKUP_DEBOUNCE, lookup(false)));
+        assert_eq!(lookups.get(), 2);
+    }
+
+    fn probe(connected: bool) -> Option<DatabaseProbe> {
+        Some(DatabaseProbe {
+            connected,
+            pool: "default".to_string(),
+            size: 4,
+            idle: 2,
+        })
+    }
+
+    #[test]
+    fn test_database_health_configured_and_healthy() {
+        let (detail, degraded) = database_health(true, probe(true));
+        assert!(!degraded);
+        assert_eq!(detail, json!({"status": "connected", "pool": "default", "size": 4, "idle": 2}));
+    }
+
+    #[test]
+    fn test_database_health_configured_and_down() {
+        let (detail, degraded) = database_health(true, probe(false));
+        assert!(degraded);
+        assert_eq!(detail["status"], "disconnected");
+
+        // A configured pool that never initialized is down too
+        let (detail, degraded) = database_health(true, None);
+        assert!(degraded);
+        assert_eq!(detail, json!({"status": "disconnected"}));
+    }
+
+    #[test]
+    fn test_database_health_not_configured() {
+        let (detail, degraded) = database_health(false, None);
+        assert!(!degraded);
+        assert_eq!(detail, json!({"status": "not_configured"}));
+    }
+}
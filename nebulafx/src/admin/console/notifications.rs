@@ -0,0 +1,49 @@
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use nebulafx_ecstore::config::KVS;
+use nebulafx_notify::registry::TargetRegistry;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Target definition submitted for a connectivity probe
+#[derive(Debug, Deserialize)]
+pub(crate) struct TestTargetRequest {
+    target_type: String,
+    #[serde(default = "default_probe_id")]
+    id: String,
+    #[serde(default)]
+    config: HashMap<String, String>,
+}
+
+fn default_probe_id() -> String {
+    "probe".to_string()
+}
+
+/// Probe a notification target without registering it or sending an event
+pub(crate) async fn test_notification_target(Json(request): Json<TestTargetRequest>) -> Response {
+    let mut config = KVS::new();
+    for (key, value) in request.config {
+        config.insert(key, value);
+    }
+
+    match TargetRegistry::new()
+        .probe_target(&request.target_type, request.id, &config)
+        .await
+    {
+        Ok(probe) => {
+            info!(
+                target: "nebulafx::console::notifications",
+                target_type = %probe.target_type,
+                reachable = probe.reachable,
+                latency_ms = probe.latency_ms,
+                "Notification target probed from console"
+            );
+            Json(probe).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
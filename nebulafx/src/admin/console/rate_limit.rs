@@ -0,0 +1,203 @@
+use super::client_ip::ClientIp;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{StatusCode, header::RETRY_AFTER};
+use lru::LruCache;
+use std::net::{IpAddr, Ipv4Addr};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Clients tracked at once; the least recently seen is dropped to make room for a new one
+const RATE_LIMIT_MAX_CLIENTS: usize = 10_000;
+
+/// Key for requests whose client address is unknown, e.g. over the console Unix socket
+const UNKNOWN_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Per-client token buckets holding up to a minute's worth of requests
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    buckets: Arc<Mutex<LruCache<IpAddr, Bucket>>>,
+    capacity: f64,
+    refill_per_sec: f64,
+}
+
+impl RateLimiter {
+    /// Limit each client to `rpm` requests per minute, refilled continuously at `rpm / 60` per second
+    pub(crate) fn new(rpm: u32) -> Self {
+        Self::with_max_clients(rpm, RATE_LIMIT_MAX_CLIENTS)
+    }
+
+    fn with_max_clients(rpm: u32, max_clients: usize) -> Self {
+        let rpm = f64::from(rpm.max(1));
+        Self {
+            buckets: Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(max_clients).unwrap_or(NonZeroUsize::MIN)))),
+            capacity: rpm,
+            refill_per_sec: rpm / 60.0,
+        }
+    }
+
+    /// Take a token for `client`, or return how long until one is available
+    fn acquire(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        // Full caches drop their least recently used client to make room
+        let bucket = buckets.get_or_insert_mut(client, || Bucket {
+            tokens: self.capacity,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+
+    #[cfg(test)]
+    fn tracked_clients(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+}
+
+/// Answer 429 with `Retry-After` once a client has used up its requests
+pub(crate) async fn rate_limit(State(limiter): State<RateLimiter>, req: Request, next: Next) -> Response {
+    let client = ClientIp::get(req.extensions()).unwrap_or(UNKNOWN_CLIENT);
+    if let Err(wait) = limiter.acquire(client, Instant::now()) {
+        let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, retry_after.to_string())],
+            "too many console requests",
+        )
+            .into_response();
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::console::client_ip::{TrustedProxies, resolve_client_ip};
+    use axum::{Router, body::Body, extract::ConnectInfo, middleware, routing::get};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn limited_app(rpm: u32, proxies: &str) -> Router {
+        let proxies = TrustedProxies::new(
+            proxies
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse().unwrap())
+                .collect(),
+        );
+        Router::new()
+            .route("/nebulafx/console/config.json", get(|| async { "{}" }))
+            .layer(middleware::from_fn_with_state(RateLimiter::new(rpm), rate_limit))
+            .layer(middleware::from_fn_with_state(proxies, resolve_client_ip))
+    }
+
+    async fn send(app: &Router, peer: &str, forwarded_for: Option<&str>) -> Response {
+        let mut req = Request::builder().uri("/nebulafx/console/config.json");
+        if let Some(forwarded_for) = forwarded_for {
+            req = req.header("x-forwarded-for", forwarded_for);
+        }
+        let mut req = req.body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(format!("{peer}:50000").parse::<SocketAddr>().unwrap()));
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_requests_past_the_limit_get_429() {
+        let app = limited_app(3, "");
+
+        for _ in 0..3 {
+            assert_eq!(send(&app, "10.0.0.1", None).await.status(), StatusCode::OK);
+        }
+        let limited = send(&app, "10.0.0.1", None).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()[RETRY_AFTER], "20");
+
+        // Other peers have their own bucket
+        assert_eq!(send(&app, "10.0.0.2", None).await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_clients_limited_separately() {
+        let app = limited_app(1, "10.0.0.9");
+
+        // Both requests arrive through the same trusted proxy
+        assert_eq!(send(&app, "10.0.0.9", Some("203.0.113.7")).await.status(), StatusCode::OK);
+        assert_eq!(send(&app, "10.0.0.9", Some("203.0.113.8, 10.0.0.9")).await.status(), StatusCode::OK);
+        assert_eq!(send(&app, "10.0.0.9", Some("203.0.113.7")).await.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_address_ignored_from_untrusted_peers() {
+        let app = limited_app(1, "10.0.0.9");
+
+        // Rotating the header does not give a direct client a fresh bucket
+        assert_eq!(send(&app, "203.0.113.7", Some("198.51.100.1")).await.status(), StatusCode::OK);
+        assert_eq!(
+            send(&app, "203.0.113.7", Some("198.51.100.2")).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = RateLimiter::new(60);
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.acquire(ip("10.0.0.1"), start).is_ok());
+        }
+        assert_eq!(limiter.acquire(ip("10.0.0.1"), start), Err(Duration::from_secs(1)));
+        assert!(limiter.acquire(ip("10.0.0.1"), start + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_least_recently_seen_client_evicted() {
+        let limiter = RateLimiter::with_max_clients(1, 2);
+        let start = Instant::now();
+        assert!(limiter.acquire(ip("10.0.0.1"), start).is_ok());
+        assert!(limiter.acquire(ip("10.0.0.2"), start + Duration::from_millis(1)).is_ok());
+        assert!(limiter.acquire(ip("10.0.0.3"), start + Duration::from_millis(2)).is_ok());
+        assert_eq!(limiter.tracked_clients(), 2);
+
+        // 10.0.0.1 was dropped, so it starts over with a full bucket
+        assert!(limiter.acquire(ip("10.0.0.1"), start + Duration::from_millis(3)).is_ok());
+        // 10.0.0.3 is still tracked and exhausted
+        assert!(limiter.acquire(ip("10.0.0.3"), start + Duration::from_millis(4)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_clients_share_a_bucket() {
+        let app = limited_app(1, "");
+        let send = || {
+            app.clone().oneshot(
+                Request::builder()
+                    .uri("/nebulafx/console/config.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+        assert_eq!(send().await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send().await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::server::{StartupReport, startup_timeline};
+use axum::Json;
+use serde::Serialize;
+use tokio::runtime::Handle;
+
+/// Tokio runtime metrics for diagnosing task starvation
+///
+/// Detailed metrics need a build with `RUSTFLAGS="--cfg tokio_unstable"`; otherwise
+/// only `status: "unavailable"` is reported.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct RuntimeSnapshot {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workers: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alive_tasks: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocking_threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    injection_queue_depth: Option<usize>,
+}
+
+/// Report metrics of the runtime serving the console
+pub(crate) async fn runtime_metrics() -> Json<RuntimeSnapshot> {
+    Json(runtime_snapshot(&Handle::current()))
+}
+
+/// Per-step durations of this process's boot, up to the failing step if startup failed
+pub(crate) async fn startup_report() -> Json<StartupReport> {
+    Json(startup_timeline().report())
+}
+
+#[cfg(tokio_unstable)]
+fn runtime_snapshot(handle: &Handle) -> RuntimeSnapshot {
+    let metrics = handle.metrics();
+    RuntimeSnapshot {
+        status: "available",
+        workers: Some(metrics.num_workers()),
+        alive_tasks: Some(metrics.num_alive_tasks()),
+        blocking_threads: Some(metrics.num_blocking_threads()),
+        injection_queue_depth: Some(metrics.global_queue_depth()),
+    }
+}
+
+#[cfg(not(tokio_unstable))]
+fn runtime_snapshot(_handle: &Handle) -> RuntimeSnapshot {
+    RuntimeSnapshot {
+        status: "unavailable",
+        workers: None,
+        alive_tasks: None,
+        blocking_threads: None,
+        injection_queue_depth: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn test_runtime_snapshot_reports_workers() {
+        let Json(snapshot) = runtime_metrics().await;
+
+        if cfg!(tokio_unstable) {
+            assert_eq!(snapshot.status, "available");
+            assert_eq!(snapshot.workers, Some(3));
+            assert!(snapshot.alive_tasks.is_some());
+        } else {
+            assert_eq!(snapshot.status, "unavailable");
+            assert_eq!(snapshot.workers, None);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use nebulafx_ahm::{Scanner, ScannerTuning, get_global_scanner};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+/// Scanner tuning as reported and accepted by `{CONSOLE_PREFIX}/scanner/config`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct ScannerConfigView {
+    interval_secs: u64,
+    max_concurrent_scans: usize,
+    /// Whether the values come from a runtime override rather than the configuration
+    overridden: bool,
+}
+
+/// Fields to change; omitted fields keep their current value
+#[derive(Debug, Deserialize)]
+pub(crate) struct ScannerConfigUpdate {
+    interval_secs: Option<u64>,
+    max_concurrent_scans: Option<usize>,
+}
+
+impl ScannerConfigUpdate {
+    fn apply_to(&self, current: ScannerTuning) -> ScannerTuning {
+        ScannerTuning {
+            scan_interval: self.interval_secs.map(Duration::from_secs).unwrap_or(current.scan_interval),
+            max_concurrent_scans: self.max_concurrent_scans.unwrap_or(current.max_concurrent_scans),
+        }
+    }
+}
+
+async fn view(scanner: &Scanner) -> ScannerConfigView {
+    let tuning = scanner.tuning().await;
+    ScannerConfigView {
+        interval_secs: tuning.scan_interval.as_secs(),
+        max_concurrent_scans: tuning.max_concurrent_scans,
+        overridden: scanner.tuning_override().await.is_some(),
+    }
+}
+
+fn scanner_unavailable() -> Response {
+    (StatusCode::SERVICE_UNAVAILABLE, "scanner is not running").into_response()
+}
+
+/// Interval and concurrency the running scanner uses
+pub(crate) async fn get_scanner_config() -> Response {
+    let Some(scanner) = get_global_scanner() else {
+        return scanner_unavailable();
+    };
+    Json(view(scanner).await).into_response()
+}
+
+/// Override the scanner tuning without restarting it; the override holds until cleared
+pub(crate) async fn update_scanner_config(Json(update): Json<ScannerConfigUpdate>) -> Response {
+    let Some(scanner) = get_global_scanner() else {
+        return scanner_unavailable();
+    };
+
+    let tuning = update.apply_to(scanner.tuning().await);
+    if let Err(e) = scanner.set_tuning_override(tuning).await {
+        return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+    }
+    info!(
+        target: "nebulafx::console::scanner",
+        interval_secs = tuning.scan_interval.as_secs(),
+        max_concurrent_scans = tuning.max_concurrent_scans,
+        "Scanner tuning overridden from console"
+    );
+    Json(view(scanner).await).into_response()
+}
+
+/// Drop the runtime override and return to the tuning the scanner started with
+pub(crate) async fn clear_scanner_config() -> Response {
+    let Some(scanner) = get_global_scanner() else {
+        return scanner_unavailable();
+    };
+
+    scanner.clear_tuning_override().await;
+    info!(target: "nebulafx::console::scanner", "Scanner tuning override cleared from console");
+    Json(view(scanner).await).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_update_keeps_other_fields() {
+        let current = ScannerTuning {
+            scan_interval: Duration::from_secs(300),
+            max_concurrent_scans: 20,
+        };
+        let update: ScannerConfigUpdate = serde_json::from_str(r#"{"interval_secs": 30}"#).unwrap();
+        assert_eq!(
+            update.apply_to(current),
+            ScannerTuning {
+                scan_interval: Duration::from_secs(30),
+                max_concurrent_scans: 20,
+            }
+        );
+    }
+}
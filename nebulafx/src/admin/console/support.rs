@@ -0,0 +1,26 @@
+use axum::response::{IntoResponse, Response};
+use http::{StatusCode, header};
+use tracing::{error, info};
+
+/// Gzipped tarball of redacted config, version, health, topology, pool stats and the recent access log
+pub(crate) async fn download_support_bundle() -> Response {
+    let bundle = crate::support_bundle::collect_running().await;
+    match bundle.to_tar_gz() {
+        Ok(bytes) => {
+            let filename = format!("nebulafx-support-{}.tar.gz", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+            info!(target: "nebulafx::console::support", size = bytes.len(), "Support bundle generated");
+            (
+                [
+                    (header::CONTENT_TYPE, "application/gzip".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{filename}\"")),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(target: "nebulafx::console::support", "Failed to build support bundle: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("failed to build support bundle: {e}")).into_response()
+        }
+    }
+}
@@ -89,12 +89,20 @@ impl Operation for HealthCheckHandler {
     async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
         use serde_json::json;
 
-        let health_info = json!({
+        let mut health_info = json!({
             "status": "ok",
             "service": "nebulafx-endpoint",
             "timestamp": chrono::Utc::now().to_rfc3339(),
-            "version": env!("CARGO_PKG_VERSION")
+            "version": env!("CARGO_PKG_VERSION"),
+            "region": nebulafx_ecstore::global::get_global_region(),
+            "erasure": nebulafx_ecstore::global::get_global_erasure_layout()
         });
+        if let Some(guard) = crate::storage::free_space::free_space_guard() {
+            health_info["disk"] = guard.status();
+        }
+        if let Some(certs) = crate::server::tls_cert_status() {
+            health_info["tls"] = json!(certs);
+        }
 
         let body = serde_json::to_string(&health_info).unwrap_or_else(|_| "{}".to_string());
         let response_body = Body::from(body);
@@ -1344,10 +1352,8 @@ impl Operation for ProfileStatusHandler {
 
         #[cfg(not(target_os = "windows"))]
         let status = {
-            use nebulafx_config::{DEFAULT_ENABLE_PROFILING, ENV_ENABLE_PROFILING};
-            use nebulafx_utils::get_env_bool;
-
-            let enabled = get_env_bool(ENV_ENABLE_PROFILING, DEFAULT_ENABLE_PROFILING);
+            let enabled =
+                crate::config::PROFILING_TOGGLE.resolve(crate::config::get_config().profiling.as_ref().and_then(|p| p.enabled));
             if enabled {
                 HashMap::from([
                     ("enabled", "true"),
@@ -1360,7 +1366,7 @@ impl Operation for ProfileStatusHandler {
                 HashMap::from([
                     ("enabled", "false"),
                     ("status", "disabled"),
-                    ("message", "Set NEUBULAFX_ENABLE_PROFILING=true to enable profiling"),
+                    ("message", "Set profiling.enabled = true to enable profiling"),
                 ])
             }
         };
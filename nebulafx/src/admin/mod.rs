@@ -23,9 +23,14 @@ use s3s::route::S3Route;
 const ADMIN_PREFIX: &str = "/nebulafx/admin";
 // const ADMIN_PREFIX: &str = "/minio/admin";
 
-pub fn make_admin_route(_console_enabled: bool) -> std::io::Result<impl S3Route> {
-    // _console_enabled 参数保留以保持 API 兼容性，但始终为 true（Console API 始终启用）
-    let mut r: S3Router<AdminOperation> = S3Router::new(true);
+/// Whether `path` is served by the admin, RPC or console routes rather than the S3 API
+pub(crate) fn is_internal_path(path: &str) -> bool {
+    path.starts_with(ADMIN_PREFIX) || path.starts_with(rpc::RPC_PREFIX) || console::is_console_path(path)
+}
+
+pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route> {
+    // The console is disabled here when it is served on a Unix socket instead
+    let mut r: S3Router<AdminOperation> = S3Router::new(console_enabled);
 
     // Health check endpoint for monitoring and orchestration
     r.insert(Method::GET, "/health", AdminOperation(&HealthCheckHandler {}))?;
@@ -0,0 +1,214 @@
+use http::HeaderMap;
+use nebulafx_ecstore::bucket::metadata_sys::init_bucket_metadata_sys;
+use nebulafx_ecstore::endpoints::EndpointServerPools;
+use nebulafx_ecstore::store::{ECStore, init_local_disks};
+use nebulafx_ecstore::store_api::{DeleteBucketOptions, ObjectIO, ObjectOptions, PutObjReader, StorageAPI};
+use nebulafx_postgresqlx::PostgreSQLPool;
+use serde::Serialize;
+use std::io::{Error, Result};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// Prefix of the throwaway bucket each run writes to
+pub(crate) const BENCH_BUCKET_PREFIX: &str = "nebulafx-bench-";
+
+/// How much work a benchmark run does
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BenchOptions {
+    pub objects: usize,
+    pub object_size: usize,
+    pub db_round_trips: usize,
+}
+
+/// Throughput of one storage operation
+#[derive(Debug, Serialize)]
+pub(crate) struct Throughput {
+    operations: usize,
+    elapsed_ms: u128,
+    ops_per_sec: f64,
+    mib_per_sec: f64,
+}
+
+impl Throughput {
+    fn new(operations: usize, bytes: usize, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        Self {
+            operations,
+            elapsed_ms: elapsed.as_millis(),
+            ops_per_sec: operations as f64 / secs,
+            mib_per_sec: bytes as f64 / (1024.0 * 1024.0) / secs,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct StorageBench {
+    bucket: String,
+    object_size: usize,
+    put: Throughput,
+    get: Throughput,
+}
+
+/// Round-trip latency of `SELECT 1` against the pool
+#[derive(Debug, Serialize)]
+pub(crate) struct DatabaseBench {
+    round_trips: usize,
+    avg_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BenchReport {
+    storage: StorageBench,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database: Option<DatabaseBench>,
+}
+
+/// Open the configured volumes as a local store, formatting them if needed
+pub(crate) async fn open_store(volumes: &str) -> Result<Arc<ECStore>> {
+    let server_addr: SocketAddr = "127.0.0.1:0".parse().map_err(Error::other)?;
+    let (endpoint_pools, _) = EndpointServerPools::from_volumes(&server_addr.to_string(), vec![volumes.to_string()])
+        .await
+        .map_err(Error::other)?;
+    init_local_disks(endpoint_pools.clone()).await.map_err(Error::other)?;
+    let store = ECStore::new(server_addr, endpoint_pools, CancellationToken::new()).await?;
+    init_bucket_metadata_sys(store.clone(), Vec::new()).await;
+    Ok(store)
+}
+
+/// PUT then GET `objects` small objects in a fresh bucket, removing it afterwards
+pub(crate) async fn bench_storage(store: &ECStore, options: BenchOptions) -> Result<StorageBench> {
+    let bucket = format!("{BENCH_BUCKET_PREFIX}{}", uuid::Uuid::new_v4().simple());
+    store.make_bucket(&bucket, &Default::default()).await.map_err(Error::other)?;
+
+    let result = run_storage_bench(store, &bucket, options).await;
+    if let Err(e) = cleanup(store, &bucket, options.objects).await {
+        warn!(target: "nebulafx::main::bench", bucket = %bucket, "Failed to remove benchmark bucket: {}", e);
+    }
+    result
+}
+
+async fn run_storage_bench(store: &ECStore, bucket: &str, options: BenchOptions) -> Result<StorageBench> {
+    let payload = vec![0x5a_u8; options.object_size];
+    let opts = ObjectOptions::default();
+
+    let start = Instant::now();
+    for i in 0..options.objects {
+        let mut reader = PutObjReader::from_vec(payload.clone());
+        store
+            .put_object(bucket, &object_key(i), &mut reader, &opts)
+            .await
+            .map_err(Error::other)?;
+    }
+    let put = Throughput::new(options.objects, options.objects * options.object_size, start.elapsed());
+
+    let start = Instant::now();
+    let mut buf = Vec::with_capacity(options.object_size);
+    for i in 0..options.objects {
+        let mut reader = store
+            .get_object_reader(bucket, &object_key(i), None, HeaderMap::new(), &opts)
+            .await
+            .map_err(Error::other)?;
+        buf.clear();
+        reader.stream.read_to_end(&mut buf).await?;
+        if buf.len() != options.object_size {
+            return Err(Error::other(format!(
+                "read {} bytes of {}, expected {}",
+                buf.len(),
+                object_key(i),
+                options.object_size
+            )));
+        }
+    }
+    let get = Throughput::new(options.objects, options.objects * options.object_size, start.elapsed());
+
+    Ok(StorageBench {
+        bucket: bucket.to_string(),
+        object_size: options.object_size,
+        put,
+        get,
+    })
+}
+
+async fn cleanup(store: &ECStore, bucket: &str, objects: usize) -> Result<()> {
+    for i in 0..objects {
+        // Objects that were never written are expected to be missing
+        let _ = store.delete_object(bucket, &object_key(i), ObjectOptions::default()).await;
+    }
+    let opts = DeleteBucketOptions {
+        force: true,
+        ..Default::default()
+    };
+    store.delete_bucket(bucket, &opts).await.map_err(Error::other)
+}
+
+fn object_key(i: usize) -> String {
+    format!("object-{i:06}")
+}
+
+/// Measure `SELECT 1` round trips; read-only, so nothing needs cleaning up
+pub(crate) async fn bench_database(pool: &PostgreSQLPool, round_trips: usize) -> Result<DatabaseBench> {
+    let mut total = Duration::ZERO;
+    let mut max = Duration::ZERO;
+    for _ in 0..round_trips {
+        let start = Instant::now();
+        pool.health_check().await.map_err(Error::other)?;
+        let elapsed = start.elapsed();
+        total += elapsed;
+        max = max.max(elapsed);
+    }
+    Ok(DatabaseBench {
+        round_trips,
+        avg_ms: total.as_secs_f64() * 1000.0 / round_trips.max(1) as f64,
+        max_ms: max.as_secs_f64() * 1000.0,
+    })
+}
+
+/// Run the storage benchmark, and the database one when a pool is given
+pub(crate) async fn run_bench(store: &ECStore, pool: Option<&PostgreSQLPool>, options: BenchOptions) -> Result<BenchReport> {
+    let storage = bench_storage(store, options).await?;
+    let database = match pool {
+        Some(pool) => Some(bench_database(pool, options.db_round_trips).await?),
+        None => None,
+    };
+    Ok(BenchReport { storage, database })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bench_runs_against_local_store() {
+        let base = std::env::temp_dir().join(format!("nebulafx-bench-test-{}", uuid::Uuid::new_v4()));
+        for i in 1..=4 {
+            std::fs::create_dir_all(base.join(format!("disk{i}"))).unwrap();
+        }
+        let volumes = format!("{}/disk{{1...4}}", base.display());
+
+        let store = open_store(&volumes).await.unwrap();
+        let options = BenchOptions {
+            objects: 3,
+            object_size: 4096,
+            db_round_trips: 0,
+        };
+        let report = run_bench(&store, None, options).await.unwrap();
+
+        assert_eq!(report.storage.put.operations, 3);
+        assert_eq!(report.storage.get.operations, 3);
+        assert!(report.database.is_none());
+        // The benchmark bucket is removed afterwards
+        assert!(
+            store
+                .get_bucket_info(&report.storage.bucket, &Default::default())
+                .await
+                .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
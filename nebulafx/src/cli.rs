@@ -0,0 +1,300 @@
+use crate::bench::{self, BenchOptions};
+use crate::config::{Config, active_config_path, get_config, init_config, write_default_config};
+use crate::exit::{EXIT_CONFIG, StartupError};
+use clap::{Parser, Subcommand};
+use nebulafx_ecstore::config::KVS;
+use nebulafx_notify::registry::TargetRegistry;
+use nebulafx_postgresqlx::PostgreSQLPool;
+use nebulafx_tomlx::{PrintFormat, TomlConfigError, load_config_redacted};
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+/// Command line interface for the NebulaFX server
+#[derive(Parser, Debug)]
+#[command(name = "nebulafx", version, about = "NebulaFX object storage server")]
+pub struct Cli {
+    /// Config file to read, overriding `NEBULAFX_CONFIG_PATH` and the `ENVIRONMENT`-based default
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Maintenance commands that run instead of starting the server
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Check that a notification target is reachable without registering it
+    TestTarget {
+        /// Target type, e.g. `webhook` or `mqtt`
+        target_type: String,
+
+        /// Target settings as KEY=VALUE pairs, e.g. `endpoint=http://hooks.internal/events`
+        #[arg(value_parser = parse_key_value)]
+        settings: Vec<(String, String)>,
+
+        /// Identifier used for the probe
+        #[arg(long, default_value = "probe")]
+        id: String,
+    },
+    /// Benchmark small-object PUT/GET on the configured volumes and database round trips
+    ///
+    /// Objects are written to a throwaway bucket that is removed afterwards.
+    Bench {
+        /// Confirm running against the configured storage and database
+        #[arg(long)]
+        yes: bool,
+
+        /// Number of objects to PUT and GET
+        #[arg(long, default_value_t = 100)]
+        objects: usize,
+
+        /// Size of each object in bytes
+        #[arg(long, default_value_t = 4096)]
+        object_size: usize,
+
+        /// Number of database round trips to time
+        #[arg(long, default_value_t = 100)]
+        db_round_trips: usize,
+    },
+    /// Collect redacted config, version and the running server's health into a gzipped tarball
+    ///
+    /// The recent access log is only included in bundles downloaded from the console's
+    /// `support-bundle` endpoint.
+    SupportBundle {
+        /// Where to write the bundle
+        #[arg(long, default_value = "support-bundle.tar.gz")]
+        out: PathBuf,
+
+        /// Base URL of the running server whose health is included
+        #[arg(long, default_value = "http://127.0.0.1:9000")]
+        endpoint: String,
+    },
+    /// Load and check the config file without starting the server
+    ///
+    /// Prints the effective settings with secrets masked. Exits 0 when the config is
+    /// valid and 78 (`EX_CONFIG`) with every problem listed when it is not.
+    Validate,
+    /// Manage the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// Config file commands
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Write a commented default config covering server, database, storage, TLS and observability
+    Init {
+        /// Where to write the config
+        #[arg(default_value = "config.toml")]
+        path: PathBuf,
+
+        /// Replace the file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Run a subcommand to completion
+pub fn run(command: Command) -> std::result::Result<(), StartupError> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    match command {
+        Command::TestTarget {
+            target_type,
+            settings,
+            id,
+        } => Ok(runtime.block_on(test_target(&target_type, id, settings))?),
+        Command::Bench {
+            yes,
+            objects,
+            object_size,
+            db_round_trips,
+        } => {
+            if !yes {
+                return Err(Error::other("bench writes to the configured storage and database; pass --yes to run it").into());
+            }
+            Ok(runtime.block_on(run_bench(BenchOptions {
+                objects,
+                object_size,
+                db_round_trips,
+            }))?)
+        }
+        Command::SupportBundle { out, endpoint } => Ok(runtime.block_on(support_bundle(&out, &endpoint))?),
+        Command::Validate => validate_config(),
+        Command::Config {
+            command: ConfigCommand::Init { path, force },
+        } => Ok(init_config_file(&path, force)?),
+    }
+}
+
+fn init_config_file(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists; pass --force to overwrite it", path.display()),
+        ));
+    }
+    write_default_config(path).map_err(Error::other)?;
+    println!("Default config written to {}", path.display());
+    Ok(())
+}
+
+fn validate_config() -> std::result::Result<(), StartupError> {
+    let path = active_config_path();
+    println!("Validating {}", path.display());
+    match check_config(&path) {
+        Ok(rendered) => {
+            println!("Effective settings (secrets masked):\n{rendered}");
+            println!("Configuration is valid (exit code 0)");
+            Ok(())
+        }
+        Err(problems) => {
+            eprintln!("Configuration is invalid:");
+            for problem in &problems {
+                eprintln!("  - {problem}");
+            }
+            eprintln!("Exit code {EXIT_CONFIG} (EX_CONFIG); exit code 0 means the configuration is valid");
+            Err(StartupError::Config(format!("{} problem(s) in {}", problems.len(), path.display())))
+        }
+    }
+}
+
+/// The config at `path` rendered with secrets masked, or every problem found in it
+fn check_config(path: &Path) -> std::result::Result<String, Vec<String>> {
+    let (mut config, rendered) = load_config_redacted::<Config>(path, PrintFormat::Toml).map_err(|e| vec![e.to_string()])?;
+    let mut problems = Vec::new();
+    for check in [config.resolve_credential_files(), config.validate_startup()] {
+        match check {
+            Ok(()) => {}
+            Err(TomlConfigError::Invalid(problem)) => problems.push(problem),
+            Err(TomlConfigError::Validation(found)) => problems.extend(found),
+            Err(e) => problems.push(e.to_string()),
+        }
+    }
+    if problems.is_empty() { Ok(rendered) } else { Err(problems) }
+}
+
+async fn support_bundle(out: &Path, endpoint: &str) -> Result<()> {
+    init_config().map_err(Error::other)?;
+    let bundle = crate::support_bundle::collect_from_cli(endpoint).await;
+    std::fs::write(out, bundle.to_tar_gz()?)?;
+    println!("Support bundle written to {}", out.display());
+    Ok(())
+}
+
+async fn run_bench(options: BenchOptions) -> Result<()> {
+    init_config().map_err(Error::other)?;
+    let config = get_config();
+    let volumes = config
+        .server
+        .as_ref()
+        .and_then(|s| s.volumes.as_deref())
+        .ok_or_else(|| Error::other("server.volumes is not configured"))?;
+
+    let store = bench::open_store(volumes).await?;
+    let pool = match config.database.as_ref() {
+        Some(database) => {
+            PostgreSQLPool::init(Some(database)).await.map_err(Error::other)?;
+            Some(PostgreSQLPool::get().map_err(Error::other)?)
+        }
+        None => None,
+    };
+
+    let report = bench::run_bench(&store, pool.as_ref(), options).await?;
+    println!("{}", serde_json::to_string_pretty(&report).map_err(Error::other)?);
+    Ok(())
+}
+
+async fn test_target(target_type: &str, id: String, settings: Vec<(String, String)>) -> Result<()> {
+    let mut config = KVS::new();
+    for (key, value) in settings {
+        config.insert(key, value);
+    }
+
+    let probe = TargetRegistry::new()
+        .probe_target(target_type, id, &config)
+        .await
+        .map_err(|e| Error::other(format!("Invalid target definition: {e}")))?;
+
+    println!("{}", serde_json::to_string_pretty(&probe).map_err(Error::other)?);
+    if probe.reachable {
+        Ok(())
+    } else {
+        Err(Error::other(format!(
+            "Target {} is unreachable: {}",
+            probe.id,
+            probe.error.unwrap_or_default()
+        )))
+    }
+}
+
+fn parse_key_value(s: &str) -> std::result::Result<(String, String), String> {
+    let (key, value) = s.split_once('=').ok_or_else(|| format!("expected KEY=VALUE, got '{s}'"))?;
+    Ok((key.trim().to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> std::result::Result<String, Vec<String>> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, content).unwrap();
+        check_config(&path)
+    }
+
+    #[test]
+    fn test_valid_config_rendered_with_secrets_masked() {
+        let rendered =
+            check("[server]\nvolumes = \"/data/disk{1...4}\"\naccess_key = \"nebulafxadmin\"\nsecret_key = \"s3cr3t-value\"\n")
+                .unwrap();
+        assert!(rendered.contains("/data/disk{1...4}"));
+        assert!(!rendered.contains("s3cr3t-value"));
+        assert!(rendered.contains(nebulafx_tomlx::REDACTED));
+    }
+
+    #[test]
+    fn test_invalid_config_lists_every_problem() {
+        let problems = check("[server]\naccess_key = \"nebulafxadmin\"\n").unwrap_err();
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].contains("server.volumes"));
+        assert!(problems[1].contains("server.secret_key is missing"));
+    }
+
+    #[test]
+    fn test_startup_problems_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let problems = check(&format!(
+            "[server]\nvolumes = \"/data\"\naccess_key = \"ab\"\nsecret_key = \"s3cr3t-value\"\n[tls]\npath = \"{}\"\n",
+            dir.path().display()
+        ))
+        .unwrap_err();
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].contains("server.access_key must be at least"), "{problems:?}");
+        assert!(problems[1].starts_with("tls.path"), "{problems:?}");
+    }
+
+    #[test]
+    fn test_config_init_refuses_to_overwrite_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[server]\n").unwrap();
+
+        let err = init_config_file(&path, false).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[server]\n");
+
+        init_config_file(&path, true).unwrap();
+        assert!(check_config(&path).is_ok());
+    }
+
+    #[test]
+    fn test_unreadable_config_reported() {
+        let problems = check("[server\n").unwrap_err();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Failed to parse"), "{problems:?}");
+    }
+}
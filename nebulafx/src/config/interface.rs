@@ -1,10 +1,16 @@
-use serde::Deserialize;
-pub use nebulafx_postgresqlx::PostgreSQLConfig;
+use nebulafx_config::{NEUBULAFX_TLS_CERT, NEUBULAFX_TLS_KEY};
 pub use nebulafx_obs::ObservabilityConfig;
+pub use nebulafx_postgresqlx::PostgreSQLConfig;
 pub use nebulafx_profilingx::ProfilingConfig;
 pub use nebulafx_tokiox::RuntimeConfig;
+pub use nebulafx_tomlx::LogLevel;
+use nebulafx_utils::string::{find_ellipses_patterns, has_ellipses};
+use rustls::crypto::CryptoProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Config {
     pub server: Option<ServerConfig>,
     pub database: Option<PostgreSQLConfig>,
@@ -13,12 +19,19 @@ pub struct Config {
     pub observability: Option<ObservabilityConfig>,
     pub profiling: Option<ProfilingConfig>,
     pub runtime: Option<RuntimeConfig>,
+    pub console: Option<ConsoleConfig>,
+    pub audit: Option<AuditConfig>,
+    pub notify: Option<NotifyConfig>,
+    pub background: Option<BackgroundConfig>,
+    /// Extra key fragments whose values are redacted when the config is printed or reported
+    pub redact_keys: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
     pub name: Option<String>,
     pub host: Option<String>,
+    pub advertise_ip: Option<String>,
     pub port: Option<u16>,
     pub server_domains: Option<Vec<String>>,
     pub region: Option<String>,
@@ -27,20 +40,351 @@ pub struct ServerConfig {
     pub console_cors_allowed_origins: Option<String>,
     pub access_key: Option<String>,
     pub secret_key: Option<String>,
+    /// File holding `access_key`, e.g. a mounted Docker or Kubernetes secret; preferred over the inline value
+    pub access_key_file: Option<String>,
+    /// File holding `secret_key`; preferred over the inline value
+    pub secret_key_file: Option<String>,
     pub root_user: Option<String>,
     pub root_password: Option<String>,
+    pub startup_delay_ms: Option<u64>,
+    /// Longest wait for in-flight requests to drain on shutdown
+    pub shutdown_grace_period_ms: Option<u64>,
+    pub max_object_size: Option<String>,
+    pub iam_init: Option<IamInitMode>,
+    pub role: Option<ServerRole>,
+    /// Directory keeping the last shutdown reason across restarts; unset keeps it in memory only
+    pub state_dir: Option<String>,
+}
+
+/// When the IAM system is loaded during boot
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IamInitMode {
+    /// Block startup until all IAM data is loaded
+    #[default]
+    Eager,
+    /// Load IAM in the background; IAM-dependent requests fail until it is ready
+    Lazy,
+}
+
+/// Role of this node in a primary/standby deployment
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerRole {
+    #[default]
+    Primary,
+    /// Serves requests but leaves heal, scanner and write-side startup work to the primary
+    Standby,
+}
+
+impl ServerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerRole::Primary => "primary",
+            ServerRole::Standby => "standby",
+        }
+    }
+}
+
+/// Region used when `server.region` is unset or empty
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+impl ServerConfig {
+    /// The configured region, falling back to [`DEFAULT_REGION`]
+    pub fn resolved_region(&self) -> &str {
+        self.region
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .unwrap_or(DEFAULT_REGION)
+    }
+
+    /// Maximum accepted object size in bytes, parsed from `max_object_size` (e.g. "5GiB")
+    ///
+    /// Returns `Ok(None)` when no limit is configured.
+    pub fn max_object_size_bytes(&self) -> Result<Option<u64>, String> {
+        let Some(value) = self.max_object_size.as_deref() else {
+            return Ok(None);
+        };
+        let size = value
+            .trim()
+            .parse::<bytesize::ByteSize>()
+            .map_err(|e| format!("invalid server.max_object_size '{value}': {e}"))?;
+        if size.as_u64() == 0 {
+            return Err("server.max_object_size must be greater than zero".to_string());
+        }
+        Ok(Some(size.as_u64()))
+    }
+
+    /// Check that `access_key`/`secret_key` and `root_user`/`root_password` are each
+    /// set together or both absent, and that secrets meet [`MIN_SECRET_LENGTH`]
+    pub fn validate_credentials(&self) -> Result<(), String> {
+        validate_credential_pair(
+            ("server.access_key", self.access_key.as_deref()),
+            ("server.secret_key", self.secret_key.as_deref()),
+        )?;
+        validate_credential_pair(
+            ("server.root_user", self.root_user.as_deref()),
+            ("server.root_password", self.root_password.as_deref()),
+        )
+    }
+
+    /// Replace `access_key`/`secret_key` with the contents of `access_key_file`/`secret_key_file`
+    ///
+    /// A trailing newline is trimmed. An inline value that differs from its file is an error.
+    pub fn resolve_credential_files(&mut self) -> Result<(), String> {
+        resolve_credential_file("server.access_key", &mut self.access_key, self.access_key_file.as_deref())?;
+        resolve_credential_file("server.secret_key", &mut self.secret_key, self.secret_key_file.as_deref())
+    }
+
+    /// Check that `access_key` meets [`MIN_ACCESS_KEY_LENGTH`] when set
+    pub fn validate_access_key(&self) -> Result<(), String> {
+        match self.access_key.as_deref() {
+            Some(key) if key.len() < MIN_ACCESS_KEY_LENGTH => {
+                Err(format!("server.access_key must be at least {MIN_ACCESS_KEY_LENGTH} characters"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolve `host` and `port` (default `0.0.0.0:9000`) to the address the server binds
+    pub fn validate_address(&self) -> Result<(), String> {
+        let address = format!("{}:{}", self.host.as_deref().unwrap_or("0.0.0.0"), self.port.unwrap_or(9000));
+        nebulafx_utils::net::parse_and_resolve_address(&address)
+            .map(|_| ())
+            .map_err(|e| format!("server.host and server.port do not resolve to a local address '{address}': {e}"))
+    }
+
+    /// Check the `{N...M}` ranges of every space-separated entry in `volumes`
+    pub fn validate_volumes(&self) -> Result<(), String> {
+        let Some(volumes) = self.volumes.as_deref() else {
+            return Ok(());
+        };
+        for volume in volumes.split_whitespace() {
+            if has_ellipses(&[volume]) {
+                find_ellipses_patterns(volume).map_err(|e| format!("invalid server.volumes entry '{volume}': {e}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resolve_credential_file(key: &str, inline: &mut Option<String>, file: Option<&str>) -> Result<(), String> {
+    let Some(file) = file else {
+        return Ok(());
+    };
+    let content = std::fs::read_to_string(file).map_err(|e| format!("failed to read {key}_file '{file}': {e}"))?;
+    let value = content.trim_end_matches(['\r', '\n']);
+    if inline.as_deref().is_some_and(|v| v != value) {
+        return Err(format!("{key} and {key}_file are both set but differ; set only one"));
+    }
+    *inline = Some(value.to_string());
+    Ok(())
+}
+
+/// Minimum length of `server.secret_key` and `server.root_password`
+pub const MIN_SECRET_LENGTH: usize = 8;
+
+/// Minimum length of `server.access_key`
+pub const MIN_ACCESS_KEY_LENGTH: usize = 3;
+
+fn validate_credential_pair(
+    (user_field, user): (&str, Option<&str>),
+    (secret_field, secret): (&str, Option<&str>),
+) -> Result<(), String> {
+    match (user, secret) {
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(format!("{user_field} is set but {secret_field} is missing; set both or neither")),
+        (None, Some(_)) => Err(format!("{secret_field} is set but {user_field} is missing; set both or neither")),
+        (Some(_), Some(secret)) if secret.len() < MIN_SECRET_LENGTH => {
+            Err(format!("{secret_field} must be at least {MIN_SECRET_LENGTH} characters"))
+        }
+        (Some(_), Some(_)) => Ok(()),
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct ConsoleConfig {
+    pub access_log_level: Option<LogLevel>,
+    pub access_log_exclude_paths: Option<Vec<String>>,
+    /// Serve the console on this Unix socket instead of the TCP port (Unix only)
+    pub unix_socket: Option<String>,
+    /// Octal permissions of the console socket, e.g. "0600"
+    pub unix_socket_mode: Option<String>,
+    /// Answer 503 on every console route except `/livez` and `/readyz` until startup completes and IAM is loaded or not configured
+    pub auth_required_during_init: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AuditConfig {
+    /// `async` acknowledges before the backend write, `sync` after it (default: async)
+    pub mode: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct NotifyConfig {
+    /// Events buffered per notification target before the overflow policy applies
+    pub queue_limit: Option<usize>,
+    /// `drop_oldest`, `drop_new` or `block`
+    pub overflow_policy: Option<String>,
+}
+
+/// Background services; each key supersedes its `NEUBULAFX_ENABLE_*` environment variable
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct BackgroundConfig {
+    pub scanner: Option<bool>,
+    pub heal: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct StorageConfig {
     pub base_path: Option<String>,
+    /// `single-drive`, `single-node` or `distributed`; startup fails if the volumes resolve differently
+    pub expected_setup: Option<String>,
+    /// Format blank disks on first boot (default: true); when false, startup fails instead
+    pub allow_format: Option<bool>,
+    /// Free space each local drive must keep before S3 writes are refused, e.g. "10GiB"
+    pub min_free_bytes: Option<String>,
+    /// Free space each local drive must keep, as a percentage of its size
+    pub min_free_percent: Option<f64>,
+    /// Buckets whose objects can be read but not written or deleted through the S3 API
+    pub read_only_buckets: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl StorageConfig {
+    /// `min_free_bytes` in bytes, or `Ok(None)` when unset
+    pub fn min_free_bytes_value(&self) -> Result<Option<u64>, String> {
+        let Some(value) = self.min_free_bytes.as_deref() else {
+            return Ok(None);
+        };
+        let size = value
+            .trim()
+            .parse::<bytesize::ByteSize>()
+            .map_err(|e| format!("invalid storage.min_free_bytes '{value}': {e}"))?;
+        Ok(Some(size.as_u64()))
+    }
+
+    /// `read_only_buckets` as a set, rejecting names S3 would not accept
+    pub fn read_only_bucket_set(&self) -> Result<HashSet<String>, String> {
+        self.read_only_buckets
+            .iter()
+            .flatten()
+            .map(|bucket| {
+                nebulafx_ecstore::bucket::utils::check_valid_bucket_name_strict(bucket)
+                    .map(|_| bucket.clone())
+                    .map_err(|e| format!("invalid bucket '{bucket}' in storage.read_only_buckets: {e}"))
+            })
+            .collect()
+    }
+
+    /// `min_free_percent`, checked to lie in `[0, 100)`
+    pub fn min_free_percent_value(&self) -> Result<Option<f64>, String> {
+        match self.min_free_percent {
+            Some(percent) if !(0.0..100.0).contains(&percent) => {
+                Err(format!("storage.min_free_percent must be at least 0 and below 100, got {percent}"))
+            }
+            percent => Ok(percent),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct TlsConfig {
     pub path: Option<String>,
+    /// Informational only; the server always loads `nebulafx_key.pem` from `path`
     pub key_file: Option<String>,
+    /// Informational only; the server always loads `nebulafx_cert.pem` from `path`
     pub cert_file: Option<String>,
+    /// Allowed cipher suites by rustls name, e.g. `TLS13_AES_256_GCM_SHA384`; rustls defaults when unset
+    pub cipher_suites: Option<Vec<String>>,
+    /// Key-exchange groups in preference order, e.g. `X25519`, `secp384r1`; rustls defaults when unset
+    pub kx_groups: Option<Vec<String>>,
+    /// Days before expiry at which certificates are logged as expiring soon (default: 30)
+    pub expiry_warning_days: Option<u32>,
+    /// Fail startup when a certificate has already expired
+    pub fail_on_expired: Option<bool>,
+}
+
+impl TlsConfig {
+    /// The ring crypto provider restricted to `cipher_suites` and ordered by `kx_groups`
+    pub fn crypto_provider(&self) -> Result<CryptoProvider, String> {
+        let mut provider = rustls::crypto::ring::default_provider();
+        if let Some(names) = self.cipher_suites.as_deref() {
+            provider.cipher_suites =
+                select_by_name("tls.cipher_suites", names, &provider.cipher_suites, |suite| suite.suite().as_str())?;
+        }
+        if let Some(names) = self.kx_groups.as_deref() {
+            provider.kx_groups = select_by_name("tls.kx_groups", names, &provider.kx_groups, |group| group.name().as_str())?;
+        }
+        Ok(provider)
+    }
+
+    /// Check that `path` holds a certificate the server can load, when the directory exists
+    ///
+    /// Like the listener, which serves plain HTTP without the directory, a missing `path` is
+    /// not a problem. An existing one needs a `nebulafx_cert.pem`/`nebulafx_key.pem` pair at its
+    /// root or in a domain subdirectory; `key_file` and `cert_file` are not read by the server.
+    pub fn validate_files(&self) -> Vec<String> {
+        let Some(dir) = self.path.as_deref().filter(|p| !p.is_empty()).map(Path::new) else {
+            return Vec::new();
+        };
+        if !dir.is_dir() {
+            return Vec::new();
+        }
+        let cert = dir.join(NEUBULAFX_TLS_CERT);
+        let key = dir.join(NEUBULAFX_TLS_KEY);
+        match (cert.is_file(), key.is_file()) {
+            (true, true) => Vec::new(),
+            (true, false) => vec![format!(
+                "TLS key '{}' does not exist next to '{}'",
+                key.display(),
+                cert.display()
+            )],
+            (false, true) => vec![format!(
+                "TLS certificate '{}' does not exist next to '{}'",
+                cert.display(),
+                key.display()
+            )],
+            (false, false) if has_domain_cert_pair(dir) => Vec::new(),
+            (false, false) => vec![format!(
+                "tls.path '{}' has no {NEUBULAFX_TLS_CERT} and {NEUBULAFX_TLS_KEY} at its root or in a domain directory",
+                dir.display()
+            )],
+        }
+    }
+}
+
+/// Whether a subdirectory of `dir` holds the certificate pair for an SNI domain
+fn has_domain_cert_pair(dir: &Path) -> bool {
+    std::fs::read_dir(dir).into_iter().flatten().flatten().any(|entry| {
+        let domain = entry.path();
+        domain.join(NEUBULAFX_TLS_CERT).is_file() && domain.join(NEUBULAFX_TLS_KEY).is_file()
+    })
+}
+
+/// Pick the entries of `available` named in `names`, keeping the order of `names`
+fn select_by_name<T: Copy>(
+    key: &str,
+    names: &[String],
+    available: &[T],
+    name_of: impl Fn(&T) -> Option<&'static str>,
+) -> Result<Vec<T>, String> {
+    if names.is_empty() {
+        return Err(format!("{key} must not be empty; remove it to use the defaults"));
+    }
+    names
+        .iter()
+        .map(|name| {
+            available
+                .iter()
+                .find(|item| name_of(item).is_some_and(|n| n.eq_ignore_ascii_case(name)))
+                .copied()
+                .ok_or_else(|| {
+                    let supported: Vec<_> = available.iter().filter_map(&name_of).collect();
+                    format!("unknown {key} entry '{name}', supported: {}", supported.join(", "))
+                })
+        })
+        .collect()
 }
 
 
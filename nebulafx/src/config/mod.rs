@@ -1,11 +1,18 @@
 mod interface;
+mod reload;
+mod template;
+mod toggles;
 
 pub use interface::*;
+pub use reload::{ConfigDiff, ReloadDispatcher, ReloadReport, ReloadSection};
+pub use template::write_default_config;
+pub use toggles::{EnvToggle, HEAL_TOGGLE, PROFILING_TOGGLE, SCANNER_TOGGLE};
 
+use nebulafx_tomlx::{Result, TomlConfigError, Validate, load_config_from_path};
+use std::env;
 use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
-use std::env;
-use nebulafx_tomlx::{load_config_from_path, Result, TomlConfigError};
 use tracing::error;
 
 pub struct Success;
@@ -17,19 +24,155 @@ impl fmt::Display for Success {
 }
 
 const ENVIRONMENT: &str = "ENVIRONMENT";
-const PRO_ENV: [&str; 6] = ["pro", "production", "p", "P", "PRO", "PRODUCTION"];
 static CONFIG: OnceLock<Config> = OnceLock::new();
 
-fn load_config(if_production: bool) -> Result<Config> {
-    load_config_from_path(if if_production {
-        "config.toml"
-    } else {
-        "config.dev.toml"
-    }, if_production)
+/// Environment variable pointing at the config file, overriding the `ENVIRONMENT`-based default
+pub const CONFIG_PATH_ENV: &str = "NEBULAFX_CONFIG_PATH";
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Deployment environment selected by `ENVIRONMENT`, which picks the default config file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Environment {
+    Production,
+    Staging,
+    #[default]
+    Development,
+}
+
+impl Environment {
+    /// Match a trimmed, lowercased `ENVIRONMENT` value; anything unrecognized is development
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "p" | "pro" | "prod" | "production" => Environment::Production,
+            "stage" | "staging" => Environment::Staging,
+            _ => Environment::Development,
+        }
+    }
+
+    /// The environment `init_config` loads for
+    pub fn from_env() -> Self {
+        env::var(ENVIRONMENT).map(|v| Self::parse(&v)).unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Production => "production",
+            Environment::Staging => "staging",
+            Environment::Development => "development",
+        }
+    }
+
+    fn config_file(&self) -> &'static str {
+        match self {
+            Environment::Production => "config.toml",
+            Environment::Staging => "config.staging.toml",
+            Environment::Development => "config.dev.toml",
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn load_config(environment: Environment) -> Result<Config> {
+    finish_loading(load_config_from_path(active_config_path(), environment == Environment::Production)?)
+}
+
+/// Read the credential files of a parsed config, then validate it
+fn finish_loading(mut config: Config) -> Result<Config> {
+    // Validated once the credential files are read, so file-backed keys count as set
+    config.resolve_credential_files()?;
+    config.validate().map_err(TomlConfigError::Validation)?;
+    Ok(config)
+}
+
+/// Read the config from `path` instead of the default; called for `--config` before `init_config`
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
+}
+
+/// `--config` wins over `NEBULAFX_CONFIG_PATH`, which wins over the `ENVIRONMENT`-based file name
+fn resolve_config_path(flag: Option<&Path>, env_path: Option<&str>, environment: Environment) -> PathBuf {
+    flag.map(Path::to_path_buf)
+        .or_else(|| env_path.filter(|p| !p.is_empty()).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(environment.config_file()))
+}
+
+/// Config file `init_config` reads
+pub fn active_config_path() -> PathBuf {
+    resolve_config_path(
+        CONFIG_PATH_OVERRIDE.get().map(PathBuf::as_path),
+        env::var(CONFIG_PATH_ENV).ok().as_deref(),
+        Environment::from_env(),
+    )
+}
+
+impl Config {
+    /// Read the credentials configured as files into `server.access_key`/`server.secret_key`
+    pub fn resolve_credential_files(&mut self) -> Result<()> {
+        match self.server.as_mut() {
+            Some(server) => server.resolve_credential_files().map_err(TomlConfigError::Invalid),
+            None => Ok(()),
+        }
+    }
+
+    /// Every problem in the effective config that would stop the server once it starts binding
+    ///
+    /// Runs after loading, so it also checks the host: that `host`/`port` resolve and an
+    /// existing TLS directory holds a certificate. Problems are aggregated into [`TomlConfigError::Validation`].
+    pub fn validate_startup(&self) -> Result<()> {
+        let mut problems = self.validate().err().unwrap_or_default();
+        if let Some(server) = self.server.as_ref() {
+            problems.extend(server.validate_address().err());
+            problems.extend(server.validate_volumes().err());
+            problems.extend(server.validate_access_key().err());
+        }
+        if let Some(tls) = self.tls.as_ref() {
+            problems.extend(tls.validate_files());
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(TomlConfigError::Validation(problems))
+        }
+    }
+}
+
+/// Required sections and fields, then the cross-field checks that serde cannot express
+///
+/// Checked at load time; [`Config::validate_startup`] repeats them along with the host checks.
+impl Validate for Config {
+    fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        match self.server.as_ref() {
+            None => problems.push("[server] section is missing".to_string()),
+            Some(server) => {
+                if server.volumes.as_deref().is_none_or(|v| v.trim().is_empty()) {
+                    problems.push("server.volumes must not be empty".to_string());
+                }
+                problems.extend(server.validate_credentials().err());
+            }
+        }
+        if let Some(observability) = self.observability.as_ref() {
+            problems.extend(observability.validate().err());
+        }
+        if let Some(tls) = self.tls.as_ref() {
+            problems.extend(tls.crypto_provider().err());
+        }
+        if let Some(storage) = self.storage.as_ref() {
+            problems.extend(storage.min_free_bytes_value().err());
+            problems.extend(storage.min_free_percent_value().err());
+            problems.extend(storage.read_only_bucket_set().err());
+        }
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
 }
 
 pub fn init_config() -> Result<Success> {
-    let config = match load_config(env::var(ENVIRONMENT).map(|v| PRO_ENV.contains(&v.as_str())).unwrap_or(false)) {
+    let config = match load_config(Environment::from_env()) {
         Ok(c) => c,
         Err(e) => {
             error!("Failed to load config: {}", e);
@@ -46,3 +189,352 @@ pub fn get_config() -> &'static Config {
     CONFIG.get().expect("Config not initialized. Call init_config() first.")
 }
 
+/// Install a minimal config for tests of code that reads `get_config`
+#[cfg(test)]
+pub(crate) fn init_test_config() {
+    CONFIG.get_or_init(|| nebulafx_tomlx::load_config_from_str("[server]\nvolumes = \"/tmp/nebulafx-test\"\n").unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_tomlx::{load_config_from_str, load_config_validated};
+    use std::sync::Arc;
+
+    fn validate(server: &str) -> std::result::Result<(), Vec<String>> {
+        load_config_from_str::<Config>(&format!("[server]\nvolumes = \"/data\"\n{server}"))
+            .unwrap()
+            .validate()
+    }
+
+    #[test]
+    fn test_credential_pairs_set_together() {
+        assert!(validate("access_key = \"nebulafxadmin\"\nsecret_key = \"nebulafxadmin\"\n").is_ok());
+        assert!(validate("root_user = \"admin\"\nroot_password = \"a-long-password\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_credentials_both_absent() {
+        assert!(validate("port = 9000\n").is_ok());
+    }
+
+    #[test]
+    fn test_missing_one_credential() {
+        let problems = validate("access_key = \"nebulafxadmin\"\n").unwrap_err();
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("server.secret_key is missing"));
+
+        let problems = validate("root_password = \"a-long-password\"\n").unwrap_err();
+        assert!(problems[0].contains("server.root_user is missing"));
+    }
+
+    #[test]
+    fn test_short_secret_rejected() {
+        let problems = validate("access_key = \"admin\"\nsecret_key = \"short\"\n").unwrap_err();
+        assert!(problems[0].contains("at least 8 characters"));
+    }
+
+    #[test]
+    fn test_config_path_precedence() {
+        assert_eq!(
+            resolve_config_path(None, None, Environment::Development),
+            PathBuf::from("config.dev.toml")
+        );
+        assert_eq!(resolve_config_path(None, None, Environment::Production), PathBuf::from("config.toml"));
+        assert_eq!(
+            resolve_config_path(None, None, Environment::Staging),
+            PathBuf::from("config.staging.toml")
+        );
+        assert_eq!(resolve_config_path(None, Some(""), Environment::Production), PathBuf::from("config.toml"));
+        assert_eq!(
+            resolve_config_path(None, Some("/etc/nebulafx/env.toml"), Environment::Production),
+            PathBuf::from("/etc/nebulafx/env.toml")
+        );
+        assert_eq!(
+            resolve_config_path(
+                Some(Path::new("/opt/flag.toml")),
+                Some("/etc/nebulafx/env.toml"),
+                Environment::Development
+            ),
+            PathBuf::from("/opt/flag.toml")
+        );
+    }
+
+    #[test]
+    fn test_environment_spellings() {
+        for value in [
+            "p",
+            "P",
+            "pro",
+            "PRO",
+            "prod",
+            "Prod",
+            "production",
+            "PRODUCTION",
+            "Production ",
+            " prod\n",
+        ] {
+            assert_eq!(Environment::parse(value), Environment::Production, "{value:?}");
+        }
+        for value in ["staging", "Staging", "STAGE", " staging "] {
+            assert_eq!(Environment::parse(value), Environment::Staging, "{value:?}");
+        }
+        for value in ["", "dev", "development", "test", "producton"] {
+            assert_eq!(Environment::parse(value), Environment::Development, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_environment_read_from_env() {
+        temp_env::with_var(ENVIRONMENT, Some("Production "), || {
+            assert_eq!(Environment::from_env(), Environment::Production);
+        });
+        temp_env::with_var(ENVIRONMENT, None::<&str>, || {
+            assert_eq!(Environment::from_env(), Environment::Development);
+        });
+    }
+
+    #[test]
+    fn test_config_path_env_read_by_active_path() {
+        temp_env::with_var(CONFIG_PATH_ENV, Some("/srv/nebulafx.yaml"), || {
+            assert_eq!(active_config_path(), PathBuf::from("/srv/nebulafx.yaml"));
+        });
+    }
+
+    fn required_fields(content: &str) -> std::result::Result<(), Vec<String>> {
+        load_config_from_str::<Config>(content).unwrap().validate()
+    }
+
+    #[test]
+    fn test_missing_server_section_rejected() {
+        let problems = required_fields("[storage]\nbase_path = \"/data\"\n").unwrap_err();
+        assert_eq!(problems, vec!["[server] section is missing".to_string()]);
+    }
+
+    #[test]
+    fn test_empty_volumes_rejected() {
+        let problems = required_fields("[server]\nport = 9000\n").unwrap_err();
+        assert_eq!(problems, vec!["server.volumes must not be empty".to_string()]);
+        assert!(required_fields("[server]\nvolumes = \"  \"\n").is_err());
+        assert!(required_fields("[server]\nvolumes = \"/data/disk{1...4}\"\n").is_ok());
+    }
+
+    #[test]
+    fn test_required_and_cross_field_problems_reported_together() {
+        let problems =
+            required_fields("[server]\naccess_key = \"nebulafxadmin\"\n[storage]\nmin_free_percent = 100.0\n").unwrap_err();
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert_eq!(problems[0], "server.volumes must not be empty");
+        assert!(problems[1].contains("server.secret_key is missing"));
+        assert!(problems[2].contains("storage.min_free_percent"));
+    }
+
+    #[test]
+    fn test_missing_server_reported_by_loader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[storage]\nbase_path = \"/data\"\n").unwrap();
+
+        let err = load_config_validated::<Config>(&path, false).unwrap_err();
+        assert!(matches!(&err, TomlConfigError::Validation(problems) if problems.len() == 1));
+        assert!(err.to_string().contains("[server] section is missing"));
+    }
+
+    fn credentials(server: &str) -> Result<Config> {
+        let mut config = load_config_from_str::<Config>(&format!("[server]\nvolumes = \"/data\"\n{server}")).unwrap();
+        config.resolve_credential_files().map(|()| config)
+    }
+
+    #[test]
+    fn test_credentials_read_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let access = dir.path().join("access_key");
+        let secret = dir.path().join("secret_key");
+        std::fs::write(&access, "fileadmin\n").unwrap();
+        std::fs::write(&secret, "file-secret-value\r\n").unwrap();
+
+        let config = credentials(&format!(
+            "access_key_file = \"{}\"\nsecret_key_file = \"{}\"\n",
+            access.display(),
+            secret.display()
+        ))
+        .unwrap();
+        let server = config.server.as_ref().unwrap();
+        assert_eq!(server.access_key.as_deref(), Some("fileadmin"));
+        assert_eq!(server.secret_key.as_deref(), Some("file-secret-value"));
+        assert!(config.validate().is_ok());
+
+        // A matching inline value is not a conflict
+        let config = credentials(&format!("access_key = \"fileadmin\"\naccess_key_file = \"{}\"\n", access.display())).unwrap();
+        assert_eq!(config.server.unwrap().access_key.as_deref(), Some("fileadmin"));
+    }
+
+    #[test]
+    fn test_conflicting_inline_and_file_credentials_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("secret_key");
+        std::fs::write(&secret, "file-secret-value\n").unwrap();
+
+        let err =
+            credentials(&format!("secret_key = \"inline-secret\"\nsecret_key_file = \"{}\"\n", secret.display())).unwrap_err();
+        assert!(matches!(err, TomlConfigError::Invalid(_)));
+        assert!(
+            err.to_string()
+                .contains("server.secret_key and server.secret_key_file are both set but differ")
+        );
+    }
+
+    #[test]
+    fn test_missing_credential_file_reported() {
+        let err = credentials("access_key_file = \"/nonexistent/access_key\"\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("failed to read server.access_key_file '/nonexistent/access_key'")
+        );
+    }
+
+    fn startup_problems(content: &str) -> Vec<String> {
+        match load_config_from_str::<Config>(content).unwrap().validate_startup() {
+            Ok(()) => Vec::new(),
+            Err(TomlConfigError::Validation(problems)) => problems,
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+
+    const VALID_SERVER: &str = "[server]\nhost = \"127.0.0.1\"\nport = 9000\nvolumes = \"/data/disk{1...4}\"\n";
+
+    #[test]
+    fn test_startup_validation_passes_for_valid_config() {
+        assert!(startup_problems(VALID_SERVER).is_empty());
+    }
+
+    #[test]
+    fn test_startup_rejects_unresolvable_host() {
+        let problems = startup_problems("[server]\nhost = \"no-such-host.invalid\"\nvolumes = \"/data\"\n");
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("server.host and server.port"));
+    }
+
+    #[test]
+    fn test_startup_rejects_bad_volume_ranges() {
+        let problems = startup_problems("[server]\nhost = \"127.0.0.1\"\nvolumes = \"/data/disk{1..4}\"\n");
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("invalid server.volumes entry '/data/disk{1..4}'"));
+    }
+
+    #[test]
+    fn test_startup_rejects_short_keys() {
+        let problems = startup_problems(&format!("{VALID_SERVER}access_key = \"ab\"\nsecret_key = \"short\"\n"));
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].contains("server.secret_key must be at least 8 characters"));
+        assert!(problems[1].contains("server.access_key must be at least 3 characters"));
+    }
+
+    #[test]
+    fn test_startup_rejects_incomplete_tls_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = format!("[tls]\npath = \"{}\"\n", dir.path().display());
+        let problems = startup_problems(&format!("{VALID_SERVER}{tls}"));
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("has no nebulafx_cert.pem and nebulafx_key.pem"), "{problems:?}");
+
+        std::fs::write(dir.path().join(nebulafx_config::NEUBULAFX_TLS_KEY), "").unwrap();
+        let problems = startup_problems(&format!("{VALID_SERVER}{tls}"));
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].starts_with("TLS certificate"), "{problems:?}");
+
+        std::fs::write(dir.path().join(nebulafx_config::NEUBULAFX_TLS_CERT), "").unwrap();
+        assert!(startup_problems(&format!("{VALID_SERVER}{tls}")).is_empty());
+    }
+
+    #[test]
+    fn test_startup_accepts_missing_or_sni_only_tls_directory() {
+        // Without the directory the server falls back to plain HTTP
+        assert!(startup_problems(&format!("{VALID_SERVER}[tls]\npath = \"/nonexistent/tls\"\n")).is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let domain = dir.path().join("s3.example.com");
+        std::fs::create_dir(&domain).unwrap();
+        std::fs::write(domain.join(nebulafx_config::NEUBULAFX_TLS_CERT), "").unwrap();
+        std::fs::write(domain.join(nebulafx_config::NEUBULAFX_TLS_KEY), "").unwrap();
+        let tls = format!("[tls]\npath = \"{}\"\n", dir.path().display());
+        assert!(startup_problems(&format!("{VALID_SERVER}{tls}")).is_empty());
+    }
+
+    #[test]
+    fn test_startup_problems_aggregated_across_sections() {
+        let problems = startup_problems(
+            "[server]\nhost = \"127.0.0.1\"\naccess_key = \"ab\"\nsecret_key = \"a-long-secret\"\n[storage]\nmin_free_percent = 100.0\n",
+        );
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert_eq!(problems[0], "server.volumes must not be empty");
+        assert!(problems[1].contains("storage.min_free_percent"));
+        assert!(problems[2].contains("server.access_key"));
+    }
+
+    fn tls_config(tls: &str) -> Config {
+        load_config_from_str::<Config>(&format!("{VALID_SERVER}[tls]\n{tls}")).unwrap()
+    }
+
+    #[test]
+    fn test_restricted_cipher_suites_build_server_config() {
+        let config = tls_config("cipher_suites = [\"TLS13_AES_256_GCM_SHA384\"]\nkx_groups = [\"secp384r1\", \"X25519\"]\n");
+        assert!(config.validate().is_ok());
+
+        let server_config = crate::server::tls_server_builder(config.tls.as_ref())
+            .unwrap()
+            .with_cert_resolver(Arc::new(rustls::server::ResolvesServerCertUsingSni::new()));
+        let provider = server_config.crypto_provider();
+        assert_eq!(provider.cipher_suites.len(), 1);
+        assert_eq!(provider.cipher_suites[0].suite().as_str(), Some("TLS13_AES_256_GCM_SHA384"));
+        let groups: Vec<_> = provider.kx_groups.iter().map(|g| g.name().as_str()).collect();
+        assert_eq!(groups, vec![Some("secp384r1"), Some("X25519")]);
+    }
+
+    #[test]
+    fn test_tls_defaults_when_unset() {
+        let config = tls_config("path = \"/opt/tls\"\n");
+        let provider = config.tls.as_ref().unwrap().crypto_provider().unwrap();
+        assert_eq!(provider.cipher_suites.len(), rustls::crypto::ring::default_provider().cipher_suites.len());
+    }
+
+    #[test]
+    fn test_unknown_tls_names_rejected() {
+        let problems = tls_config("cipher_suites = [\"TLS_RSA_WITH_RC4_128_SHA\"]\n")
+            .validate()
+            .unwrap_err();
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("unknown tls.cipher_suites entry 'TLS_RSA_WITH_RC4_128_SHA'"));
+
+        let problems = tls_config("kx_groups = [\"curve9000\"]\n").validate().unwrap_err();
+        assert!(problems[0].contains("unknown tls.kx_groups entry 'curve9000'"));
+
+        assert!(tls_config("cipher_suites = []\n").validate().is_err());
+    }
+
+    #[test]
+    fn test_min_free_space_settings_validated() {
+        let storage = |body: &str| load_config_from_str::<Config>(&format!("{VALID_SERVER}[storage]\n{body}")).unwrap();
+
+        assert!(
+            storage("min_free_bytes = \"10GiB\"\nmin_free_percent = 5.0\n")
+                .validate()
+                .is_ok()
+        );
+        assert!(storage("min_free_bytes = \"lots\"\n").validate().is_err());
+        let problems = storage("min_free_percent = 100.0\n").validate().unwrap_err();
+        assert!(problems[0].contains("storage.min_free_percent"));
+    }
+
+    #[test]
+    fn test_read_only_bucket_names_validated() {
+        let storage = |body: &str| load_config_from_str::<Config>(&format!("{VALID_SERVER}[storage]\n{body}")).unwrap();
+
+        let config = storage("read_only_buckets = [\"archive-2023\", \"legal\"]\n");
+        assert!(config.validate().is_ok());
+        assert_eq!(config.storage.unwrap().read_only_bucket_set().unwrap().len(), 2);
+
+        let problems = storage("read_only_buckets = [\"Bad_Bucket\"]\n").validate().unwrap_err();
+        assert!(problems[0].contains("invalid bucket 'Bad_Bucket' in storage.read_only_buckets"));
+    }
+}
@@ -0,0 +1,294 @@
+use super::{Config, finish_loading};
+use nebulafx_tomlx::{ConfigWatcher, watch_config};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// Part of the configuration that can be applied without restarting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReloadSection {
+    /// `observability.logger_level` and `observability.log_format`
+    Logging,
+    /// `server.console_cors_allowed_origins`
+    Cors,
+    /// `console.access_log_level` and `console.access_log_exclude_paths`
+    ConsoleAccessLog,
+}
+
+/// Keys that are only read at startup; changing them requires a restart
+const RESTART_REQUIRED: [&str; 6] = [
+    "server.host",
+    "server.port",
+    "server.volumes",
+    "server.cors_allowed_origins",
+    "storage.base_path",
+    "tls",
+];
+
+/// Differences between two configurations, grouped by how they can be applied
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    pub changed: Vec<ReloadSection>,
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+/// Compare a field of an optional section, treating a missing section like an unset field
+fn differs<S, T: PartialEq>(old: Option<&S>, new: Option<&S>, field: impl Fn(&S) -> T) -> bool {
+    old.map(&field) != new.map(&field)
+}
+
+impl Config {
+    /// Sections that differ between `self` and `new`
+    pub fn diff(&self, new: &Config) -> ConfigDiff {
+        let (old_server, new_server) = (self.server.as_ref(), new.server.as_ref());
+        let (old_obs, new_obs) = (self.observability.as_ref(), new.observability.as_ref());
+        let (old_console, new_console) = (self.console.as_ref(), new.console.as_ref());
+
+        let mut diff = ConfigDiff::default();
+        if differs(old_obs, new_obs, |o| (o.logger_level, o.log_format)) {
+            diff.changed.push(ReloadSection::Logging);
+        }
+        if differs(old_server, new_server, |s| s.console_cors_allowed_origins.clone()) {
+            diff.changed.push(ReloadSection::Cors);
+        }
+        if differs(old_console, new_console, |c| (c.access_log_level, c.access_log_exclude_paths.clone())) {
+            diff.changed.push(ReloadSection::ConsoleAccessLog);
+        }
+
+        let restart = [
+            differs(old_server, new_server, |s| s.host.clone()),
+            differs(old_server, new_server, |s| s.port),
+            differs(old_server, new_server, |s| s.volumes.clone()),
+            differs(old_server, new_server, |s| s.cors_allowed_origins.clone()),
+            differs(self.storage.as_ref(), new.storage.as_ref(), |s| s.base_path.clone()),
+            differs(self.tls.as_ref(), new.tls.as_ref(), |t| {
+                (t.path.clone(), t.key_file.clone(), t.cert_file.clone())
+            }),
+        ];
+        diff.restart_required = RESTART_REQUIRED
+            .into_iter()
+            .zip(restart)
+            .filter_map(|(key, changed)| changed.then_some(key))
+            .collect();
+        diff
+    }
+}
+
+type ReloadHandler = Box<dyn Fn(&Config) -> Result<(), String> + Send + Sync>;
+
+/// Outcome of applying a new configuration
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    pub applied: Vec<ReloadSection>,
+    pub failed: Vec<(ReloadSection, String)>,
+    /// Changed sections no handler is registered for; they take effect after a restart
+    pub unhandled: Vec<ReloadSection>,
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Routes changed config sections to the handlers that reconfigure them
+///
+/// Subsystems whose section did not change are left running untouched.
+#[derive(Default)]
+pub struct ReloadDispatcher {
+    handlers: HashMap<ReloadSection, Vec<ReloadHandler>>,
+}
+
+impl ReloadDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler called with the new config whenever `section` changes
+    pub fn on<F>(&mut self, section: ReloadSection, handler: F) -> &mut Self
+    where
+        F: Fn(&Config) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.handlers.entry(section).or_default().push(Box::new(handler));
+        self
+    }
+
+    /// Apply the sections that differ between `old` and `new`
+    pub fn apply(&self, old: &Config, new: &Config) -> ReloadReport {
+        let diff = old.diff(new);
+        let mut report = ReloadReport {
+            restart_required: diff.restart_required,
+            ..Default::default()
+        };
+
+        for section in diff.changed {
+            let Some(handlers) = self.handlers.get(&section) else {
+                warn!(target: "nebulafx::main::reload", section = ?section, "Config change takes effect after a restart");
+                report.unhandled.push(section);
+                continue;
+            };
+            match handlers.iter().try_for_each(|handler| handler(new)) {
+                Ok(()) => {
+                    info!(target: "nebulafx::main::reload", section = ?section, "Applied config change");
+                    report.applied.push(section);
+                }
+                Err(e) => {
+                    error!(target: "nebulafx::main::reload", section = ?section, "Failed to apply config change: {}", e);
+                    report.failed.push((section, e));
+                }
+            }
+        }
+        if !report.restart_required.is_empty() {
+            warn!(
+                target: "nebulafx::main::reload",
+                "Config changes to {} take effect after a restart",
+                report.restart_required.join(", ")
+            );
+        }
+        report
+    }
+
+    /// Apply each saved change to the config file at `path`, starting from `current`
+    ///
+    /// The new file goes through the same credential resolution and validation as at
+    /// startup; a file that fails them is logged and skipped, and `current` stays in effect.
+    pub fn watch(self, path: impl AsRef<Path>, current: Config) -> nebulafx_tomlx::Result<ConfigWatcher> {
+        let current = Mutex::new(current);
+        watch_config(path, move |new: Config| {
+            let new = match finish_loading(new) {
+                Ok(new) => new,
+                Err(e) => {
+                    warn!(target: "nebulafx::main::reload", "Ignoring config change: {}", e);
+                    return;
+                }
+            };
+            let mut current = current.lock().unwrap();
+            self.apply(&current, &new);
+            *current = new;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_tomlx::load_config_from_str;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    const BASE: &str = r#"
+        [server]
+        host = "0.0.0.0"
+        port = 9000
+        volumes = "/data"
+        console_cors_allowed_origins = "*"
+
+        [observability]
+        logger_level = "info"
+
+        [console]
+        access_log_level = "info"
+    "#;
+
+    fn config(toml: &str) -> Config {
+        load_config_from_str(toml).unwrap()
+    }
+
+    fn counting(dispatcher: &mut ReloadDispatcher, section: ReloadSection) -> Arc<AtomicUsize> {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        dispatcher.on(section, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+        calls
+    }
+
+    #[test]
+    fn test_cors_change_triggers_only_cors_handler() {
+        let mut dispatcher = ReloadDispatcher::new();
+        let logging = counting(&mut dispatcher, ReloadSection::Logging);
+        let cors = counting(&mut dispatcher, ReloadSection::Cors);
+        let access_log = counting(&mut dispatcher, ReloadSection::ConsoleAccessLog);
+
+        let old = config(BASE);
+        let new = config(&BASE.replace(
+            r#"console_cors_allowed_origins = "*""#,
+            r#"console_cors_allowed_origins = "https://a.example""#,
+        ));
+        let report = dispatcher.apply(&old, &new);
+
+        assert_eq!(cors.load(Ordering::SeqCst), 1);
+        assert_eq!(logging.load(Ordering::SeqCst), 0);
+        assert_eq!(access_log.load(Ordering::SeqCst), 0);
+        assert_eq!(report.applied, vec![ReloadSection::Cors]);
+        assert!(report.restart_required.is_empty());
+    }
+
+    #[test]
+    fn test_bind_address_requires_restart() {
+        let old = config(BASE);
+        let new = config(&BASE.replace("port = 9000", "port = 9001"));
+
+        let diff = old.diff(&new);
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.restart_required, vec!["server.port"]);
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_failed_handler_is_reported() {
+        let mut dispatcher = ReloadDispatcher::new();
+        dispatcher.on(ReloadSection::Logging, |_| Err("filter reload failed".to_string()));
+
+        let new = config(&BASE.replace(r#"logger_level = "info""#, r#"logger_level = "debug""#));
+        let report = dispatcher.apply(&config(BASE), &new);
+        assert_eq!(report.failed, vec![(ReloadSection::Logging, "filter reload failed".to_string())]);
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn test_change_without_handler_is_left_for_restart() {
+        let dispatcher = ReloadDispatcher::new();
+        let new = config(&BASE.replace(r#"logger_level = "info""#, r#"logger_level = "debug""#));
+
+        let report = dispatcher.apply(&config(BASE), &new);
+        assert_eq!(report.unhandled, vec![ReloadSection::Logging]);
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn test_saved_config_change_reaches_handler() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, BASE).unwrap();
+
+        let mut dispatcher = ReloadDispatcher::new();
+        let cors = counting(&mut dispatcher, ReloadSection::Cors);
+        let logging = counting(&mut dispatcher, ReloadSection::Logging);
+        let _watcher = dispatcher.watch(&path, config(BASE)).unwrap();
+
+        // An invalid file is skipped, so the valid one after it is compared with BASE
+        std::fs::write(&path, BASE.replace(r#"volumes = "/data""#, "")).unwrap();
+        std::thread::sleep(Duration::from_millis(800));
+        std::fs::write(
+            &path,
+            BASE.replace(
+                r#"console_cors_allowed_origins = "*""#,
+                r#"console_cors_allowed_origins = "https://a.example""#,
+            ),
+        )
+        .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while cors.load(Ordering::SeqCst) == 0 {
+            assert!(Instant::now() < deadline, "CORS handler was not called");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert_eq!(cors.load(Ordering::SeqCst), 1);
+        assert_eq!(logging.load(Ordering::SeqCst), 0);
+    }
+}
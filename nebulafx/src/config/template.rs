@@ -0,0 +1,128 @@
+use super::{Config, ServerConfig, StorageConfig, TlsConfig};
+use nebulafx_obs::ObservabilityConfig;
+use nebulafx_postgresqlx::{PostgreSQLConfig, PostgreSQLConnectionConfig};
+use nebulafx_tomlx::{LogLevel, Result, save_config_commented};
+use std::path::Path;
+
+const HEADER: &str = "NebulaFX configuration generated by `nebulafx config init`
+Unset keys fall back to their defaults; see config.toml in the repository for every option";
+
+/// Comments written above each key of the generated file, by dotted key path
+const COMMENTS: &[(&str, &str)] = &[
+    ("server", "S3 and console listener"),
+    ("server.name", "Display name of this server"),
+    ("server.host", "Address to listen on"),
+    ("server.port", "S3 API port"),
+    ("server.region", "S3 region; also advertised to the console"),
+    ("server.volumes", "Data directories; `{1...4}` expands to one drive per number"),
+    ("server.access_key", "S3 credentials; change both before exposing the server"),
+    ("server.root_user", "Console administrator; change both before exposing the server"),
+    ("server.iam_init", "IAM loading at boot: \"eager\" or \"lazy\""),
+    ("server.role", "Node role: \"primary\" or \"standby\""),
+    ("database", "PostgreSQL metadata store"),
+    ("database.password", "Change before deploying"),
+    ("database.sslmode", "TLS: disable/allow/prefer/require/verify-ca/verify-full"),
+    ("database.auto_migrate", "Apply pending migrations when the pool starts"),
+    ("database.connection", "Connection pool"),
+    ("database.connection.timeout", "Time allowed to establish a connection"),
+    ("database.connection.max_retries", "Attempts before startup gives up on the database"),
+    ("storage", "Local storage"),
+    ("storage.allow_format", "Format blank disks on first boot; set false to fail instead"),
+    (
+        "tls",
+        "TLS for the listeners, off until path names a directory holding nebulafx_cert.pem and\n\
+         nebulafx_key.pem, plus one subdirectory per SNI domain:\npath = \"/opt/tls\"",
+    ),
+    ("tls.expiry_warning_days", "Log a warning for certificates expiring within this many days"),
+    ("tls.fail_on_expired", "Fail startup when a certificate has already expired"),
+    ("observability", "Logging and telemetry"),
+    ("observability.logger_level", "trace/debug/info/warn/error"),
+    ("observability.log_directory", "Directory for log files"),
+    ("observability.log_rotation_time", "Rotation period: hour/day/minute/second"),
+];
+
+/// Config written by `config init`: a single-node server with the sections new users edit first
+fn default_config() -> Config {
+    Config {
+        server: Some(ServerConfig {
+            name: Some("NebulaFX".to_string()),
+            host: Some("0.0.0.0".to_string()),
+            port: Some(9000),
+            region: Some("us-east-1".to_string()),
+            volumes: Some("/data/nebulafx{1...4}".to_string()),
+            access_key: Some("nebulafxadmin".to_string()),
+            secret_key: Some("nebulafxadmin".to_string()),
+            root_user: Some("nebulafxadmin".to_string()),
+            root_password: Some("nebulafxadmin".to_string()),
+            iam_init: Some(Default::default()),
+            role: Some(Default::default()),
+            ..Default::default()
+        }),
+        database: Some(PostgreSQLConfig {
+            host: Some("localhost".to_string()),
+            port: Some(5432),
+            user: Some("postgres".to_string()),
+            password: Some("postgres".to_string()),
+            database: Some("nebulafx".to_string()),
+            sslmode: Some(Default::default()),
+            auto_migrate: Some(true),
+            connection: Some(PostgreSQLConnectionConfig {
+                timeout: Some("5s".to_string()),
+                max_retries: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        storage: Some(StorageConfig {
+            allow_format: Some(true),
+            ..Default::default()
+        }),
+        tls: Some(TlsConfig {
+            expiry_warning_days: Some(30),
+            fail_on_expired: Some(false),
+            ..Default::default()
+        }),
+        observability: Some(ObservabilityConfig {
+            use_stdout: Some(true),
+            logger_level: Some(LogLevel::Info),
+            log_stdout_enabled: Some(true),
+            log_directory: Some("./logs".to_string()),
+            log_filename: Some("nebulafx".to_string()),
+            log_rotation_time: Some("day".to_string()),
+            log_keep_files: Some(30),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Write [`default_config`] to `path` with a comment above each key, replacing any existing file
+pub fn write_default_config(path: &Path) -> Result<()> {
+    save_config_commented(path, &default_config(), HEADER, COMMENTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_tomlx::load_config_validated;
+
+    #[test]
+    fn test_generated_config_reparses_and_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        write_default_config(&path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        for section in ["[server]", "[database]", "[storage]", "[tls]", "[observability]"] {
+            assert!(content.contains(section), "{section} missing from:\n{content}");
+        }
+        assert!(content.contains("# S3 API port\nport = 9000\n"), "{content}");
+        assert!(content.contains("# path = \"/opt/tls\"\n"), "{content}");
+
+        // Checked as the server checks it at startup, so an unset TLS directory stays valid
+        let config = load_config_validated::<Config>(&path, false).unwrap();
+        assert!(config.tls.as_ref().is_some_and(|tls| tls.path.is_none()));
+        config.validate_startup().unwrap();
+        assert_eq!(config.server.unwrap().volumes, default_config().server.unwrap().volumes);
+    }
+}
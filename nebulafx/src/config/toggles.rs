@@ -0,0 +1,102 @@
+use metrics::counter;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+use tracing::warn;
+
+/// A behavior that used to be switched by a `NEUBULAFX_ENABLE_*` variable and now has a config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvToggle {
+    /// Deprecated environment variable
+    pub env: &'static str,
+    /// Config key that supersedes it
+    pub config_key: &'static str,
+    pub default: bool,
+}
+
+pub const SCANNER_TOGGLE: EnvToggle = EnvToggle {
+    env: "NEUBULAFX_ENABLE_SCANNER",
+    config_key: "background.scanner",
+    default: true,
+};
+
+pub const HEAL_TOGGLE: EnvToggle = EnvToggle {
+    env: "NEUBULAFX_ENABLE_HEAL",
+    config_key: "background.heal",
+    default: true,
+};
+
+pub const PROFILING_TOGGLE: EnvToggle = EnvToggle {
+    env: nebulafx_config::ENV_ENABLE_PROFILING,
+    config_key: "profiling.enabled",
+    default: nebulafx_config::DEFAULT_ENABLE_PROFILING,
+};
+
+/// Env vars whose deprecation warning has already been logged
+static WARNED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+impl EnvToggle {
+    /// Effective value: the config key wins, then the deprecated env var, then the default
+    pub fn resolve(&self, config_value: Option<bool>) -> bool {
+        self.resolve_with(std::env::var(self.env).ok().as_deref(), config_value)
+    }
+
+    pub(crate) fn resolve_with(&self, env_value: Option<&str>, config_value: Option<bool>) -> bool {
+        let env_flag = env_value.map(|value| value.trim().parse::<bool>().unwrap_or(self.default));
+        if env_flag.is_some() {
+            counter!("nebulafx_deprecated_env_total", &[("env", self.env)]).increment(1);
+        }
+        match (config_value, env_flag) {
+            (Some(config), Some(_)) => {
+                self.warn_deprecated();
+                config
+            }
+            (Some(config), None) => config,
+            (None, Some(env)) => env,
+            (None, None) => self.default,
+        }
+    }
+
+    /// Log that the env var is overridden, once per process; returns whether this call logged
+    fn warn_deprecated(&self) -> bool {
+        if !WARNED.lock().unwrap().insert(self.env) {
+            return false;
+        }
+        warn!(
+            target: "nebulafx::config::deprecated_env",
+            env = self.env,
+            config_key = self.config_key,
+            "{} is deprecated and ignored because {} is set; the config key takes precedence",
+            self.env,
+            self.config_key
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_wins_over_deprecated_env_and_warns_once() {
+        let toggle = EnvToggle {
+            env: "NEUBULAFX_ENABLE_TEST_TOGGLE",
+            config_key: "background.test",
+            default: true,
+        };
+
+        assert!(!toggle.resolve_with(Some("true"), Some(false)));
+        assert!(WARNED.lock().unwrap().contains(toggle.env));
+        // The warning already fired for this variable
+        assert!(!toggle.warn_deprecated());
+        assert!(toggle.resolve_with(Some("false"), Some(true)));
+    }
+
+    #[test]
+    fn test_env_and_default_apply_without_config() {
+        assert!(!HEAL_TOGGLE.resolve_with(Some("false"), None));
+        assert!(HEAL_TOGGLE.resolve_with(Some("not-a-bool"), None));
+        assert!(HEAL_TOGGLE.resolve_with(None, None));
+        assert!(!WARNED.lock().unwrap().contains(HEAL_TOGGLE.env));
+    }
+}
@@ -0,0 +1,85 @@
+use nebulafx_postgresqlx::PostgreSQLError;
+use nebulafx_tomlx::TomlConfigError;
+use std::process::ExitCode;
+
+/// Generic failure; restarting may or may not help
+pub(crate) const EXIT_FAILURE: u8 = 1;
+/// A dependency such as the database or network was unavailable (`EX_TEMPFAIL`); a restart may succeed
+pub(crate) const EXIT_TEMPFAIL: u8 = 75;
+/// The configuration is invalid (`EX_CONFIG`); restarting will fail the same way
+pub(crate) const EXIT_CONFIG: u8 = 78;
+
+/// Startup failure classified by whether an orchestrator should restart the process
+///
+/// See `docs/EXIT_CODES.md` for the code mapping.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StartupError {
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    #[error("Temporary failure: {0}")]
+    Temporary(String),
+
+    #[error(transparent)]
+    Other(#[from] std::io::Error),
+}
+
+impl StartupError {
+    pub(crate) fn exit_code(&self) -> u8 {
+        match self {
+            StartupError::Config(_) => EXIT_CONFIG,
+            StartupError::Temporary(_) => EXIT_TEMPFAIL,
+            StartupError::Other(_) => EXIT_FAILURE,
+        }
+    }
+}
+
+impl From<TomlConfigError> for StartupError {
+    fn from(e: TomlConfigError) -> Self {
+        StartupError::Config(e.to_string())
+    }
+}
+
+impl From<PostgreSQLError> for StartupError {
+    fn from(e: PostgreSQLError) -> Self {
+        match e {
+            // A failing migration fails the same way on every restart
+            PostgreSQLError::ConfigurationError(_) | PostgreSQLError::MigrationFailed(_) => StartupError::Config(e.to_string()),
+            PostgreSQLError::ConnectionFailed(_)
+            | PostgreSQLError::QueryError(_)
+            | PostgreSQLError::PoolError(_)
+            | PostgreSQLError::TransactionError(_) => StartupError::Temporary(e.to_string()),
+        }
+    }
+}
+
+impl From<StartupError> for ExitCode {
+    fn from(e: StartupError) -> Self {
+        ExitCode::from(e.exit_code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use nebulafx_tomlx::load_config_from_str;
+
+    #[test]
+    fn test_invalid_config_exits_with_config_code() {
+        let err = load_config_from_str::<Config>("[server]\nport = \"not-a-port\"\n").unwrap_err();
+        assert_eq!(StartupError::from(err).exit_code(), EXIT_CONFIG);
+    }
+
+    #[test]
+    fn test_database_errors_are_classified() {
+        let unreachable = PostgreSQLError::ConnectionFailed("connection refused".to_string());
+        assert_eq!(StartupError::from(unreachable).exit_code(), EXIT_TEMPFAIL);
+
+        let missing = PostgreSQLError::ConfigurationError("database section missing".to_string());
+        assert_eq!(StartupError::from(missing).exit_code(), EXIT_CONFIG);
+
+        let io = StartupError::from(std::io::Error::other("bind failed"));
+        assert_eq!(io.exit_code(), EXIT_FAILURE);
+    }
+}
@@ -1,22 +1,30 @@
 mod admin;
 mod auth;
+mod bench;
+mod cli;
 mod config;
 mod error;
+mod exit;
 // mod grpc;
 
 mod server;
 mod storage;
+mod support_bundle;
 
+use crate::exit::StartupError;
 use crate::server::{
-    SHUTDOWN_TIMEOUT, ServiceState, ServiceStateManager, ShutdownSignal, init_event_notifier, shutdown_event_notifier,
-    start_audit_system, start_http_server, stop_audit_system, wait_for_shutdown,
+    ConsoleSocket, DEFAULT_SHUTDOWN_GRACE_PERIOD, DEFAULT_STARTUP_DELAY, RoleStep, ServiceState, ServiceStateManager,
+    ShutdownPlan, ShutdownSignal, iam_readiness, init_event_notifier, record_shutdown_reason, restore_shutdown_reason, role_runs,
+    shutdown_event_notifier, start_audit_system, start_console_socket, start_http_server, start_iam, startup_timeline,
+    stop_audit_system, verify_expected_setup, verify_region_consistency, wait_for_shutdown, wait_until_stopped,
 };
 use crate::storage::ecfs::{process_lambda_configurations, process_queue_configurations, process_topic_configurations};
+use crate::storage::free_space::{FREE_SPACE_POLL_INTERVAL, free_space_guard, spawn_free_space_poller};
 use chrono::Datelike;
 use clap::Parser;
 use nebulafx_ahm::{
     Scanner, create_ahm_services_cancel_token, heal::storage::ECStoreHealStorage, init_heal_manager,
-    scanner::data_scanner::ScannerConfig, shutdown_ahm_services,
+    scanner::data_scanner::ScannerConfig, set_global_scanner, shutdown_ahm_services,
 };
 use nebulafx_common::globals::set_global_addr;
 use nebulafx_ecstore::bucket::metadata_sys;
@@ -40,10 +48,12 @@ use nebulafx_iam::init_iam_sys;
 use nebulafx_notify::notifier_global;
 use nebulafx_obs::init_obs;
 use nebulafx_targets::arn::TargetID;
+use nebulafx_utils::humanize_duration;
 use nebulafx_utils::net::parse_and_resolve_address;
 use s3s::s3_error;
-use std::env;
 use std::io::{Error, Result};
+use std::path::Path;
+use std::process::ExitCode;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
@@ -74,61 +84,77 @@ const LOGO: &str = r#"
 
 "#;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    match start() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("NebulaFX exited with an error: {e}");
+            e.into()
+        }
+    }
+}
+
+fn start() -> std::result::Result<(), StartupError> {
+    let cli = cli::Cli::parse();
+    if let Some(path) = cli.config {
+        config::set_config_path(path);
+    }
+    if let Some(command) = cli.command {
+        return cli::run(command);
+    }
+
     info!("{}", LOGO);
     match init_config() {
         Ok(s) => info!("Config initialized successfully: {}", s),
         Err(e) => {
             error!("Failed to initialize config: {}", e);
-            return Err(Error::other(format!("Failed to initialize config: {}", e)));
+            return Err(e.into());
         }
     }
     match init_obs(get_config().observability.as_ref()) {
         Ok(s) => info!("Observability initialized successfully: {}", s),
         Err(e) => {
             error!("Failed to initialize observability: {}", e);
-            return Err(Error::other(e));
+            return Err(Error::other(e).into());
         }
     }
+    // Logged once observability is up so the line reaches the configured outputs
+    info!(
+        "Loaded {} config from {}",
+        config::Environment::from_env(),
+        config::active_config_path().display()
+    );
     let runtime = get_tokio_runtime_builder(get_config().runtime.as_ref())
         .build()
         .expect("Failed to build Tokio runtime");
     runtime.block_on(async_main())
 }
-async fn async_main() -> Result<()> {
+
+async fn async_main() -> std::result::Result<(), StartupError> {
     let config = get_config();
+    if let Err(e) = config.validate_startup() {
+        error!("Failed to validate config: {}", e);
+        return Err(e.into());
+    }
     // Initialize PostgreSQL connection pool if database config exists
     match PostgreSQLPool::init(config.database.as_ref()).await {
         Ok(s) => info!("PostgreSQL connection pool initialized successfully: {}", s),
-            Err(e) => {
-                error!("Failed to initialize PostgreSQL connection pool: {}", e);
-                return Err(Error::other(format!("Database connection failed: {}", e)));
-            }
+        Err(e) => {
+            error!("Failed to initialize PostgreSQL connection pool: {}", e);
+            return Err(e.into());
         }
-    
-    // Initialize database schema and root user if database is configured
-    if let Some(_) = config.database.as_ref() {
-        use nebulafx_iam::init::{init_database, init_root_user};
-        let pool = PostgreSQLPool::get()
-            .map_err(|e| Error::other(format!("Failed to get database pool: {}", e)))?;
-        
-        // Initialize database tables
-        if let Err(e) = init_database(pool.inner()).await {
+    }
+
+    // Initialize database schema if database is configured
+    if let Some(database) = config.database.as_ref() {
+        use nebulafx_iam::init::init_database;
+        let pool = PostgreSQLPool::get()?;
+
+        let schema = database.schema.as_deref().unwrap_or("public");
+        // Create the IAM tables that are missing from the schema
+        if let Err(e) = init_database(&pool, schema).await {
             error!("Failed to initialize database tables: {}", e);
-            return Err(Error::other(format!("Database initialization failed: {}", e)));
-        }
-        
-        // Initialize root user
-        let root_user = config.server.as_ref()
-            .and_then(|s| s.root_user.as_deref())
-            .unwrap_or("nebulafxadmin");
-        let root_password = config.server.as_ref()
-            .and_then(|s| s.root_password.as_deref())
-            .unwrap_or("nebulafxadmin");
-        
-        if let Err(e) = init_root_user(pool.inner(), root_user, root_password).await {
-            error!("Failed to initialize root user: {}", e);
-            return Err(Error::other(format!("Root user initialization failed: {}", e)));
+            return Err(StartupError::Temporary(format!("Database initialization failed: {}", e)));
         }
     }
 
@@ -137,15 +163,17 @@ async fn async_main() -> Result<()> {
         Ok(s) => info!("Profiling initialized successfully: {}", s),
         Err(e) => {
             error!("Failed to initialize profiling: {}", e);
-            return Err(Error::other(format!("Failed to initialize profiling: {}", e)));
+            return Err(Error::other(format!("Failed to initialize profiling: {}", e)).into());
         }
     }
     // Run with config
     match run(config.as_ref()).await {
         Ok(_) => Ok(()),
         Err(e) => {
+            // Logs the steps reached before the failure, unless startup had already completed
+            startup_timeline().finish(false);
             error!("Server encountered an error and is shutting down: {}", e);
-            Err(e)
+            Err(e.into())
         }
     }
 }
@@ -153,15 +181,23 @@ async fn async_main() -> Result<()> {
 #[instrument(skip(config))]
 async fn run(config: &Config) -> Result<()> {
     debug!("config: {:?}", config);
+    let startup_begin = std::time::Instant::now();
 
     // Get server config
-    let server_config = config.server.as_ref().ok_or_else(|| Error::other("Server config not found"))?;
-    
-    if let Some(region) = &server_config.region {
-        nebulafx_ecstore::global::set_global_region(region.clone());
+    let server_config = config
+        .server
+        .as_ref()
+        .ok_or_else(|| Error::other("Server config not found"))?;
+
+    nebulafx_ecstore::global::set_global_region(server_config.resolved_region().to_string());
+
+    let state_dir = server_config.state_dir.as_deref().map(Path::new);
+    if let Some(dir) = state_dir {
+        restore_shutdown_reason(dir);
     }
 
-    let address = format!("{}:{}", 
+    let address = format!(
+        "{}:{}",
         server_config.host.as_deref().unwrap_or("0.0.0.0"),
         server_config.port.unwrap_or(9000)
     );
@@ -181,9 +217,15 @@ async fn run(config: &Config) -> Result<()> {
 
     // For RPC
     let volumes = server_config.volumes.as_deref().unwrap_or("/deploy/data/dev{1...8}");
+    let timeline = startup_timeline();
+
+    let step = timeline.begin("endpoints");
     let (endpoint_pools, setup_type) = EndpointServerPools::from_volumes(server_address.clone().as_str(), volumes.to_string())
         .await
         .map_err(Error::other)?;
+    let expected_setup = config.storage.as_ref().and_then(|s| s.expected_setup.as_deref());
+    verify_expected_setup(expected_setup, &setup_type)?;
+    step.done();
 
     for (i, eps) in endpoint_pools.as_ref().iter().enumerate() {
         info!(
@@ -224,25 +266,71 @@ async fn run(config: &Config) -> Result<()> {
 
     // 启动主 HTTP 服务器（包含 S3 API 和 Console API 端点）
     // 前端独立运行，不再需要独立的 Console 服务器
-    let s3_shutdown_tx = {
-        let s3_shutdown_tx = start_http_server(config, state_manager.clone()).await?;
-        Some(s3_shutdown_tx)
-    };
+    let step = timeline.begin("http_server");
+    let grace = shutdown_grace_period(server_config);
+    let s3_shutdown_tx = start_http_server(config, state_manager.clone(), grace).await?;
+    // The console socket stops on the same signal as the HTTP listener
+    let console_socket = start_console_socket(config.console.as_ref(), s3_shutdown_tx.subscribe()).await?;
+    let s3_shutdown_tx = Some(s3_shutdown_tx);
+    step.done();
+
+    // Saved changes to the config file reach the subsystems that can apply them live
+    let mut reload = config::ReloadDispatcher::new();
+    reload.on(config::ReloadSection::Cors, |new| {
+        let origins = new.server.as_ref().and_then(|s| s.console_cors_allowed_origins.as_deref());
+        admin::console::reload_console_cors(origins.unwrap_or(nebulafx_config::DEFAULT_CONSOLE_CORS_ALLOWED_ORIGINS))
+    });
+    let _config_watcher = reload
+        .watch(config::active_config_path(), config.clone())
+        .inspect_err(|e| warn!(target: "nebulafx::main::run", "Config changes will not be reloaded: {}", e))
+        .ok();
+    verify_region_consistency(server_config);
 
     set_global_endpoints(endpoint_pools.as_ref().clone());
+    let layout = nebulafx_ecstore::erasure_layout(&endpoint_pools, &setup_type).map_err(Error::other)?;
+    for pool in &layout {
+        info!(
+            target: "nebulafx::main::run",
+            pool = pool.pool,
+            data = pool.data,
+            parity = pool.parity,
+            "Pool {}: {} set(s) of {} drives, {} data + {} parity shards per set",
+            pool.pool + 1, pool.set_count, pool.drives_per_set, pool.data, pool.parity
+        );
+    }
+    nebulafx_ecstore::global::set_global_erasure_layout(layout);
     update_erasure_type(setup_type).await;
 
     // Initialize the local disk
+    let step = timeline.begin("local_disks");
     init_local_disks(endpoint_pools.clone()).await.map_err(Error::other)?;
+    step.done();
+
+    if let Some(guard) = free_space_guard() {
+        let local_drives = endpoint_pools
+            .as_ref()
+            .iter()
+            .flat_map(|pool| pool.endpoints.as_ref())
+            .filter(|ep| ep.is_local)
+            .map(|ep| std::path::PathBuf::from(ep.get_file_path()))
+            .collect();
+        spawn_free_space_poller(guard.clone(), local_drives, FREE_SPACE_POLL_INTERVAL);
+    }
 
     let ctx = CancellationToken::new();
 
+    // Blank disks are only formatted when storage.allow_format permits it
+    let allow_format = config.storage.as_ref().and_then(|s| s.allow_format).unwrap_or(true);
+    nebulafx_ecstore::global::set_global_allow_format(allow_format);
+
     // init store
+    let step = timeline.begin("ecstore");
     let store = ECStore::new(server_addr, endpoint_pools.clone(), ctx.clone())
         .await
         .inspect_err(|err| {
             error!("ECStore::new {:?}", err);
         })?;
+    step.done();
 
     ecconfig::init();
     // config system configuration
@@ -270,21 +358,40 @@ async fn run(config: &Config) -> Result<()> {
     // Collect bucket names into a vector
     let buckets: Vec<String> = buckets_list.into_iter().map(|v| v.name).collect();
 
+    let role = server_config.role.unwrap_or_default();
     if let Some(pool) = GLOBAL_REPLICATION_POOL.get() {
-        pool.clone().init_resync(ctx.clone(), buckets.clone()).await?;
+        if role_runs(role, RoleStep::ReplicationResync) {
+            pool.clone().init_resync(ctx.clone(), buckets.clone()).await?;
+        } else {
+            info!(target: "nebulafx::main::run", role = role.as_str(), "Deferring replication resync to the primary");
+        }
     }
 
     init_bucket_metadata_sys(store.clone(), buckets.clone()).await;
 
     // Initialize IAM system with database pool
-    if let Some(db_config) = config.database.as_ref() {
+    let step = timeline.begin("iam");
+    if config.database.is_some() {
         let pool = PostgreSQLPool::get()
             .map_err(|e| Error::other(format!("Failed to get database pool: {}", e)))?;
-        init_iam_sys(pool.inner().clone()).await.map_err(Error::other)?;
+        let mode = server_config.iam_init.unwrap_or_default();
+        let seed_root = role_runs(role, RoleStep::SeedRootUser);
+        start_iam(mode, iam_readiness(), async move {
+            init_iam_sys(pool.inner().clone()).await.map_err(Error::other)?;
+            if seed_root {
+                seed_root_user(get_config(), &pool).await
+            } else {
+                info!(target: "nebulafx::main::run", "Standby node, leaving root user seeding to the primary");
+                Ok(())
+            }
+        })
+        .await?;
     } else {
         warn!("Database not configured, IAM system will not be initialized");
     }
+    step.done();
 
+    let step = timeline.begin("notifications");
     add_bucket_notification_configuration(buckets.clone()).await;
 
     // Initialize the global notification system
@@ -292,22 +399,27 @@ async fn run(config: &Config) -> Result<()> {
         error!("new_global_notification_sys failed {:?}", &err);
         Error::other(err)
     })?;
+    step.done();
 
     // Create a cancellation token for AHM services
     let _ = create_ahm_services_cancel_token();
 
-    // Check environment variables to determine if scanner and heal should be enabled
-    let enable_scanner = parse_bool_env_var("NEUBULAFX_ENABLE_SCANNER", true);
-    let enable_heal = parse_bool_env_var("NEUBULAFX_ENABLE_HEAL", true);
+    // [background] decides whether scanner and heal run, falling back to the deprecated env vars
+    let (enable_scanner, enable_heal) = background_services_enabled();
+    // Only the primary repairs and scans the shared drives
+    let enable_scanner = enable_scanner && role_runs(role, RoleStep::Scanner);
+    let enable_heal = enable_heal && role_runs(role, RoleStep::Heal);
 
     info!(
         target: "nebulafx::main::run",
         enable_scanner = enable_scanner,
         enable_heal = enable_heal,
+        role = role.as_str(),
         "Background services configuration: scanner={}, heal={}", enable_scanner, enable_heal
     );
 
     // Initialize heal manager and scanner based on environment variables
+    let step = timeline.begin("background_services");
     if enable_heal || enable_scanner {
         if enable_heal {
             // Initialize heal manager with channel processor
@@ -316,31 +428,49 @@ async fn run(config: &Config) -> Result<()> {
 
             if enable_scanner {
                 info!(target: "nebulafx::main::run","Starting scanner with heal manager...");
-                let scanner = Scanner::new(Some(ScannerConfig::default()), Some(heal_manager));
+                let scanner = Arc::new(Scanner::new(Some(ScannerConfig::default()), Some(heal_manager)));
                 scanner.start().await?;
+                set_global_scanner(scanner)?;
             } else {
                 info!(target: "nebulafx::main::run","Scanner disabled, but heal manager is initialized and available");
             }
         } else if enable_scanner {
             info!("Starting scanner without heal manager...");
-            let scanner = Scanner::new(Some(ScannerConfig::default()), None);
+            let scanner = Arc::new(Scanner::new(Some(ScannerConfig::default()), None));
             scanner.start().await?;
+            set_global_scanner(scanner)?;
         }
     } else {
         info!(target: "nebulafx::main::run","Both scanner and heal are disabled, skipping AHM service initialization");
     }
+    step.done();
+    timeline.finish(true);
+
+    let startup_elapsed = startup_begin.elapsed();
+    info!(
+        target: "nebulafx::main::run",
+        startup_ms = startup_elapsed.as_millis() as u64,
+        startup = %humanize_duration(startup_elapsed),
+        role = role.as_str(),
+        erasure = %erasure_summary(nebulafx_ecstore::global::get_global_erasure_layout()),
+        "NebulaFX started in {} as {}", humanize_duration(startup_elapsed), role.as_str()
+    );
 
-    // Perform hibernation for 1 second
-    tokio::time::sleep(SHUTDOWN_TIMEOUT).await;
+    // Optional hibernation before listening for shutdown (default: DEFAULT_STARTUP_DELAY)
+    hibernate(startup_delay(server_config)).await;
+    // Shutdown stops the background services started above
+    let background_services = enable_scanner || enable_heal;
     // listen to the shutdown signal
-    match wait_for_shutdown().await {
+    let signal = wait_for_shutdown().await;
+    record_shutdown_reason(&signal, state_dir);
+    match signal {
         #[cfg(unix)]
         ShutdownSignal::CtrlC | ShutdownSignal::Sigint | ShutdownSignal::Sigterm => {
-            handle_shutdown(&state_manager, s3_shutdown_tx, ctx.clone()).await;
+            handle_shutdown(&state_manager, s3_shutdown_tx, console_socket, grace, background_services, ctx.clone()).await;
         }
         #[cfg(not(unix))]
         ShutdownSignal::CtrlC => {
-            handle_shutdown(&state_manager, s3_shutdown_tx, ctx.clone()).await;
+            handle_shutdown(&state_manager, s3_shutdown_tx, console_socket, grace, background_services, ctx.clone()).await;
         }
     }
 
@@ -348,21 +478,84 @@ async fn run(config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// Parse a boolean environment variable with default value
+/// Create or update the root user from `server.root_user`/`server.root_password`
 ///
-/// Returns true if the environment variable is not set or set to true/1/yes/on/enabled,
-/// false if set to false/0/no/off/disabled
-fn parse_bool_env_var(var_name: &str, default: bool) -> bool {
-    env::var(var_name)
-        .unwrap_or_else(|_| default.to_string())
-        .parse::<bool>()
-        .unwrap_or(default)
+/// Runs after `init_iam_sys` so a fresh deployment always has a usable admin identity.
+async fn seed_root_user(config: &Config, pool: &PostgreSQLPool) -> Result<()> {
+    use nebulafx_iam::init::{init_root_user, validate_root_credentials};
+
+    let root_user = config
+        .server
+        .as_ref()
+        .and_then(|s| s.root_user.as_deref())
+        .unwrap_or("nebulafxadmin");
+    let root_password = config
+        .server
+        .as_ref()
+        .and_then(|s| s.root_password.as_deref())
+        .unwrap_or("nebulafxadmin");
+
+    validate_root_credentials(root_user, root_password).map_err(|e| Error::other(format!("Invalid root credentials: {}", e)))?;
+
+    if let Err(e) = init_root_user(pool.inner(), root_user, root_password).await {
+        error!("Failed to initialize root user: {}", e);
+        return Err(Error::other(format!("Root user initialization failed: {}", e)));
+    }
+
+    info!(target: "nebulafx::main::run", "Root user '{}' seeded from config", root_user);
+    Ok(())
+}
+
+/// Data+parity shards per set for each pool, e.g. `12+4, 6+2`
+fn erasure_summary(layout: &[nebulafx_ecstore::ErasureLayout]) -> String {
+    layout
+        .iter()
+        .map(|pool| format!("{}+{}", pool.data, pool.parity))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Resolve the post-startup hibernation delay from `server.startup_delay_ms`
+fn startup_delay(server_config: &config::ServerConfig) -> std::time::Duration {
+    server_config
+        .startup_delay_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DEFAULT_STARTUP_DELAY)
+}
+
+/// Resolve the longest shutdown drain wait from `server.shutdown_grace_period_ms`
+fn shutdown_grace_period(server_config: &config::ServerConfig) -> std::time::Duration {
+    server_config
+        .shutdown_grace_period_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DEFAULT_SHUTDOWN_GRACE_PERIOD)
+}
+
+/// Sleep for `delay`, returning immediately when it is zero
+async fn hibernate(delay: std::time::Duration) {
+    if delay.is_zero() {
+        debug!(target: "nebulafx::main::run", "Startup hibernation disabled");
+        return;
+    }
+    tokio::time::sleep(delay).await;
+}
+
+/// Whether the data scanner and auto heal are enabled
+fn background_services_enabled() -> (bool, bool) {
+    let background = get_config().background.as_ref();
+    (
+        config::SCANNER_TOGGLE.resolve(background.and_then(|b| b.scanner)),
+        config::HEAL_TOGGLE.resolve(background.and_then(|b| b.heal)),
+    )
 }
 
 /// Handles the shutdown process of the server
 async fn handle_shutdown(
     state_manager: &ServiceStateManager,
     s3_shutdown_tx: Option<tokio::sync::broadcast::Sender<()>>,
+    console_socket: Option<ConsoleSocket>,
+    grace: std::time::Duration,
+    background_services: bool,
     ctx: CancellationToken,
 ) {
     ctx.cancel();
@@ -374,58 +567,50 @@ async fn handle_shutdown(
     // update the status to stopping first
     state_manager.update(ServiceState::Stopping);
 
-    // Check environment variables to determine what services need to be stopped
-    let enable_scanner = parse_bool_env_var("NEUBULAFX_ENABLE_SCANNER", true);
-    let enable_heal = parse_bool_env_var("NEUBULAFX_ENABLE_HEAL", true);
-
-    // Stop background services based on what was enabled
-    if enable_scanner || enable_heal {
-        info!(
-            target: "nebulafx::main::handle_shutdown",
-            "Stopping background services (data scanner and auto heal)..."
-        );
-        shutdown_background_services();
-
-        info!(
-            target: "nebulafx::main::handle_shutdown",
-            "Stopping AHM services..."
-        );
-        shutdown_ahm_services();
-    } else {
-        info!(
-            target: "nebulafx::main::handle_shutdown",
-            "Background services were disabled, skipping AHM shutdown"
-        );
-    }
-
-    // Stop the notification system
-    info!(
-        target: "nebulafx::main::handle_shutdown",
-        "Shutting down event notifier system..."
-    );
-    shutdown_event_notifier().await;
-
-    // Stop the audit system
-    info!(
-        target: "nebulafx::main::handle_shutdown",
-        "Stopping audit system..."
-    );
-    match stop_audit_system().await {
-        Ok(_) => info!("Audit system stopped successfully."),
-        Err(e) => error!("Failed to stop audit system: {}", e),
-    }
-
-    info!(
-        target: "nebulafx::main::handle_shutdown",
-        "Server is stopping..."
-    );
-    if let Some(s3_shutdown_tx) = s3_shutdown_tx {
-        let _ = s3_shutdown_tx.send(());
-    }
-    // 已移除：不再需要独立的 Console 服务器关闭逻辑
-
-    // Wait for the worker thread to complete the cleaning work
-    tokio::time::sleep(SHUTDOWN_TIMEOUT).await;
+    // In-flight requests may still emit events and audit entries, so HTTP drains first
+    // and the database closes once nothing else can issue queries
+    let http_state = state_manager.clone();
+    ShutdownPlan::new()
+        .step("http", &[], move || async move {
+            let start = std::time::Instant::now();
+            if let Some(s3_shutdown_tx) = s3_shutdown_tx {
+                let _ = s3_shutdown_tx.send(());
+            }
+            if let Some(console_socket) = console_socket {
+                console_socket.shutdown(grace).await;
+            }
+            // The HTTP worker marks the service stopped once its connections have drained
+            let remaining = grace.saturating_sub(start.elapsed());
+            if !wait_until_stopped(remaining, || http_state.current_state() == ServiceState::Stopped).await {
+                warn!(
+                    target: "nebulafx::main::handle_shutdown",
+                    "HTTP connections still open after the {:?} grace period", grace
+                );
+            }
+        })
+        .step("notifier", &["http"], shutdown_event_notifier)
+        .step("audit", &["notifier"], || async {
+            match stop_audit_system().await {
+                Ok(_) => info!("Audit system stopped successfully."),
+                Err(e) => error!("Failed to stop audit system: {}", e),
+            }
+            // End any console audit tail streams
+            nebulafx_audit::shutdown_audit_tail();
+        })
+        .step("ahm", &["audit"], move || async move {
+            if background_services {
+                shutdown_background_services();
+                shutdown_ahm_services();
+            } else {
+                info!(
+                    target: "nebulafx::main::handle_shutdown",
+                    "Background services were disabled, skipping AHM shutdown"
+                );
+            }
+        })
+        .step("database", &["ahm"], PostgreSQLPool::close_global)
+        .run()
+        .await;
 
     // the last updated status is stopped
     state_manager.update(ServiceState::Stopped);
@@ -482,3 +667,60 @@ async fn add_bucket_notification_configuration(buckets: Vec<String>) {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn server_config_with_delay(startup_delay_ms: Option<u64>) -> config::ServerConfig {
+        config::ServerConfig {
+            startup_delay_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_startup_delay_defaults_to_one_second() {
+        assert_eq!(startup_delay(&server_config_with_delay(None)), DEFAULT_STARTUP_DELAY);
+        assert_eq!(startup_delay(&server_config_with_delay(Some(250))), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_shutdown_grace_period_parsed_separately() {
+        let server: config::ServerConfig =
+            nebulafx_tomlx::load_config_from_str("startup_delay_ms = 0\nshutdown_grace_period_ms = 5000\n").unwrap();
+        assert_eq!(startup_delay(&server), Duration::ZERO);
+        assert_eq!(shutdown_grace_period(&server), Duration::from_secs(5));
+        assert_eq!(shutdown_grace_period(&config::ServerConfig::default()), DEFAULT_SHUTDOWN_GRACE_PERIOD);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_wait_ends_once_http_stops() {
+        let state_manager = ServiceStateManager::new();
+        state_manager.update(ServiceState::Stopping);
+        let worker = state_manager.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            worker.update(ServiceState::Stopped);
+        });
+
+        let start = std::time::Instant::now();
+        assert!(wait_until_stopped(Duration::from_secs(10), || state_manager.current_state() == ServiceState::Stopped).await);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_wait_gives_up_after_grace_period() {
+        let start = std::time::Instant::now();
+        assert!(!wait_until_stopped(Duration::from_millis(100), || false).await);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_zero_startup_delay_skips_hibernation() {
+        let delay = startup_delay(&server_config_with_delay(Some(0)));
+        let start = std::time::Instant::now();
+        hibernate(delay).await;
+        assert!(start.elapsed() < DEFAULT_STARTUP_DELAY);
+    }
+}
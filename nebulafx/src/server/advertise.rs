@@ -0,0 +1,83 @@
+use crate::config::ServerConfig;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+static ADVERTISE_IP: OnceLock<IpAddr> = OnceLock::new();
+
+/// The IP advertised in console URLs, resolved once and then cached
+///
+/// Preference: `server.advertise_ip`, then `server.host` when it is a concrete address,
+/// then the first non-loopback interface address, then loopback.
+pub(crate) fn resolve_advertise_ip(config: Option<&ServerConfig>) -> IpAddr {
+    *ADVERTISE_IP.get_or_init(|| {
+        let advertise_ip = config.and_then(|c| c.advertise_ip.as_deref());
+        let bind_host = config.and_then(|c| c.host.as_deref());
+        let (ip, source) = select_advertise_ip(advertise_ip, bind_host, nebulafx_utils::get_local_ip);
+        info!(target: "nebulafx::main::startup", advertise_ip = %ip, source, "Resolved advertised IP");
+        ip
+    })
+}
+
+/// Pick the advertised IP and name the source it came from
+fn select_advertise_ip(
+    advertise_ip: Option<&str>,
+    bind_host: Option<&str>,
+    interface_ip: impl FnOnce() -> Option<IpAddr>,
+) -> (IpAddr, &'static str) {
+    if let Some(value) = advertise_ip.map(str::trim).filter(|v| !v.is_empty()) {
+        match value.parse::<IpAddr>() {
+            Ok(ip) => return (ip, "advertise_ip"),
+            Err(e) => warn!(target: "nebulafx::main::startup", "Ignoring invalid server.advertise_ip '{}': {}", value, e),
+        }
+    }
+
+    if let Some(ip) = bind_host.and_then(|h| h.trim().parse::<IpAddr>().ok())
+        && !ip.is_unspecified()
+    {
+        return (ip, "bind_address");
+    }
+
+    if let Some(ip) = interface_ip().filter(|ip| !ip.is_loopback() && !ip.is_unspecified()) {
+        return (ip, "interface");
+    }
+
+    (IpAddr::V4(Ipv4Addr::LOCALHOST), "loopback")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_configured_advertise_ip_wins() {
+        let selected = select_advertise_ip(Some("203.0.113.7"), Some("10.0.0.5"), || Some(ip("192.168.1.2")));
+        assert_eq!(selected, (ip("203.0.113.7"), "advertise_ip"));
+    }
+
+    #[test]
+    fn test_concrete_bind_address_before_interface() {
+        let selected = select_advertise_ip(None, Some("10.0.0.5"), || Some(ip("192.168.1.2")));
+        assert_eq!(selected, (ip("10.0.0.5"), "bind_address"));
+
+        // An invalid advertise_ip falls through to the next source
+        let selected = select_advertise_ip(Some("not-an-ip"), Some("10.0.0.5"), || None);
+        assert_eq!(selected, (ip("10.0.0.5"), "bind_address"));
+    }
+
+    #[test]
+    fn test_wildcard_bind_uses_interface_ip() {
+        let selected = select_advertise_ip(None, Some("0.0.0.0"), || Some(ip("192.168.1.2")));
+        assert_eq!(selected, (ip("192.168.1.2"), "interface"));
+    }
+
+    #[test]
+    fn test_falls_back_to_loopback() {
+        assert_eq!(select_advertise_ip(None, Some("::"), || None), (ip("127.0.0.1"), "loopback"));
+        assert_eq!(select_advertise_ip(None, None, || Some(ip("127.0.0.1"))), (ip("127.0.0.1"), "loopback"));
+    }
+}
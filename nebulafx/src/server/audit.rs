@@ -1,5 +1,6 @@
+use crate::config::{AuditConfig, get_config};
 use nebulafx_audit::system::AuditSystemState;
-use nebulafx_audit::{AuditError, AuditResult, audit_system, init_audit_system};
+use nebulafx_audit::{AuditError, AuditMode, AuditResult, audit_system, init_audit_system};
 use nebulafx_config::DEFAULT_DELIMITER;
 use nebulafx_ecstore::config::GLOBAL_SERVER_CONFIG;
 use tracing::{info, warn};
@@ -56,6 +57,13 @@ pub(crate) async fn start_audit_system() -> AuditResult<()> {
         webhook_config.is_some()
     );
     let system = init_audit_system();
+    let mode = audit_mode(get_config().audit.as_ref());
+    system.set_mode(mode);
+    info!(
+        target: "nebulafx::main::start_audit_system",
+        mode = %mode,
+        "Audit write mode resolved"
+    );
     let state = system.get_state().await;
     if state == AuditSystemState::Running {
         warn!(
@@ -106,3 +114,29 @@ pub(crate) async fn stop_audit_system() -> AuditResult<()> {
         Ok(())
     }
 }
+
+/// Resolve `audit.mode`, falling back to async writes
+fn audit_mode(config: Option<&AuditConfig>) -> AuditMode {
+    match config.and_then(|c| c.mode.as_deref()) {
+        Some(value) => value.parse::<AuditMode>().unwrap_or_else(|e| {
+            warn!(target: "nebulafx::main::start_audit_system", "{}, using {}", e, AuditMode::default());
+            AuditMode::default()
+        }),
+        None => AuditMode::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_mode_from_config() {
+        let config = |mode: &str| AuditConfig {
+            mode: Some(mode.to_string()),
+        };
+        assert_eq!(audit_mode(None), AuditMode::Async);
+        assert_eq!(audit_mode(Some(&config("SYNC"))), AuditMode::Sync);
+        assert_eq!(audit_mode(Some(&config("eventually"))), AuditMode::Async);
+    }
+}
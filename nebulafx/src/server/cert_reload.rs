@@ -0,0 +1,243 @@
+use super::tls_expiry::refresh_cert_expiries;
+use arc_swap::ArcSwap;
+use nebulafx_config::{NEUBULAFX_TLS_CERT, NEUBULAFX_TLS_KEY};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use std::io::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Quiet period after the last change before the certificates are re-read,
+/// so a renewal that rewrites the key and the certificate is picked up as one pair
+const CERT_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// SNI-aware resolver over the TLS directory whose certificates are swapped in place when the files change
+///
+/// Handshakes in flight keep the certificate they resolved; new handshakes see the
+/// reloaded set. A directory that fails to load leaves the previous certificates in use.
+pub(crate) struct ReloadableCertResolver {
+    dir: PathBuf,
+    current: ArcSwap<Box<dyn ResolvesServerCert>>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+fn load_resolver(dir: &Path) -> Result<Box<dyn ResolvesServerCert>> {
+    let cert_key_pairs = nebulafx_utils::load_all_certs_from_directory(&dir.to_string_lossy())?;
+    Ok(Box::new(nebulafx_utils::create_multi_cert_resolver(cert_key_pairs)?))
+}
+
+/// Like [`load_resolver`], but fails when any pair in `dir` does not load
+///
+/// The loader only warns about a pair it cannot read, so without this check a reload
+/// would stop serving that domain instead of keeping its previous certificate.
+fn load_resolver_strict(dir: &Path) -> Result<Box<dyn ResolvesServerCert>> {
+    let cert_key_pairs = nebulafx_utils::load_all_certs_from_directory(&dir.to_string_lossy())?;
+    if let Some(failed) = cert_pair_names(dir)?
+        .into_iter()
+        .find(|name| !cert_key_pairs.contains_key(name))
+    {
+        return Err(Error::other(format!("certificate pair '{failed}' did not load")));
+    }
+    Ok(Box::new(nebulafx_utils::create_multi_cert_resolver(cert_key_pairs)?))
+}
+
+/// Names the loader gives the pairs in `dir`: `default` for the root, else the domain directory
+fn cert_pair_names(dir: &Path) -> Result<Vec<String>> {
+    let has_pair = |dir: &Path| dir.join(NEUBULAFX_TLS_CERT).exists() && dir.join(NEUBULAFX_TLS_KEY).exists();
+    let mut names = Vec::new();
+    if has_pair(dir) {
+        names.push("default".to_string());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && has_pair(&path) {
+            names.extend(path.file_name().and_then(|name| name.to_str()).map(str::to_string));
+        }
+    }
+    Ok(names)
+}
+
+impl std::fmt::Debug for ReloadableCertResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReloadableCertResolver").field("dir", &self.dir).finish()
+    }
+}
+
+impl ReloadableCertResolver {
+    /// Load the certificates under `dir` without watching for changes
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let current = ArcSwap::from_pointee(load_resolver(&dir)?);
+        Ok(Self {
+            dir,
+            current,
+            watcher: Mutex::new(None),
+        })
+    }
+
+    /// Load the certificates under `dir` and reload them whenever a file below it changes
+    ///
+    /// Watching stops once the last reference to the resolver is dropped.
+    pub(crate) fn watch(dir: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        let resolver = Arc::new(Self::new(dir)?);
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                    // The receiver only goes away once the watcher is dropped
+                    let _ = tx.send(());
+                }
+            }
+            Err(e) => warn!("TLS certificate watch error: {}", e),
+        })
+        .map_err(std::io::Error::other)?;
+        watcher
+            .watch(&resolver.dir, RecursiveMode::Recursive)
+            .map_err(std::io::Error::other)?;
+
+        let weak: Weak<Self> = Arc::downgrade(&resolver);
+        std::thread::Builder::new()
+            .name("tls-cert-watch".to_string())
+            .spawn(move || {
+                // Ends when the watcher, and with it the sender, is dropped
+                while rx.recv().is_ok() {
+                    loop {
+                        match rx.recv_timeout(CERT_RELOAD_DEBOUNCE) {
+                            Ok(()) => continue,
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+                    let Some(resolver) = weak.upgrade() else { return };
+                    if let Err(e) = resolver.reload() {
+                        warn!("keeping current TLS certificates, reload of {} failed: {}", resolver.dir.display(), e);
+                    }
+                }
+            })?;
+
+        *resolver.watcher.lock().unwrap() = Some(watcher);
+        Ok(resolver)
+    }
+
+    /// Re-read the directory and swap in its certificates, along with the expiries `/health` reports
+    pub(crate) fn reload(&self) -> Result<()> {
+        self.current.store(Arc::new(load_resolver_strict(&self.dir)?));
+        info!("reloaded TLS certificates from {}", self.dir.display());
+        refresh_cert_expiries(&self.dir);
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        self.current.load().resolve(client_hello)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::pki_types::{CertificateDer, ServerName};
+    use std::time::Instant;
+    use tokio::io::AsyncWriteExt;
+    use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+    /// Write a self-signed `localhost` certificate as the default pair of `dir` and return it
+    fn write_cert(dir: &Path) -> CertificateDer<'static> {
+        let key = rcgen::KeyPair::generate().unwrap();
+        let cert = rcgen::CertificateParams::new(vec!["localhost".to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap();
+        std::fs::write(dir.join(NEUBULAFX_TLS_KEY), key.serialize_pem()).unwrap();
+        std::fs::write(dir.join(NEUBULAFX_TLS_CERT), cert.pem()).unwrap();
+        cert.der().clone()
+    }
+
+    /// Complete a handshake against `acceptor` and return the leaf the server presented
+    async fn served_leaf(acceptor: &TlsAcceptor, trusted: &[CertificateDer<'static>]) -> CertificateDer<'static> {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in trusted {
+            roots.add(cert.clone()).unwrap();
+        }
+        let client = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connector = TlsConnector::from(Arc::new(client));
+
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let server = tokio::spawn({
+            let acceptor = acceptor.clone();
+            async move {
+                let mut stream = acceptor.accept(server_io).await.unwrap();
+                stream.shutdown().await.ok();
+            }
+        });
+        let stream = connector
+            .connect(ServerName::try_from("localhost").unwrap(), client_io)
+            .await
+            .unwrap();
+        let leaf = stream.get_ref().1.peer_certificates().unwrap()[0].clone();
+        drop(stream);
+        server.await.unwrap();
+        leaf
+    }
+
+    #[tokio::test]
+    async fn test_swapped_cert_file_is_served() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = tempfile::tempdir().unwrap();
+        let old = write_cert(dir.path());
+
+        let resolver = ReloadableCertResolver::watch(dir.path()).unwrap();
+        let server = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_cert_resolver(resolver.clone());
+        let acceptor = TlsAcceptor::from(Arc::new(server));
+        assert_eq!(served_leaf(&acceptor, std::slice::from_ref(&old)).await, old);
+
+        let new = write_cert(dir.path());
+        let trusted = [old.clone(), new.clone()];
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while served_leaf(&acceptor, &trusted).await != new {
+            assert!(Instant::now() < deadline, "new certificate was not picked up");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_current_certs() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = tempfile::tempdir().unwrap();
+        write_cert(dir.path());
+        let resolver = ReloadableCertResolver::new(dir.path()).unwrap();
+        let before = Arc::as_ptr(&resolver.current.load_full());
+
+        std::fs::write(dir.path().join(NEUBULAFX_TLS_KEY), "not a key").unwrap();
+        assert!(resolver.reload().is_err());
+        assert_eq!(Arc::as_ptr(&resolver.current.load_full()), before);
+    }
+
+    #[test]
+    fn test_reload_with_a_broken_domain_keeps_current_certs() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = tempfile::tempdir().unwrap();
+        write_cert(dir.path());
+        let domain = dir.path().join("s3.example.com");
+        std::fs::create_dir(&domain).unwrap();
+        write_cert(&domain);
+        let resolver = ReloadableCertResolver::new(dir.path()).unwrap();
+        let before = Arc::as_ptr(&resolver.current.load_full());
+
+        // The root pair still loads, but the domain would be dropped
+        std::fs::write(domain.join(NEUBULAFX_TLS_KEY), "not a key").unwrap();
+        let err = resolver.reload().unwrap_err();
+        assert!(err.to_string().contains("s3.example.com"), "{err}");
+        assert_eq!(Arc::as_ptr(&resolver.current.load_full()), before);
+    }
+}
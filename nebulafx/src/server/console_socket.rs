@@ -0,0 +1,312 @@
+use crate::config::ConsoleConfig;
+use std::io::{Error, Result};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::broadcast;
+#[cfg(unix)]
+use tokio::task::JoinHandle;
+#[cfg(unix)]
+use tracing::{error, info, warn};
+
+/// Permissions of the console socket unless `console.unix_socket_mode` is set
+#[cfg(unix)]
+const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+/// Parse an octal mode such as "0600" or "0o660"
+#[cfg(unix)]
+fn socket_mode(config: &ConsoleConfig) -> Result<u32> {
+    let Some(mode) = config.unix_socket_mode.as_deref() else {
+        return Ok(DEFAULT_SOCKET_MODE);
+    };
+    let digits = mode.trim().trim_start_matches("0o");
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if mode <= 0o777 => Ok(mode),
+        _ => Err(Error::other(format!(
+            "invalid console.unix_socket_mode '{mode}', expected an octal mode such as \"0600\""
+        ))),
+    }
+}
+
+/// Console API served on a Unix domain socket instead of the TCP port
+#[cfg(unix)]
+pub(crate) struct ConsoleSocket {
+    path: PathBuf,
+    in_flight: Arc<AtomicUsize>,
+    task: JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl ConsoleSocket {
+    /// Wait up to `grace` for requests in flight when the shutdown signal was sent, then
+    /// stop serving and remove the socket file
+    pub(crate) async fn shutdown(mut self, grace: std::time::Duration) {
+        if tokio::time::timeout(grace, &mut self.task).await.is_err() {
+            warn!(
+                target: "nebulafx::console::socket",
+                abandoned = self.in_flight.load(Ordering::SeqCst),
+                "Console socket requests still running after {:?}, closing anyway",
+                grace
+            );
+            self.task.abort();
+        }
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => info!(target: "nebulafx::console::socket", path = %self.path.display(), "Removed console socket"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!(target: "nebulafx::console::socket", path = %self.path.display(), "Failed to remove console socket: {}", e)
+            }
+        }
+    }
+}
+
+/// Serve the console on `console.unix_socket` if it is configured, until `shutdown` fires
+#[cfg(unix)]
+pub(crate) async fn start_console_socket(
+    config: Option<&ConsoleConfig>,
+    shutdown: broadcast::Receiver<()>,
+) -> Result<Option<ConsoleSocket>> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let Some(path) = config.unix_socket.as_deref() else {
+        return Ok(None);
+    };
+    let mode = socket_mode(config)?;
+    let socket = bind_console_socket(Path::new(path), mode, crate::admin::console::make_console_server(), shutdown)?;
+    info!(target: "nebulafx::console::startup", path, mode = %format!("{mode:o}"), "Console API listening on Unix socket");
+    Ok(Some(socket))
+}
+
+/// Never constructed: Unix sockets are unavailable on this platform
+#[cfg(not(unix))]
+pub(crate) enum ConsoleSocket {}
+
+#[cfg(not(unix))]
+impl ConsoleSocket {
+    pub(crate) async fn shutdown(self, _grace: std::time::Duration) {
+        match self {}
+    }
+}
+
+/// Unix sockets are unavailable on this platform, so `console.unix_socket` is rejected
+#[cfg(not(unix))]
+pub(crate) async fn start_console_socket(
+    config: Option<&ConsoleConfig>,
+    _shutdown: broadcast::Receiver<()>,
+) -> Result<Option<ConsoleSocket>> {
+    match config.and_then(|c| c.unix_socket.as_deref()) {
+        Some(_) => Err(Error::other("console.unix_socket is only supported on Unix platforms")),
+        None => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+fn bind_console_socket(
+    path: &Path,
+    mode: u32,
+    router: axum::Router,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> Result<ConsoleSocket> {
+    use std::os::unix::fs::PermissionsExt;
+
+    remove_stale_socket(path)?;
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let router = router.layer(axum::middleware::from_fn_with_state(in_flight.clone(), count_in_flight));
+    let draining = Arc::new(AtomicUsize::new(0));
+    let task = tokio::spawn({
+        let in_flight = in_flight.clone();
+        async move {
+            let shutdown = {
+                let draining = draining.clone();
+                async move {
+                    // A dropped sender also means the server is going away
+                    let _ = shutdown_rx.recv().await;
+                    draining.store(in_flight.load(Ordering::SeqCst), Ordering::SeqCst);
+                }
+            };
+            match axum::serve(listener, router).with_graceful_shutdown(shutdown).await {
+                Ok(()) => info!(
+                    target: "nebulafx::console::socket",
+                    drained = draining.load(Ordering::SeqCst),
+                    "Console socket drained in-flight requests"
+                ),
+                Err(e) => error!(target: "nebulafx::console::socket", "Console socket server failed: {}", e),
+            }
+        }
+    });
+
+    Ok(ConsoleSocket {
+        path: path.to_path_buf(),
+        in_flight,
+        task,
+    })
+}
+
+/// Track requests being handled, so shutdown can report how many it waited for
+#[cfg(unix)]
+async fn count_in_flight(
+    axum::extract::State(in_flight): axum::extract::State<Arc<AtomicUsize>>,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    struct Guard(Arc<AtomicUsize>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    in_flight.fetch_add(1, Ordering::SeqCst);
+    let _guard = Guard(in_flight);
+    next.run(req).await
+}
+
+/// Remove a socket file left behind by a previous run, refusing to touch live sockets or other files
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(Error::other(format!(
+            "console.unix_socket path {} exists and is not a socket",
+            path.display()
+        )));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(Error::other(format!("console socket {} is already in use", path.display())));
+    }
+
+    warn!(target: "nebulafx::console::startup", path = %path.display(), "Removing stale console socket");
+    std::fs::remove_file(path)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("nebulafx-console-{}.sock", uuid::Uuid::new_v4()))
+    }
+
+    async fn get_over_socket(path: &Path, uri: &str) -> String {
+        let mut stream = tokio::net::UnixStream::connect(path).await.unwrap();
+        let request = format!("GET {uri} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_console_served_over_unix_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = socket_path();
+        // A socket file left by a crashed process must not block startup
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let router = axum::Router::new().route("/nebulafx/console/health", get(|| async { "ok" }));
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let socket = bind_console_socket(&path, 0o600, router, shutdown_rx).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        let response = get_over_socket(&path, "/nebulafx/console/health").await;
+        assert!(response.starts_with("HTTP/1.1 200"), "unexpected response: {response}");
+        assert!(response.ends_with("ok"));
+
+        shutdown_tx.send(()).unwrap();
+        socket.shutdown(std::time::Duration::from_secs(5)).await;
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_protected_console_route_requires_signature_over_socket() {
+        use tower::ServiceExt;
+
+        crate::config::init_test_config();
+        let router = crate::admin::console::make_console_server();
+        let uri = "/nebulafx/console/debug/errors";
+
+        let req = axum::http::Request::builder()
+            .uri(uri)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+
+        let path = socket_path();
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let socket = bind_console_socket(&path, 0o600, router, shutdown_rx).unwrap();
+
+        // Being able to connect to the socket is not a substitute for signing the request
+        let response = get_over_socket(&path, uri).await;
+        assert!(response.starts_with("HTTP/1.1 401"), "unexpected response: {response}");
+        assert!(response.ends_with("Signature is required"), "unexpected response: {response}");
+
+        shutdown_tx.send(()).unwrap();
+        socket.shutdown(std::time::Duration::from_secs(5)).await;
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_request_completes_after_shutdown_signal() {
+        let path = socket_path();
+        let started = Arc::new(tokio::sync::Notify::new());
+        let router = axum::Router::new().route(
+            "/nebulafx/console/slow",
+            get({
+                let started = started.clone();
+                || async move {
+                    started.notify_one();
+                    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                    "done"
+                }
+            }),
+        );
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let socket = bind_console_socket(&path, 0o600, router, shutdown_rx).unwrap();
+
+        let request = tokio::spawn({
+            let path = path.clone();
+            async move { get_over_socket(&path, "/nebulafx/console/slow").await }
+        });
+        started.notified().await;
+        assert_eq!(socket.in_flight.load(Ordering::SeqCst), 1);
+        shutdown_tx.send(()).unwrap();
+        socket.shutdown(std::time::Duration::from_secs(5)).await;
+
+        let response = request.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "unexpected response: {response}");
+        assert!(response.ends_with("done"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_socket_mode_parsing() {
+        let config = |mode: Option<&str>| ConsoleConfig {
+            access_log_level: None,
+            access_log_exclude_paths: None,
+            unix_socket: None,
+            unix_socket_mode: mode.map(str::to_string),
+            auth_required_during_init: None,
+        };
+        assert_eq!(socket_mode(&config(None)).unwrap(), DEFAULT_SOCKET_MODE);
+        assert_eq!(socket_mode(&config(Some("0600"))).unwrap(), 0o600);
+        assert_eq!(socket_mode(&config(Some("0o640"))).unwrap(), 0o640);
+        assert!(socket_mode(&config(Some("rw-rw----"))).is_err());
+        assert!(socket_mode(&config(Some("7777"))).is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use crate::config::{NotifyConfig, get_config};
 use nebulafx_config::DEFAULT_DELIMITER;
 use nebulafx_ecstore::config::GLOBAL_SERVER_CONFIG;
+use nebulafx_notify::{OverflowPolicy, QueueLimits};
 use tracing::{error, info, instrument, warn};
 
 /// Shuts down the event notifier system gracefully
@@ -66,7 +68,14 @@ pub(crate) async fn init_event_notifier() {
 
     // 3. Initialize the notification system asynchronously with a global configuration
     // Use direct await for better error handling and faster initialization
-    if let Err(e) = nebulafx_notify::initialize(server_config).await {
+    let queue_limits = queue_limits(get_config().notify.as_ref());
+    info!(
+        target: "nebulafx::main::init_event_notifier",
+        queue_limit = queue_limits.capacity,
+        overflow_policy = %queue_limits.policy,
+        "Event notifier queue limits resolved"
+    );
+    if let Err(e) = nebulafx_notify::initialize(server_config, queue_limits).await {
         error!("Failed to initialize event notifier system: {}", e);
     } else {
         info!(
@@ -75,3 +84,47 @@ pub(crate) async fn init_event_notifier() {
         );
     }
 }
+
+/// Resolve per-target queue limits from `[notify]`, falling back to the defaults
+fn queue_limits(config: Option<&NotifyConfig>) -> QueueLimits {
+    let defaults = QueueLimits::default();
+    let policy = match config.and_then(|c| c.overflow_policy.as_deref()) {
+        Some(value) => value.parse::<OverflowPolicy>().unwrap_or_else(|e| {
+            warn!(target: "nebulafx::main::init_event_notifier", "{}, using {}", e, defaults.policy);
+            defaults.policy
+        }),
+        None => defaults.policy,
+    };
+
+    QueueLimits {
+        capacity: config
+            .and_then(|c| c.queue_limit)
+            .filter(|&limit| limit > 0)
+            .unwrap_or(defaults.capacity),
+        policy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_limits_from_config() {
+        assert_eq!(queue_limits(None), QueueLimits::default());
+
+        let config = NotifyConfig {
+            queue_limit: Some(500),
+            overflow_policy: Some("block".to_string()),
+        };
+        let limits = queue_limits(Some(&config));
+        assert_eq!(limits.capacity, 500);
+        assert_eq!(limits.policy, OverflowPolicy::Block);
+
+        let invalid = NotifyConfig {
+            queue_limit: Some(0),
+            overflow_policy: Some("spill".to_string()),
+        };
+        assert_eq!(queue_limits(Some(&invalid)), QueueLimits::default());
+    }
+}
@@ -1,11 +1,22 @@
 use crate::admin;
 use crate::auth::IAMAuth;
 use crate::config;
-use crate::server::{ServiceState, ServiceStateManager, hybrid::hybrid, layer::RedirectLayer};
+use crate::config::TlsConfig;
+use crate::server::{
+    ServiceState, ServiceStateManager,
+    advertise::resolve_advertise_ip,
+    cert_reload::ReloadableCertResolver,
+    hybrid::hybrid,
+    layer::{MaxObjectSizeLayer, MinFreeSpaceLayer, ReadOnlyBucketsLayer, RedirectLayer},
+    preflight_tls_certs,
+};
 use crate::storage;
+use crate::storage::free_space::{FreeSpaceFloor, FreeSpaceGuard, init_free_space_guard};
 use crate::storage::tonic_service::make_server;
+use axum::extract::ConnectInfo;
 use bytes::Bytes;
 use http::{HeaderMap, Request as HttpRequest, Response};
+use hyper::body::Incoming;
 use hyper_util::{
     rt::{TokioExecutor, TokioIo},
     server::conn::auto::Builder as ConnBuilder,
@@ -16,7 +27,8 @@ use metrics::{counter, histogram};
 use nebulafx_config::{DEFAULT_ACCESS_KEY, DEFAULT_SECRET_KEY, MI_B, NEUBULAFX_TLS_CERT, NEUBULAFX_TLS_KEY};
 use nebulafx_protos::proto_gen::node_service::node_service_server::NodeServiceServer;
 use nebulafx_utils::net::parse_and_resolve_address;
-use rustls::ServerConfig;
+use rustls::server::WantsServerCert;
+use rustls::{ConfigBuilder, ServerConfig};
 use s3s::{host::MultiDomain, service::S3Service, service::S3ServiceBuilder};
 use socket2::SockRef;
 use std::io::{Error, Result};
@@ -93,9 +105,13 @@ fn get_cors_allowed_origins() -> String {
         .unwrap_or(nebulafx_config::DEFAULT_CONSOLE_CORS_ALLOWED_ORIGINS.to_string())
 }
 
+/// Serve the S3 and console APIs until the returned sender fires
+///
+/// Connections still open when it does are given up to `grace` to finish.
 pub async fn start_http_server(
     opt: &config::Opt,
     worker_state_manager: ServiceStateManager,
+    grace: Duration,
 ) -> Result<tokio::sync::broadcast::Sender<()>> {
     let server_addr = parse_and_resolve_address(opt.address.as_str()).map_err(Error::other)?;
     let server_port = server_addr.port();
@@ -131,13 +147,9 @@ pub async fn start_http_server(
 
     // Obtain the listener address
     let local_addr: SocketAddr = listener.local_addr()?;
-    let local_ip = match nebulafx_utils::get_local_ip() {
-        Some(ip) => ip,
-        None => {
-            warn!("Unable to obtain local IP address, using fallback IP: {}", local_addr.ip());
-            local_addr.ip()
-        }
-    };
+    debug!("Listening on {}", local_addr);
+    let local_ip = resolve_advertise_ip(config::get_config().server.as_ref());
+    preflight_tls_certs(opt.tls_path.as_deref().unwrap_or_default(), config::get_config().tls.as_ref())?;
     let tls_acceptor = setup_tls_acceptor(opt.tls_path.as_deref().unwrap_or_default()).await?;
     let tls_enabled = tls_acceptor.is_some();
     let protocol = if tls_enabled { "https" } else { "http" };
@@ -177,7 +189,12 @@ pub async fn start_http_server(
         b.set_auth(IAMAuth::new(access_key, secret_key));
         b.set_access(store.clone());
         // Console API 端点始终启用（通过主服务器提供）
-        b.set_route(admin::make_admin_route(true)?);
+        let console_on_tcp = config::get_config()
+            .console
+            .as_ref()
+            .and_then(|c| c.unix_socket.as_ref())
+            .is_none();
+        b.set_route(admin::make_admin_route(console_on_tcp)?);
 
         if !opt.server_domains.is_empty() {
             MultiDomain::new(&opt.server_domains).map_err(Error::other)?; // validate domains
@@ -212,6 +229,30 @@ pub async fn start_http_server(
         Some(cors_allowed_origins)
     };
 
+    // Optional cap on S3 upload sizes
+    let max_object_size = match config::get_config().server.as_ref() {
+        Some(server) => server.max_object_size_bytes().map_err(Error::other)?,
+        None => None,
+    };
+    if let Some(limit) = max_object_size {
+        info!("S3 uploads limited to {}", nebulafx_utils::humanize_bytes(limit));
+    }
+
+    // Optional free-space floor for S3 writes; drive usage is polled once the local disks are known
+    let free_space_guard = FreeSpaceFloor::from_config(config::get_config().storage.as_ref())
+        .map_err(Error::other)?
+        .map(init_free_space_guard);
+
+    // Buckets that only accept reads through the S3 API
+    let read_only_buckets = match config::get_config().storage.as_ref() {
+        Some(storage) => storage.read_only_bucket_set().map_err(Error::other)?,
+        None => Default::default(),
+    };
+    let read_only_layer = (!read_only_buckets.is_empty()).then(|| {
+        info!("Read-only buckets: {:?}", read_only_buckets);
+        ReadOnlyBucketsLayer::new(read_only_buckets, &opt.server_domains)
+    });
+
     // Console API 端点始终启用
     let is_console = true;
     tokio::spawn(async move {
@@ -312,6 +353,9 @@ pub async fn start_http_server(
                 graceful.clone(),
                 cors_layer.clone(),
                 is_console,
+                max_object_size,
+                free_space_guard.clone(),
+                read_only_layer.clone(),
             );
         }
 
@@ -322,14 +366,14 @@ pub async fn start_http_server(
                     () = g.shutdown() => {
                         debug!("Gracefully shutdown!");
                     },
-                    () = tokio::time::sleep(Duration::from_secs(10)) => {
-                        debug!("Waited 10 seconds for graceful shutdown, aborting...");
+                    () = tokio::time::sleep(grace) => {
+                        debug!("Waited {:?} for graceful shutdown, aborting...", grace);
                     }
                 }
             }
             Err(arc_graceful) => {
                 error!("Cannot perform graceful shutdown, other references exist err: {:?}", arc_graceful);
-                tokio::time::sleep(Duration::from_secs(10)).await;
+                tokio::time::sleep(grace).await;
                 debug!("Timeout reached, forcing shutdown");
             }
         }
@@ -351,19 +395,17 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
 
     // Make sure to use a modern encryption suite
     let _ = rustls::crypto::ring::default_provider().install_default();
+    let tls_config = config::get_config().tls.as_ref();
 
     // 1. Attempt to load all certificates in the directory (multi-certificate support, for SNI)
-    if let Ok(cert_key_pairs) = nebulafx_utils::load_all_certs_from_directory(tls_path) {
-        if !cert_key_pairs.is_empty() {
-            debug!("Found {} certificates, creating SNI-aware multi-cert resolver", cert_key_pairs.len());
-
-            // Create an SNI-enabled certificate resolver
-            let resolver = nebulafx_utils::create_multi_cert_resolver(cert_key_pairs)?;
+    //    The resolver watches the directory, so renewed certificates are served to the S3 API
+    //    and the console without a restart
+    match ReloadableCertResolver::watch(tls_path) {
+        Ok(resolver) => {
+            debug!("Found certificates, creating SNI-aware reloadable cert resolver");
 
             // Configure the server to enable SNI support
-            let mut server_config = ServerConfig::builder()
-                .with_no_client_auth()
-                .with_cert_resolver(Arc::new(resolver));
+            let mut server_config = tls_server_builder(tls_config)?.with_cert_resolver(resolver);
 
             // Configure ALPN protocol priority
             server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec(), b"http/1.0".to_vec()];
@@ -375,6 +417,7 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
 
             return Ok(Some(TlsAcceptor::from(Arc::new(server_config))));
         }
+        Err(e) => debug!("No certificates loaded for SNI from {}: {}", tls_path, e),
     }
 
     // 2. Revert to the traditional single-certificate mode
@@ -385,8 +428,7 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
         let certs = nebulafx_utils::load_certs(&cert_path).map_err(|e| nebulafx_utils::certs_error(e.to_string()))?;
         let key = nebulafx_utils::load_private_key(&key_path).map_err(|e| nebulafx_utils::certs_error(e.to_string()))?;
 
-        let mut server_config = ServerConfig::builder()
-            .with_no_client_auth()
+        let mut server_config = tls_server_builder(tls_config)?
             .with_single_cert(certs, key)
             .map_err(|e| nebulafx_utils::certs_error(e.to_string()))?;
 
@@ -405,6 +447,18 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
     Ok(None)
 }
 
+/// Start a rustls server config limited to the cipher suites and key-exchange groups in `[tls]`
+pub(crate) fn tls_server_builder(tls: Option<&TlsConfig>) -> Result<ConfigBuilder<ServerConfig, WantsServerCert>> {
+    let provider = match tls {
+        Some(tls) => tls.crypto_provider().map_err(Error::other)?,
+        None => rustls::crypto::ring::default_provider(),
+    };
+    Ok(ServerConfig::builder_with_provider(Arc::new(provider))
+        .with_safe_default_protocol_versions()
+        .map_err(Error::other)?
+        .with_no_client_auth())
+}
+
 /// Process a single incoming TCP connection.
 ///
 /// This function is executed in a new Tokio task and it will:
@@ -421,14 +475,25 @@ fn process_connection(
     graceful: Arc<GracefulShutdown>,
     cors_layer: CorsLayer,
     is_console: bool,
+    max_object_size: Option<u64>,
+    free_space_guard: Option<Arc<FreeSpaceGuard>>,
+    read_only_layer: Option<ReadOnlyBucketsLayer>,
 ) {
     tokio::spawn(async move {
         // Build services inside each connected task to avoid passing complex service types across tasks,
         // It also ensures that each connection has an independent service instance.
         let rpc_service = NodeServiceServer::with_interceptor(make_server(), check_auth);
         let service = hybrid(s3_service, rpc_service);
+        let peer_addr = socket.peer_addr().ok();
 
         let hybrid_service = ServiceBuilder::new()
+            // Exposes the peer to handlers, e.g. the console rate limiter
+            .map_request(move |mut request: HttpRequest<Incoming>| {
+                if let Some(peer_addr) = peer_addr {
+                    request.extensions_mut().insert(ConnectInfo(peer_addr));
+                }
+                request
+            })
             .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
             .layer(CatchPanicLayer::new())
             .layer(
@@ -489,6 +554,9 @@ fn process_connection(
             // Compress responses
             .layer(CompressionLayer::new())
             .option_layer(if is_console { Some(RedirectLayer) } else { None })
+            .option_layer(max_object_size.map(MaxObjectSizeLayer::new))
+            .option_layer(free_space_guard.map(MinFreeSpaceLayer::new))
+            .option_layer(read_only_layer)
             .service(service);
 
         let hybrid_service = TowerToHyperService::new(hybrid_service);
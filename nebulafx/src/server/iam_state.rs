@@ -0,0 +1,144 @@
+use crate::config::IamInitMode;
+use atomic_enum::atomic_enum;
+use std::future::Future;
+use std::io::Result;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use tracing::{error, info};
+
+/// Progress of IAM initialization, tracked separately from the service state
+#[atomic_enum]
+#[derive(PartialEq)]
+pub(crate) enum IamState {
+    /// No database is configured, so IAM is never initialized
+    Disabled,
+    Initializing,
+    Ready,
+    Failed,
+}
+
+impl IamState {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            IamState::Disabled => "disabled",
+            IamState::Initializing => "initializing",
+            IamState::Ready => "ready",
+            IamState::Failed => "failed",
+        }
+    }
+}
+
+/// Shared handle to the IAM initialization state
+#[derive(Clone)]
+pub(crate) struct IamReadiness {
+    state: Arc<AtomicIamState>,
+}
+
+impl IamReadiness {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Arc::new(AtomicIamState::new(IamState::Disabled)),
+        }
+    }
+
+    pub(crate) fn update(&self, state: IamState) {
+        self.state.store(state, Ordering::SeqCst);
+    }
+
+    pub(crate) fn current_state(&self) -> IamState {
+        self.state.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for IamReadiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static IAM_READINESS: OnceLock<IamReadiness> = OnceLock::new();
+
+/// Process-wide IAM readiness reported by the console health check
+pub(crate) fn iam_readiness() -> &'static IamReadiness {
+    IAM_READINESS.get_or_init(IamReadiness::new)
+}
+
+/// Run IAM initialization eagerly or in the background
+///
+/// In eager mode this waits for `init` and returns its error. In lazy mode it returns
+/// immediately; IAM-dependent requests fail until `readiness` reports [`IamState::Ready`].
+pub(crate) async fn start_iam<F>(mode: IamInitMode, readiness: &IamReadiness, init: F) -> Result<()>
+where
+    F: Future<Output = Result<()>> + Send + 'static,
+{
+    readiness.update(IamState::Initializing);
+    match mode {
+        IamInitMode::Eager => finish_iam(readiness, init.await),
+        IamInitMode::Lazy => {
+            info!(target: "nebulafx::main::run", "Initializing IAM in the background");
+            let readiness = readiness.clone();
+            tokio::spawn(async move {
+                let _ = finish_iam(&readiness, init.await);
+            });
+            Ok(())
+        }
+    }
+}
+
+fn finish_iam(readiness: &IamReadiness, result: Result<()>) -> Result<()> {
+    match &result {
+        Ok(()) => {
+            readiness.update(IamState::Ready);
+            info!(target: "nebulafx::main::run", "IAM system initialized");
+        }
+        Err(e) => {
+            readiness.update(IamState::Failed);
+            error!(target: "nebulafx::main::run", "IAM initialization failed: {}", e);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Error;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn test_lazy_mode_is_partially_ready_before_iam_finishes() {
+        let readiness = IamReadiness::new();
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+
+        start_iam(IamInitMode::Lazy, &readiness, async move {
+            let _ = release_rx.await;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        // Boot continues while IAM is still loading
+        assert_eq!(readiness.current_state(), IamState::Initializing);
+
+        release_tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while readiness.current_state() != IamState::Ready {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("IAM did not become ready");
+    }
+
+    #[tokio::test]
+    async fn test_eager_mode_waits_and_propagates_errors() {
+        let readiness = IamReadiness::new();
+        start_iam(IamInitMode::Eager, &readiness, async { Ok(()) }).await.unwrap();
+        assert_eq!(readiness.current_state(), IamState::Ready);
+
+        let failed = start_iam(IamInitMode::Eager, &readiness, async { Err(Error::other("db down")) }).await;
+        assert!(failed.is_err());
+        assert_eq!(readiness.current_state(), IamState::Failed);
+    }
+}
@@ -1,11 +1,19 @@
+use crate::admin::is_internal_path;
 use crate::server::hybrid::HybridBody;
-use http::{Request as HttpRequest, Response, StatusCode};
+use crate::storage::free_space::FreeSpaceGuard;
+use bytes::Bytes;
+use futures::StreamExt;
+use http::{HeaderMap, Request as HttpRequest, Response, StatusCode, Uri};
 use hyper::body::Incoming;
+use s3s::dto::StreamingBlob;
+use s3s::{S3Error, S3Result, StdError, s3_error};
+use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tower::{Layer, Service};
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Redirect layer that redirects browser requests to the console
 #[derive(Clone)]
@@ -75,3 +83,575 @@ where
         Box::pin(async move { inner.call(req).await.map_err(Into::into) })
     }
 }
+
+/// Header carrying the payload size of `aws-chunked` uploads
+const AMZ_DECODED_CONTENT_LENGTH: &str = "x-amz-decoded-content-length";
+
+/// Layer that rejects S3 PUT requests whose declared size exceeds `server.max_object_size`
+///
+/// The limit is also handed to the S3 handlers as an [`ObjectSizeLimit`] extension, which
+/// enforces it on bodies without a declared length and on completed multipart uploads.
+#[derive(Clone)]
+pub struct MaxObjectSizeLayer {
+    limit: u64,
+}
+
+impl MaxObjectSizeLayer {
+    pub fn new(limit: u64) -> Self {
+        Self { limit }
+    }
+}
+
+impl<S> Layer<S> for MaxObjectSizeLayer {
+    type Service = MaxObjectSizeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MaxObjectSizeService {
+            inner,
+            limit: self.limit,
+        }
+    }
+}
+
+/// Service implementation for the object size limit
+#[derive(Clone)]
+pub struct MaxObjectSizeService<S> {
+    inner: S,
+    limit: u64,
+}
+
+/// `server.max_object_size`, carried in the request extensions to the S3 handlers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ObjectSizeLimit(pub(crate) u64);
+
+/// Error reading an upload body through [`ObjectSizeLimit::limit_body`]
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum LimitedBodyError {
+    #[error("upload exceeds server.max_object_size of {0} bytes")]
+    TooLarge(u64),
+    #[error("{0}")]
+    Read(StdError),
+}
+
+impl ObjectSizeLimit {
+    /// Reject an object of `size` bytes with `EntityTooLarge` if it is over the limit
+    pub(crate) fn check(self, size: u64) -> S3Result<()> {
+        if size > self.0 {
+            warn!(size, limit = self.0, "Rejecting upload larger than server.max_object_size");
+            return Err(s3_error!(EntityTooLarge));
+        }
+        Ok(())
+    }
+
+    /// Reject a multipart upload whose parts add up to more than the limit
+    pub(crate) fn check_parts(self, part_sizes: impl IntoIterator<Item = u64>) -> S3Result<()> {
+        self.check(part_sizes.into_iter().fold(0, u64::saturating_add))
+    }
+
+    /// Count the bytes read from `body`, failing with [`LimitedBodyError::TooLarge`] once they pass the limit
+    pub(crate) fn limit_body(self, body: StreamingBlob) -> StreamingBlob {
+        let limit = self.0;
+        let mut read = 0u64;
+        StreamingBlob::wrap(body.map(move |chunk| -> Result<Bytes, LimitedBodyError> {
+            let chunk: Bytes = chunk.map_err(LimitedBodyError::Read)?;
+            read = read.saturating_add(chunk.len() as u64);
+            if read > limit {
+                return Err(LimitedBodyError::TooLarge(limit));
+            }
+            Ok(chunk)
+        }))
+    }
+}
+
+/// Map an error read from an upload body to `EntityTooLarge` if the body passed its [`ObjectSizeLimit`]
+pub(crate) fn object_too_large(err: &StdError) -> Option<S3Error> {
+    match err.downcast_ref::<LimitedBodyError>() {
+        Some(LimitedBodyError::TooLarge(_)) => Some(s3_error!(EntityTooLarge)),
+        _ => None,
+    }
+}
+
+/// Declared payload size of a request, preferring the decoded length of chunked uploads
+fn declared_object_size(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(AMZ_DECODED_CONTENT_LENGTH)
+        .or_else(|| headers.get(http::header::CONTENT_LENGTH))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+fn entity_too_large_body(resource: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Error><Code>EntityTooLarge</Code>\
+         <Message>Your proposed upload exceeds the maximum allowed object size.</Message>\
+         <Resource>{resource}</Resource></Error>"
+    )
+}
+
+impl<S, ReqBody, RestBody, GrpcBody> Service<HttpRequest<ReqBody>> for MaxObjectSizeService<S>
+where
+    S: Service<HttpRequest<ReqBody>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ReqBody: Send + 'static,
+    RestBody: From<String> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<ReqBody>) -> Self::Future {
+        let path = req.uri().path();
+        if is_internal_path(path) {
+            let mut inner = self.inner.clone();
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        }
+
+        if req.method() == http::Method::PUT {
+            if let Some(size) = declared_object_size(req.headers()).filter(|size| *size > self.limit) {
+                warn!(path, size, limit = self.limit, "Rejecting upload larger than server.max_object_size");
+
+                let response = Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .header(http::header::CONTENT_TYPE, "application/xml")
+                    .body(HybridBody::Rest {
+                        rest_body: RestBody::from(entity_too_large_body(path)),
+                    })
+                    .expect("failed to build EntityTooLarge response");
+
+                return Box::pin(async move { Ok(response) });
+            }
+        }
+
+        req.extensions_mut().insert(ObjectSizeLimit(self.limit));
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await.map_err(Into::into) })
+    }
+}
+
+/// Layer that refuses S3 writes while a local drive is below `storage.min_free_bytes`/`min_free_percent`
+#[derive(Clone)]
+pub struct MinFreeSpaceLayer {
+    guard: Arc<FreeSpaceGuard>,
+}
+
+impl MinFreeSpaceLayer {
+    pub(crate) fn new(guard: Arc<FreeSpaceGuard>) -> Self {
+        Self { guard }
+    }
+}
+
+impl<S> Layer<S> for MinFreeSpaceLayer {
+    type Service = MinFreeSpaceService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MinFreeSpaceService {
+            inner,
+            guard: self.guard.clone(),
+        }
+    }
+}
+
+/// Service implementation for the free-space floor
+#[derive(Clone)]
+pub struct MinFreeSpaceService<S> {
+    inner: S,
+    guard: Arc<FreeSpaceGuard>,
+}
+
+/// Whether an S3 request stores data; multi-object delete and select are POSTs that do not
+fn is_s3_write(method: &http::Method, uri: &Uri) -> bool {
+    if is_internal_path(uri.path()) {
+        return false;
+    }
+    match *method {
+        http::Method::PUT => true,
+        http::Method::POST => !uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .any(|param| matches!(param.split('=').next(), Some("delete" | "select"))),
+        _ => false,
+    }
+}
+
+fn insufficient_storage_body(resource: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Error><Code>InsufficientStorage</Code>\
+         <Message>Storage reached its minimum free drive threshold.</Message>\
+         <Resource>{resource}</Resource></Error>"
+    )
+}
+
+impl<S, ReqBody, RestBody, GrpcBody> Service<HttpRequest<ReqBody>> for MinFreeSpaceService<S>
+where
+    S: Service<HttpRequest<ReqBody>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ReqBody: Send + 'static,
+    RestBody: From<String> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<ReqBody>) -> Self::Future {
+        if !self.guard.allows_writes() && is_s3_write(req.method(), req.uri()) {
+            let path = req.uri().path();
+            debug!(path, "Rejecting S3 write below the minimum free drive space");
+
+            let response = Response::builder()
+                .status(StatusCode::INSUFFICIENT_STORAGE)
+                .header(http::header::CONTENT_TYPE, "application/xml")
+                .body(HybridBody::Rest {
+                    rest_body: RestBody::from(insufficient_storage_body(path)),
+                })
+                .expect("failed to build InsufficientStorage response");
+
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await.map_err(Into::into) })
+    }
+}
+
+/// Layer that rejects S3 writes and deletes on the buckets in `storage.read_only_buckets`
+#[derive(Clone)]
+pub struct ReadOnlyBucketsLayer {
+    buckets: Arc<HashSet<String>>,
+    /// Hosts serving virtual-hosted-style requests, without ports
+    domains: Arc<Vec<String>>,
+}
+
+impl ReadOnlyBucketsLayer {
+    pub fn new(buckets: HashSet<String>, server_domains: &[String]) -> Self {
+        let domains = server_domains
+            .iter()
+            .map(|domain| domain.split(':').next().unwrap_or(domain).to_string())
+            .collect();
+        Self {
+            buckets: Arc::new(buckets),
+            domains: Arc::new(domains),
+        }
+    }
+}
+
+impl<S> Layer<S> for ReadOnlyBucketsLayer {
+    type Service = ReadOnlyBucketsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadOnlyBucketsService {
+            inner,
+            buckets: self.buckets.clone(),
+            domains: self.domains.clone(),
+        }
+    }
+}
+
+/// Service implementation for read-only buckets
+#[derive(Clone)]
+pub struct ReadOnlyBucketsService<S> {
+    inner: S,
+    buckets: Arc<HashSet<String>>,
+    domains: Arc<Vec<String>>,
+}
+
+/// Whether an S3 request changes a bucket or its objects; select is a POST that only reads
+fn is_s3_mutation(method: &http::Method, uri: &Uri) -> bool {
+    if is_internal_path(uri.path()) {
+        return false;
+    }
+    match *method {
+        http::Method::PUT | http::Method::DELETE => true,
+        http::Method::POST => !uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .any(|param| param.split('=').next() == Some("select")),
+        _ => false,
+    }
+}
+
+/// Bucket addressed by a request, from the Host header for virtual-hosted style, else the first path segment
+fn request_bucket<'a>(headers: &'a HeaderMap, uri: &'a Uri, domains: &[String]) -> Option<&'a str> {
+    let host = headers
+        .get(http::header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| uri.host())
+        .map(|host| host.split(':').next().unwrap_or(host));
+    if let Some(host) = host {
+        for domain in domains {
+            if let Some(bucket) = host
+                .strip_suffix(domain.as_str())
+                .and_then(|prefix| prefix.strip_suffix('.'))
+                .filter(|bucket| !bucket.is_empty())
+            {
+                return Some(bucket);
+            }
+        }
+    }
+    uri.path()
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .filter(|bucket| !bucket.is_empty())
+}
+
+fn access_denied_body(resource: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Error><Code>AccessDenied</Code>\
+         <Message>Bucket is read-only.</Message>\
+         <Resource>{resource}</Resource></Error>"
+    )
+}
+
+impl<S, ReqBody, RestBody, GrpcBody> Service<HttpRequest<ReqBody>> for ReadOnlyBucketsService<S>
+where
+    S: Service<HttpRequest<ReqBody>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ReqBody: Send + 'static,
+    RestBody: From<String> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<ReqBody>) -> Self::Future {
+        if is_s3_mutation(req.method(), req.uri())
+            && let Some(bucket) = request_bucket(req.headers(), req.uri(), &self.domains)
+            && self.buckets.contains(bucket)
+        {
+            let path = req.uri().path();
+            debug!(bucket, path, method = %req.method(), "Rejecting mutation of read-only bucket");
+
+            let response = Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .header(http::header::CONTENT_TYPE, "application/xml")
+                .body(HybridBody::Rest {
+                    rest_body: RestBody::from(access_denied_body(path)),
+                })
+                .expect("failed to build AccessDenied response");
+
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await.map_err(Into::into) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    /// Inner service that accepts every request
+    #[derive(Clone)]
+    struct AcceptAll;
+
+    impl Service<HttpRequest<()>> for AcceptAll {
+        type Response = Response<HybridBody<String, ()>>;
+        type Error = Infallible;
+        type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: HttpRequest<()>) -> Self::Future {
+            std::future::ready(Ok(Response::new(HybridBody::Rest {
+                rest_body: String::new(),
+            })))
+        }
+    }
+
+    fn put(path: &str, content_length: u64) -> HttpRequest<()> {
+        HttpRequest::builder()
+            .method(http::Method::PUT)
+            .uri(path)
+            .header(http::header::CONTENT_LENGTH, content_length)
+            .body(())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rejects_put_over_limit() {
+        let mut service = MaxObjectSizeLayer::new(1024).layer(AcceptAll);
+
+        let response = service.call(put("/bucket/object", 1025)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let HybridBody::Rest { rest_body } = response.into_body() else {
+            panic!("expected REST body");
+        };
+        assert!(rest_body.contains("<Code>EntityTooLarge</Code>"));
+    }
+
+    #[tokio::test]
+    async fn test_allows_put_within_limit_and_internal_paths() {
+        let mut service = MaxObjectSizeLayer::new(1024).layer(AcceptAll);
+
+        let response = service.call(put("/bucket/object", 1024)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = service.call(put("/nebulafx/admin/v3/import-iam", 4096)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn blob(chunks: &[&'static [u8]]) -> StreamingBlob {
+        let chunks: Vec<Result<Bytes, std::io::Error>> = chunks.iter().map(|c| Ok(Bytes::from_static(c))).collect();
+        StreamingBlob::wrap(futures::stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn test_body_without_declared_length_is_cut_off_at_limit() {
+        // Without a Content-Length the layer cannot reject up front, so it hands the limit on
+        let mut service = MaxObjectSizeLayer::new(8).layer(tower::service_fn(|req: HttpRequest<()>| async move {
+            assert_eq!(req.extensions().get::<ObjectSizeLimit>(), Some(&ObjectSizeLimit(8)));
+            Ok::<_, Infallible>(Response::new(HybridBody::<String, ()>::Rest {
+                rest_body: String::new(),
+            }))
+        }));
+        let unsized_put = HttpRequest::builder()
+            .method(http::Method::PUT)
+            .uri("/bucket/object")
+            .body(())
+            .unwrap();
+        assert_eq!(service.call(unsized_put).await.unwrap().status(), StatusCode::OK);
+
+        let mut body = ObjectSizeLimit(8).limit_body(blob(&[b"0123", b"4567", b"8"]));
+        assert_eq!(body.next().await.unwrap().unwrap(), Bytes::from_static(b"0123"));
+        assert_eq!(body.next().await.unwrap().unwrap(), Bytes::from_static(b"4567"));
+        let err = body.next().await.unwrap().unwrap_err();
+        assert_eq!(*object_too_large(&err).unwrap().code(), s3s::S3ErrorCode::EntityTooLarge);
+
+        let within: Vec<_> = ObjectSizeLimit(8).limit_body(blob(&[b"0123", b"4567"])).collect().await;
+        assert!(within.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload_checked_against_summed_part_sizes() {
+        let mut service = MaxObjectSizeLayer::new(1024).layer(AcceptAll);
+        // Each part fits on its own
+        for part in [
+            "/bucket/object?partNumber=1&uploadId=u",
+            "/bucket/object?partNumber=2&uploadId=u",
+        ] {
+            assert_eq!(service.call(put(part, 600)).await.unwrap().status(), StatusCode::OK);
+        }
+
+        let err = ObjectSizeLimit(1024).check_parts([600, 600]).unwrap_err();
+        assert_eq!(*err.code(), s3s::S3ErrorCode::EntityTooLarge);
+        assert!(ObjectSizeLimit(1024).check_parts([512, 512]).is_ok());
+        assert!(ObjectSizeLimit(1024).check_parts([u64::MAX, 1]).is_err());
+    }
+
+    fn request(method: http::Method, uri: &str) -> HttpRequest<()> {
+        HttpRequest::builder().method(method).uri(uri).body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_writes_rejected_below_free_space_floor() {
+        use crate::storage::free_space::{DriveUsage, FreeSpaceFloor};
+
+        let guard = Arc::new(FreeSpaceGuard::new(FreeSpaceFloor {
+            min_bytes: Some(1024),
+            min_percent: None,
+        }));
+        let mut service = MinFreeSpaceLayer::new(guard.clone()).layer(AcceptAll);
+        assert_eq!(service.call(put("/bucket/object", 10)).await.unwrap().status(), StatusCode::OK);
+
+        // Simulate a poll that finds the drive nearly full
+        guard.record(&[DriveUsage {
+            path: "/data1".to_string(),
+            free: 512,
+            total: 1 << 30,
+        }]);
+
+        let response = service.call(put("/bucket/object", 10)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INSUFFICIENT_STORAGE);
+        let HybridBody::Rest { rest_body } = response.into_body() else {
+            panic!("expected REST body");
+        };
+        assert!(rest_body.contains("<Code>InsufficientStorage</Code>"));
+        let response = service
+            .call(request(http::Method::POST, "/bucket/object?uploads"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INSUFFICIENT_STORAGE);
+
+        // Reads and deletes still go through
+        for (method, uri) in [
+            (http::Method::GET, "/bucket/object"),
+            (http::Method::HEAD, "/bucket/object"),
+            (http::Method::DELETE, "/bucket/object"),
+            (http::Method::POST, "/bucket?delete"),
+            (http::Method::POST, "/bucket/object?select&select-type=2"),
+        ] {
+            assert_eq!(service.call(request(method, uri)).await.unwrap().status(), StatusCode::OK, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_bucket_rejects_writes_but_serves_reads() {
+        let buckets = HashSet::from(["archive".to_string()]);
+        let mut service = ReadOnlyBucketsLayer::new(buckets, &["s3.example.com:9000".to_string()]).layer(AcceptAll);
+
+        let response = service.call(put("/archive/object", 10)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let HybridBody::Rest { rest_body } = response.into_body() else {
+            panic!("expected REST body");
+        };
+        assert!(rest_body.contains("<Code>AccessDenied</Code>"));
+
+        let response = service.call(request(http::Method::GET, "/archive/object")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for (method, uri) in [
+            (http::Method::DELETE, "/archive/object"),
+            (http::Method::DELETE, "/archive"),
+            (http::Method::POST, "/archive?delete"),
+            (http::Method::POST, "/archive/object?uploads"),
+        ] {
+            assert_eq!(service.call(request(method, uri)).await.unwrap().status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        // Virtual-hosted style addresses the bucket through the Host header
+        let vhost_put = HttpRequest::builder()
+            .method(http::Method::PUT)
+            .uri("/object")
+            .header(http::header::HOST, "archive.s3.example.com:9000")
+            .body(())
+            .unwrap();
+        assert_eq!(service.call(vhost_put).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        for (method, uri) in [
+            (http::Method::HEAD, "/archive/object"),
+            (http::Method::POST, "/archive/object?select&select-type=2"),
+            (http::Method::PUT, "/other/object"),
+            (http::Method::PUT, "/archived/object"),
+            (http::Method::PUT, "/nebulafx/admin/v3/heal/archive"),
+        ] {
+            assert_eq!(service.call(request(method, uri)).await.unwrap().status(), StatusCode::OK, "{uri}");
+        }
+    }
+}
@@ -1,16 +1,36 @@
+mod advertise;
 mod audit;
+mod cert_reload;
+mod console_socket;
 mod http;
 mod hybrid;
+mod iam_state;
 mod layer;
+mod region;
+mod role;
 mod service_state;
+mod setup;
+mod shutdown_plan;
+mod startup_timeline;
+mod tls_expiry;
 
 mod event;
 
 pub(crate) use audit::{start_audit_system, stop_audit_system};
+pub(crate) use console_socket::{ConsoleSocket, start_console_socket};
 pub(crate) use event::{init_event_notifier, shutdown_event_notifier};
-pub(crate) use http::start_http_server;
-pub(crate) use service_state::SHUTDOWN_TIMEOUT;
+pub(crate) use http::{start_http_server, tls_server_builder};
+pub(crate) use iam_state::{IamState, iam_readiness, start_iam};
+pub(crate) use layer::{ObjectSizeLimit, object_too_large};
+pub(crate) use region::verify_region_consistency;
+pub(crate) use role::{RoleStep, role_runs};
 pub(crate) use service_state::ServiceState;
 pub(crate) use service_state::ServiceStateManager;
 pub(crate) use service_state::ShutdownSignal;
 pub(crate) use service_state::wait_for_shutdown;
+pub(crate) use service_state::{DEFAULT_SHUTDOWN_GRACE_PERIOD, DEFAULT_STARTUP_DELAY, wait_until_stopped};
+pub(crate) use service_state::{last_shutdown_reason, record_shutdown_reason, restore_shutdown_reason};
+pub(crate) use setup::verify_expected_setup;
+pub(crate) use shutdown_plan::ShutdownPlan;
+pub(crate) use startup_timeline::{StartupReport, startup_timeline};
+pub(crate) use tls_expiry::{CertStatus, preflight_tls_certs, tls_cert_status};
@@ -0,0 +1,82 @@
+use crate::admin::console::CONSOLE_CONFIG;
+use crate::config::ServerConfig;
+use tracing::{info, warn};
+
+/// Describe every region source that disagrees with `expected`
+fn region_mismatches(expected: &str, global: Option<&str>, console: Option<&str>) -> Vec<String> {
+    [("global", global), ("console", console)]
+        .into_iter()
+        .filter_map(|(source, region)| match region {
+            Some(region) if region == expected => None,
+            Some(region) => Some(format!("{source} region is '{region}'")),
+            None => Some(format!("{source} region is not set")),
+        })
+        .collect()
+}
+
+/// Check that the configured, global and console-advertised regions agree
+///
+/// Must run after the HTTP server has initialized the console config. Divergence is
+/// logged as a warning rather than failing startup.
+pub(crate) fn verify_region_consistency(server_config: &ServerConfig) -> bool {
+    let expected = server_config.resolved_region();
+    let global = nebulafx_ecstore::global::get_global_region();
+    let console = CONSOLE_CONFIG.get().map(|cfg| cfg.region());
+
+    let mismatches = region_mismatches(expected, global.as_deref(), console);
+    if mismatches.is_empty() {
+        info!(target: "nebulafx::main::startup", region = expected, "Region is consistent across S3, console and global state");
+        true
+    } else {
+        warn!(
+            target: "nebulafx::main::startup",
+            region = expected,
+            "REGION MISMATCH: server.region resolves to '{}' but {}",
+            expected,
+            mismatches.join(", ")
+        );
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent_regions() {
+        assert!(region_mismatches("us-east-1", Some("us-east-1"), Some("us-east-1")).is_empty());
+    }
+
+    #[test]
+    fn test_divergent_regions() {
+        let mismatches = region_mismatches("us-east-1", Some("us-east-1"), Some("cn-east-1"));
+        assert_eq!(mismatches, vec!["console region is 'cn-east-1'".to_string()]);
+
+        let mismatches = region_mismatches("eu-west-1", None, Some("eu-west-1"));
+        assert_eq!(mismatches, vec!["global region is not set".to_string()]);
+    }
+
+    #[test]
+    fn test_mismatched_region_is_logged_without_failing_startup() {
+        use nebulafx_utils::testing::CapturedLogs;
+
+        // No global or console state carries this region, whatever other tests have set
+        let server_config: ServerConfig = nebulafx_tomlx::load_config_from_str("region = \"mismatch-test-1\"\n").unwrap();
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .without_time()
+            .finish();
+
+        let consistent = tracing::subscriber::with_default(subscriber, || verify_region_consistency(&server_config));
+        assert!(!consistent);
+        let output = logs.contents();
+        assert!(output.contains("WARN nebulafx::main::startup"), "{output}");
+        assert!(
+            output.contains("REGION MISMATCH: server.region resolves to 'mismatch-test-1' but global region"),
+            "{output}"
+        );
+    }
+}
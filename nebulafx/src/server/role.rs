@@ -0,0 +1,64 @@
+use crate::config::ServerRole;
+
+/// Startup work that depends on the node's role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RoleStep {
+    /// Resync of bucket replication targets
+    ReplicationResync,
+    /// Creating or updating the root user in the IAM database
+    SeedRootUser,
+    /// Heal manager repairing objects on the shared drives
+    Heal,
+    /// Background scanner, which updates data usage and queues heals
+    Scanner,
+}
+
+/// Whether a node with `role` performs `step`
+///
+/// Standbys skip steps that write shared state, leaving them to the primary.
+pub(crate) fn role_runs(role: ServerRole, step: RoleStep) -> bool {
+    match role {
+        ServerRole::Primary => true,
+        ServerRole::Standby => !matches!(
+            step,
+            RoleStep::ReplicationResync | RoleStep::SeedRootUser | RoleStep::Heal | RoleStep::Scanner
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use nebulafx_tomlx::load_config_from_str;
+
+    const ALL_STEPS: [RoleStep; 4] = [
+        RoleStep::ReplicationResync,
+        RoleStep::SeedRootUser,
+        RoleStep::Heal,
+        RoleStep::Scanner,
+    ];
+
+    #[test]
+    fn test_standby_skips_designated_steps() {
+        // Heal and scanner write to the shared drives, so only the primary runs them
+        for step in ALL_STEPS {
+            assert!(!role_runs(ServerRole::Standby, step), "{step:?}");
+        }
+    }
+
+    #[test]
+    fn test_primary_runs_every_step() {
+        for step in ALL_STEPS {
+            assert!(role_runs(ServerRole::Primary, step), "{step:?}");
+        }
+    }
+
+    #[test]
+    fn test_role_is_validated_at_load() {
+        let config = load_config_from_str::<Config>("[server]\nrole = \"standby\"\n").unwrap();
+        assert_eq!(config.server.unwrap().role, Some(ServerRole::Standby));
+
+        assert!(load_config_from_str::<Config>("[server]\nrole = \"replica\"\n").is_err());
+    }
+}
@@ -1,11 +1,20 @@
 use atomic_enum::atomic_enum;
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock, RwLock};
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
 
-// a configurable shutdown timeout
-pub(crate) const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+/// Pause after startup before shutdown signals are handled, unless `server.startup_delay_ms` is set
+pub(crate) const DEFAULT_STARTUP_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait for in-flight requests on shutdown, unless `server.shutdown_grace_period_ms` is set
+pub(crate) const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// How often [`wait_until_stopped`] re-checks its condition
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
 #[cfg(target_os = "linux")]
 fn notify_systemd(state: &str) {
@@ -42,6 +51,116 @@ pub enum ShutdownSignal {
     Sigint,
 }
 
+impl ShutdownSignal {
+    /// Stable reason recorded when this signal stops the server
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ShutdownSignal::CtrlC => "ctrl_c",
+            #[cfg(unix)]
+            ShutdownSignal::Sigterm => "sigterm",
+            #[cfg(unix)]
+            ShutdownSignal::Sigint => "sigint",
+        }
+    }
+}
+
+/// Why and when the server was asked to stop
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ShutdownReason {
+    pub signal: String,
+    pub received_at: DateTime<Utc>,
+}
+
+/// File under `server.state_dir` holding the reason the previous run stopped
+const SHUTDOWN_REASON_FILE: &str = "last_shutdown.json";
+
+/// Reason this process was asked to stop
+static LAST_SHUTDOWN_REASON: RwLock<Option<ShutdownReason>> = RwLock::new(None);
+
+/// Reason the previous run stopped, read once and kept for the lifetime of this process
+static RESTORED_SHUTDOWN_REASON: OnceLock<Option<ShutdownReason>> = OnceLock::new();
+
+/// Log the signal that stopped the server and keep it for status reporting, in `state_dir` when set
+pub(crate) fn record_shutdown_reason(signal: &ShutdownSignal, state_dir: Option<&Path>) -> ShutdownReason {
+    let reason = ShutdownReason {
+        signal: signal.reason().to_string(),
+        received_at: Utc::now(),
+    };
+    info!(
+        target: "nebulafx::main::shutdown",
+        reason = %reason.signal,
+        received_at = %reason.received_at.to_rfc3339(),
+        "Shutdown requested"
+    );
+    if let Some(dir) = state_dir {
+        if let Err(e) = write_shutdown_reason(dir, &reason) {
+            warn!(target: "nebulafx::main::shutdown", "Failed to save the shutdown reason in {}: {}", dir.display(), e);
+        }
+    }
+    *LAST_SHUTDOWN_REASON.write().unwrap() = Some(reason.clone());
+    reason
+}
+
+fn write_shutdown_reason(dir: &Path, reason: &ShutdownReason) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let tmp = dir.join(format!("{SHUTDOWN_REASON_FILE}.tmp"));
+    std::fs::write(&tmp, serde_json::to_vec(reason)?)?;
+    std::fs::rename(tmp, dir.join(SHUTDOWN_REASON_FILE))
+}
+
+/// Restore the reason the previous run saved in `state_dir`, so `/health` reports it after a restart
+///
+/// The file is read on the first call only and then kept in memory, so later calls and
+/// every `/health` probe see the same reason for the rest of the process.
+pub(crate) fn restore_shutdown_reason(state_dir: &Path) -> Option<ShutdownReason> {
+    RESTORED_SHUTDOWN_REASON
+        .get_or_init(|| take_saved_shutdown_reason(state_dir))
+        .clone()
+}
+
+/// Read and remove the reason saved in `state_dir`
+///
+/// A run that ends without a signal saves none, so no reason after a restart means the
+/// previous run crashed or was killed.
+fn take_saved_shutdown_reason(state_dir: &Path) -> Option<ShutdownReason> {
+    let path = state_dir.join(SHUTDOWN_REASON_FILE);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!(target: "nebulafx::main::startup", "Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!(target: "nebulafx::main::startup", "Failed to remove {}: {}", path.display(), e);
+    }
+    match serde_json::from_slice::<ShutdownReason>(&bytes) {
+        Ok(reason) => {
+            info!(
+                target: "nebulafx::main::startup",
+                reason = %reason.signal,
+                received_at = %reason.received_at.to_rfc3339(),
+                "Previous run was stopped by a signal"
+            );
+            Some(reason)
+        }
+        Err(e) => {
+            warn!(target: "nebulafx::main::startup", "Ignoring unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The last shutdown reason: this process's once a stop is requested, else the one restored from the previous run
+pub(crate) fn last_shutdown_reason() -> Option<ShutdownReason> {
+    LAST_SHUTDOWN_REASON
+        .read()
+        .unwrap()
+        .clone()
+        .or_else(|| RESTORED_SHUTDOWN_REASON.get().cloned().flatten())
+}
+
 #[atomic_enum]
 #[derive(PartialEq)]
 pub(crate) enum ServiceState {
@@ -83,6 +202,21 @@ pub(crate) async fn wait_for_shutdown() -> ShutdownSignal {
     }
 }
 
+/// Wait until `stopped` holds or `grace` has passed, returning whether it stopped in time
+pub(crate) async fn wait_until_stopped(grace: Duration, stopped: impl Fn() -> bool) -> bool {
+    let deadline = tokio::time::Instant::now() + grace;
+    loop {
+        if stopped() {
+            return true;
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        tokio::time::sleep(STOP_POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ServiceStateManager {
     state: Arc<AtomicServiceState>,
@@ -142,4 +276,38 @@ impl Default for ServiceStateManager {
     }
 }
 
- 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shutdown_signal_reasons() {
+        assert_eq!(ShutdownSignal::CtrlC.reason(), "ctrl_c");
+        #[cfg(unix)]
+        {
+            assert_eq!(ShutdownSignal::Sigterm.reason(), "sigterm");
+            assert_eq!(ShutdownSignal::Sigint.reason(), "sigint");
+        }
+    }
+
+    #[test]
+    fn test_shutdown_reason_survives_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let reason = record_shutdown_reason(&ShutdownSignal::CtrlC, Some(dir.path()));
+        assert_eq!(reason.signal, "ctrl_c");
+        assert_eq!(last_shutdown_reason(), Some(reason.clone()));
+
+        // Start over as the next process would
+        *LAST_SHUTDOWN_REASON.write().unwrap() = None;
+        assert_eq!(restore_shutdown_reason(dir.path()), Some(reason.clone()));
+        // The file is consumed, so a crash before the next signal leaves nothing to restore
+        assert!(!dir.path().join(SHUTDOWN_REASON_FILE).exists());
+        assert_eq!(take_saved_shutdown_reason(dir.path()), None);
+
+        // Yet the restored reason stays for the rest of the process, however often it is read
+        for _ in 0..3 {
+            assert_eq!(restore_shutdown_reason(dir.path()), Some(reason.clone()));
+            assert_eq!(last_shutdown_reason(), Some(reason.clone()));
+        }
+    }
+}
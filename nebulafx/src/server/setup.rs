@@ -0,0 +1,68 @@
+use nebulafx_ecstore::endpoints::SetupType;
+use std::io::{Error, Result};
+use tracing::info;
+
+/// Setup name accepted by `storage.expected_setup`
+fn setup_name(setup_type: &SetupType) -> &'static str {
+    match setup_type {
+        SetupType::Unknown => "unknown",
+        SetupType::FS => "fs",
+        SetupType::ErasureSD => "single-drive",
+        SetupType::Erasure => "single-node",
+        SetupType::DistErasure => "distributed",
+    }
+}
+
+fn parse_expected_setup(expected: &str) -> Result<SetupType> {
+    match expected.trim().to_ascii_lowercase().as_str() {
+        "single-drive" => Ok(SetupType::ErasureSD),
+        "single-node" => Ok(SetupType::Erasure),
+        "distributed" => Ok(SetupType::DistErasure),
+        other => Err(Error::other(format!(
+            "invalid storage.expected_setup '{other}', expected one of: single-drive, single-node, distributed"
+        ))),
+    }
+}
+
+/// Fail startup when the setup derived from the volumes differs from `storage.expected_setup`
+pub(crate) fn verify_expected_setup(expected: Option<&str>, resolved: &SetupType) -> Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    let expected = parse_expected_setup(expected)?;
+    if &expected != resolved {
+        return Err(Error::other(format!(
+            "storage.expected_setup is '{}' but the configured volumes resolve to a '{}' setup",
+            setup_name(&expected),
+            setup_name(resolved)
+        )));
+    }
+
+    info!(target: "nebulafx::main::startup", setup = setup_name(resolved), "Erasure setup matches storage.expected_setup");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_expectation() {
+        assert!(verify_expected_setup(None, &SetupType::DistErasure).is_ok());
+        assert!(verify_expected_setup(Some("distributed"), &SetupType::DistErasure).is_ok());
+        assert!(verify_expected_setup(Some("Single-Node"), &SetupType::Erasure).is_ok());
+    }
+
+    #[test]
+    fn test_mismatching_expectation() {
+        let err = verify_expected_setup(Some("single-node"), &SetupType::DistErasure).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "storage.expected_setup is 'single-node' but the configured volumes resolve to a 'distributed' setup"
+        );
+
+        assert!(verify_expected_setup(Some("distributed"), &SetupType::ErasureSD).is_err());
+        assert!(verify_expected_setup(Some("cluster"), &SetupType::DistErasure).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+use tracing::{error, info};
+
+type StopFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// One subsystem to stop, and the subsystems that must be stopped before it
+struct ShutdownStep {
+    name: &'static str,
+    after: &'static [&'static str],
+    stop: Box<dyn FnOnce() -> StopFuture + Send>,
+}
+
+/// Subsystems stopped in dependency order during graceful shutdown
+///
+/// Adding a subsystem means adding one [`ShutdownPlan::step`]; steps with no ordering
+/// constraint between them stop in declaration order.
+#[derive(Default)]
+pub(crate) struct ShutdownPlan {
+    steps: Vec<ShutdownStep>,
+}
+
+impl ShutdownPlan {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop `name` with `stop` once every step in `after` has stopped
+    pub(crate) fn step<F, Fut>(mut self, name: &'static str, after: &'static [&'static str], stop: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.steps.push(ShutdownStep {
+            name,
+            after,
+            stop: Box::new(move || Box::pin(stop())),
+        });
+        self
+    }
+
+    /// Step indices in stop order, or an error naming an unknown or cyclic dependency
+    fn order(&self) -> Result<Vec<usize>, String> {
+        for step in &self.steps {
+            if let Some(missing) = step.after.iter().find(|dep| !self.steps.iter().any(|s| s.name == **dep)) {
+                return Err(format!("shutdown step '{}' depends on unknown step '{}'", step.name, missing));
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.steps.len());
+        while order.len() < self.steps.len() {
+            let stopped = |name: &str| order.iter().any(|&i: &usize| self.steps[i].name == name);
+            let next = (0..self.steps.len())
+                .find(|i| !order.contains(i) && self.steps[*i].after.iter().all(|dep| stopped(dep)))
+                .ok_or_else(|| {
+                    let pending: Vec<_> = (0..self.steps.len())
+                        .filter(|i| !order.contains(i))
+                        .map(|i| self.steps[i].name)
+                        .collect();
+                    format!("shutdown steps {pending:?} depend on each other")
+                })?;
+            order.push(next);
+        }
+        Ok(order)
+    }
+
+    /// Stop every subsystem, logging each transition; returns the names in the order they stopped
+    pub(crate) async fn run(self) -> Vec<&'static str> {
+        let order = self.order().unwrap_or_else(|e| {
+            error!(target: "nebulafx::main::handle_shutdown", "Invalid shutdown plan, stopping in declaration order: {}", e);
+            (0..self.steps.len()).collect()
+        });
+
+        let mut steps: Vec<Option<ShutdownStep>> = self.steps.into_iter().map(Some).collect();
+        let mut stopped = Vec::with_capacity(order.len());
+        for index in order {
+            let Some(step) = steps[index].take() else {
+                continue;
+            };
+            info!(target: "nebulafx::main::handle_shutdown", step = step.name, "Stopping {}...", step.name);
+            let started = Instant::now();
+            (step.stop)().await;
+            info!(
+                target: "nebulafx::main::handle_shutdown",
+                step = step.name,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Stopped {}", step.name
+            );
+            stopped.push(step.name);
+        }
+        stopped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn recording(
+        log: &Arc<Mutex<Vec<&'static str>>>,
+        name: &'static str,
+    ) -> impl FnOnce() -> std::future::Ready<()> + Send + 'static {
+        let log = log.clone();
+        move || {
+            log.lock().unwrap().push(name);
+            std::future::ready(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subsystems_stop_in_declared_dependency_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // Declared out of order; the dependencies decide
+        let plan = ShutdownPlan::new()
+            .step("database", &["ahm"], recording(&log, "database"))
+            .step("audit", &["notifier"], recording(&log, "audit"))
+            .step("ahm", &["audit"], recording(&log, "ahm"))
+            .step("notifier", &["http"], recording(&log, "notifier"))
+            .step("http", &[], recording(&log, "http"));
+
+        let stopped = plan.run().await;
+        assert_eq!(stopped, ["http", "notifier", "audit", "ahm", "database"]);
+        assert_eq!(*log.lock().unwrap(), stopped);
+    }
+
+    #[test]
+    fn test_cycles_and_unknown_dependencies_are_reported() {
+        let plan = ShutdownPlan::new()
+            .step("a", &["b"], || async {})
+            .step("b", &["a"], || async {});
+        assert!(plan.order().unwrap_err().contains("depend on each other"));
+
+        let plan = ShutdownPlan::new().step("a", &["missing"], || async {});
+        assert!(plan.order().unwrap_err().contains("unknown step 'missing'"));
+    }
+}
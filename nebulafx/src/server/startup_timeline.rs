@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// One init step of `run()` and how long it took
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct StartupStep {
+    pub name: &'static str,
+    pub elapsed_ms: u64,
+    pub ok: bool,
+}
+
+/// The timeline as reported by `GET {CONSOLE_PREFIX}/debug/startup`
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct StartupReport {
+    /// `in_progress`, `completed` or `failed`
+    pub status: &'static str,
+    pub total_ms: u64,
+    pub steps: Vec<StartupStep>,
+}
+
+#[derive(Debug, Default)]
+struct TimelineState {
+    steps: Vec<StartupStep>,
+    /// Total boot time, set once the timeline is finished
+    total: Option<Duration>,
+    /// Startup returned an error, possibly outside any timed step
+    failed: bool,
+}
+
+/// Durations of the major init steps, kept so slow boots can be diagnosed afterwards
+#[derive(Debug)]
+pub(crate) struct StartupTimeline {
+    started: Instant,
+    state: Mutex<TimelineState>,
+}
+
+impl StartupTimeline {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            state: Mutex::new(TimelineState::default()),
+        }
+    }
+
+    /// Start timing `name`; the step is recorded as failed unless [`StepTimer::done`] is called
+    pub(crate) fn begin(&self, name: &'static str) -> StepTimer<'_> {
+        StepTimer {
+            timeline: self,
+            name,
+            started: Instant::now(),
+            done: false,
+        }
+    }
+
+    fn record(&self, name: &'static str, elapsed: Duration, ok: bool) {
+        self.state.lock().unwrap().steps.push(StartupStep {
+            name,
+            elapsed_ms: elapsed.as_millis() as u64,
+            ok,
+        });
+    }
+
+    /// Stop the clock and log the timeline as one structured event; later calls only report it
+    pub(crate) fn finish(&self, succeeded: bool) -> StartupReport {
+        let first = {
+            let mut state = self.state.lock().unwrap();
+            let first = state.total.is_none();
+            if first {
+                state.total = Some(self.started.elapsed());
+                state.failed = !succeeded;
+            }
+            first
+        };
+        let report = self.report();
+        if first {
+            let steps = serde_json::to_string(&report.steps).unwrap_or_default();
+            if report.status == "failed" {
+                warn!(target: "nebulafx::main::startup", total_ms = report.total_ms, steps = %steps, "Startup failed");
+            } else {
+                info!(target: "nebulafx::main::startup", total_ms = report.total_ms, steps = %steps, "Startup timeline");
+            }
+        }
+        report
+    }
+
+    pub(crate) fn report(&self) -> StartupReport {
+        let state = self.state.lock().unwrap();
+        let status = match state.total {
+            _ if state.failed || state.steps.iter().any(|step| !step.ok) => "failed",
+            Some(_) => "completed",
+            None => "in_progress",
+        };
+        StartupReport {
+            status,
+            total_ms: state.total.unwrap_or_else(|| self.started.elapsed()).as_millis() as u64,
+            steps: state.steps.clone(),
+        }
+    }
+}
+
+/// Times one step; dropping it without [`StepTimer::done`] records a failure
+pub(crate) struct StepTimer<'a> {
+    timeline: &'a StartupTimeline,
+    name: &'static str,
+    started: Instant,
+    done: bool,
+}
+
+impl StepTimer<'_> {
+    pub(crate) fn done(mut self) {
+        self.done = true;
+    }
+}
+
+impl Drop for StepTimer<'_> {
+    fn drop(&mut self) {
+        self.timeline.record(self.name, self.started.elapsed(), self.done);
+    }
+}
+
+static STARTUP_TIMELINE: LazyLock<StartupTimeline> = LazyLock::new(StartupTimeline::new);
+
+/// The timeline of this process's boot
+pub(crate) fn startup_timeline() -> &'static StartupTimeline {
+    &STARTUP_TIMELINE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(report: &StartupReport) -> Vec<&'static str> {
+        report.steps.iter().map(|step| step.name).collect()
+    }
+
+    #[test]
+    fn test_completed_steps_are_recorded_in_order() {
+        let timeline = StartupTimeline::new();
+        timeline.begin("endpoints").done();
+        timeline.begin("local_disks").done();
+        assert_eq!(timeline.report().status, "in_progress");
+        timeline.begin("ecstore").done();
+
+        let report = timeline.finish(true);
+        assert_eq!(report.status, "completed");
+        assert_eq!(names(&report), ["endpoints", "local_disks", "ecstore"]);
+        assert!(report.steps.iter().all(|step| step.ok));
+    }
+
+    #[test]
+    fn test_failed_step_ends_the_timeline() {
+        fn boot(timeline: &StartupTimeline) -> Result<(), &'static str> {
+            timeline.begin("endpoints").done();
+            let _step = timeline.begin("ecstore");
+            Err("disks unreachable")
+        }
+
+        let timeline = StartupTimeline::new();
+        assert!(boot(&timeline).is_err());
+
+        let report = timeline.finish(false);
+        assert_eq!(report.status, "failed");
+        assert_eq!(names(&report), ["endpoints", "ecstore"]);
+        assert!(!report.steps[1].ok);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::config::TlsConfig;
+use nebulafx_utils::CertExpiry;
+use serde::Serialize;
+use std::io::{Error, Result};
+use std::path::Path;
+use std::sync::RwLock;
+use tracing::{error, info, warn};
+
+/// Days before expiry at which a certificate is reported as expiring soon
+pub(crate) const DEFAULT_EXPIRY_WARNING_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CertStatus {
+    Valid,
+    ExpiringSoon,
+    Expired,
+}
+
+/// One served certificate as reported by `/health`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct CertReport {
+    pub name: String,
+    pub subject: String,
+    pub not_after: Option<String>,
+    pub days_to_expiry: i64,
+    pub status: CertStatus,
+}
+
+/// Certificates being served, re-assessed on every health check
+struct LoadedCerts {
+    expiries: Vec<CertExpiry>,
+    warning_days: u32,
+}
+
+/// Set by the startup preflight and replaced whenever the certificates are reloaded
+static LOADED_CERTS: RwLock<Option<LoadedCerts>> = RwLock::new(None);
+
+fn assess(expiries: &[CertExpiry], now: i64, warning_days: u32) -> Vec<CertReport> {
+    expiries
+        .iter()
+        .map(|cert| {
+            let days = cert.days_remaining(now);
+            let status = if cert.not_after <= now {
+                CertStatus::Expired
+            } else if days < warning_days as i64 {
+                CertStatus::ExpiringSoon
+            } else {
+                CertStatus::Valid
+            };
+            CertReport {
+                name: cert.name.clone(),
+                subject: cert.subject.clone(),
+                not_after: chrono::DateTime::from_timestamp(cert.not_after, 0).map(|at| at.to_rfc3339()),
+                days_to_expiry: days,
+                status,
+            }
+        })
+        .collect()
+}
+
+fn log_reports(reports: &[CertReport]) {
+    for cert in reports {
+        match cert.status {
+            CertStatus::Expired => error!(
+                target: "nebulafx::main::tls",
+                name = %cert.name,
+                subject = %cert.subject,
+                not_after = ?cert.not_after,
+                "TLS certificate has expired; clients will fail the handshake"
+            ),
+            CertStatus::ExpiringSoon => warn!(
+                target: "nebulafx::main::tls",
+                name = %cert.name,
+                subject = %cert.subject,
+                days_to_expiry = cert.days_to_expiry,
+                "TLS certificate expires in {} days", cert.days_to_expiry
+            ),
+            CertStatus::Valid => info!(
+                target: "nebulafx::main::tls",
+                name = %cert.name,
+                days_to_expiry = cert.days_to_expiry,
+                "TLS certificate is valid"
+            ),
+        }
+    }
+}
+
+/// Fail when any certificate has expired and `tls.fail_on_expired` is set
+fn reject_expired(reports: &[CertReport], tls: Option<&TlsConfig>) -> Result<()> {
+    let expired: Vec<_> = reports
+        .iter()
+        .filter(|cert| cert.status == CertStatus::Expired)
+        .map(|cert| cert.name.as_str())
+        .collect();
+    if expired.is_empty() || !tls.and_then(|t| t.fail_on_expired).unwrap_or(false) {
+        return Ok(());
+    }
+    Err(Error::other(format!(
+        "expired TLS certificates {expired:?} and tls.fail_on_expired is set"
+    )))
+}
+
+/// Check the certificates in `tls_path` before the TLS acceptor is built, logging any that
+/// are expired or close to it
+///
+/// A directory without loadable certificates is left to the acceptor setup, which
+/// falls back to plain HTTP.
+pub(crate) fn preflight_tls_certs(tls_path: &str, tls: Option<&TlsConfig>) -> Result<()> {
+    if tls_path.is_empty() || !std::path::Path::new(tls_path).is_dir() {
+        return Ok(());
+    }
+    let expiries = match nebulafx_utils::load_cert_expiries(tls_path) {
+        Ok(expiries) => expiries,
+        Err(e) => {
+            warn!(target: "nebulafx::main::tls", "Skipping TLS certificate preflight: {}", e);
+            return Ok(());
+        }
+    };
+
+    let warning_days = tls.and_then(|t| t.expiry_warning_days).unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+    let reports = assess(&expiries, chrono::Utc::now().timestamp(), warning_days);
+    log_reports(&reports);
+    *LOADED_CERTS.write().unwrap() = Some(LoadedCerts { expiries, warning_days });
+    reject_expired(&reports, tls)
+}
+
+/// Re-read the expiry dates once the certificates in `tls_path` have been reloaded
+pub(crate) fn refresh_cert_expiries(tls_path: &Path) {
+    refresh(&mut LOADED_CERTS.write().unwrap(), tls_path);
+}
+
+/// Replace the expiries in `loaded`, keeping the warning threshold set at startup
+fn refresh(loaded: &mut Option<LoadedCerts>, tls_path: &Path) {
+    let expiries = match nebulafx_utils::load_cert_expiries(&tls_path.to_string_lossy()) {
+        Ok(expiries) => expiries,
+        Err(e) => {
+            warn!(target: "nebulafx::main::tls", "Keeping previous TLS certificate expiries: {}", e);
+            return;
+        }
+    };
+    let warning_days = loaded.as_ref().map_or(DEFAULT_EXPIRY_WARNING_DAYS, |l| l.warning_days);
+    log_reports(&assess(&expiries, chrono::Utc::now().timestamp(), warning_days));
+    *loaded = Some(LoadedCerts { expiries, warning_days });
+}
+
+/// Days to expiry of each certificate being served, or `None` when TLS is not in use
+pub(crate) fn tls_cert_status() -> Option<Vec<CertReport>> {
+    let loaded = LOADED_CERTS.read().unwrap();
+    let loaded = loaded.as_ref()?;
+    Some(assess(&loaded.expiries, chrono::Utc::now().timestamp(), loaded.warning_days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nebulafx_config::{NEUBULAFX_TLS_CERT, NEUBULAFX_TLS_KEY};
+    use time::{Duration, OffsetDateTime};
+
+    /// Write a self-signed certificate for `name` valid until `expires_in` from now
+    fn write_cert(dir: &std::path::Path, name: &str, expires_in: Duration) {
+        let mut params = rcgen::CertificateParams::new(vec![name.to_string()]).unwrap();
+        params.not_before = OffsetDateTime::now_utc() - Duration::days(400);
+        params.not_after = OffsetDateTime::now_utc() + expires_in;
+        let key = rcgen::KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+
+        let domain_dir = dir.join(name);
+        std::fs::create_dir_all(&domain_dir).unwrap();
+        std::fs::write(domain_dir.join(NEUBULAFX_TLS_CERT), cert.pem()).unwrap();
+        std::fs::write(domain_dir.join(NEUBULAFX_TLS_KEY), key.serialize_pem()).unwrap();
+    }
+
+    fn tls_config(fail_on_expired: bool) -> TlsConfig {
+        nebulafx_tomlx::load_config_from_str(&format!("fail_on_expired = {fail_on_expired}\n")).unwrap()
+    }
+
+    #[test]
+    fn test_valid_near_expiry_and_expired_certs() {
+        let dir = tempfile::tempdir().unwrap();
+        write_cert(dir.path(), "expired.example", Duration::hours(1) - Duration::days(2));
+        write_cert(dir.path(), "soon.example", Duration::days(10) + Duration::hours(1));
+        write_cert(dir.path(), "valid.example", Duration::days(200));
+        let now = chrono::Utc::now().timestamp();
+
+        let expiries = nebulafx_utils::load_cert_expiries(dir.path().to_str().unwrap()).unwrap();
+        let reports = assess(&expiries, now, DEFAULT_EXPIRY_WARNING_DAYS);
+        let summary: Vec<_> = reports
+            .iter()
+            .map(|r| (r.name.as_str(), r.status, r.days_to_expiry))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("expired.example", CertStatus::Expired, -2),
+                ("soon.example", CertStatus::ExpiringSoon, 10),
+                ("valid.example", CertStatus::Valid, 199),
+            ]
+        );
+
+        assert!(reject_expired(&reports, Some(&tls_config(false))).is_ok());
+        let err = reject_expired(&reports, Some(&tls_config(true))).unwrap_err();
+        assert!(err.to_string().contains("expired.example"));
+    }
+
+    #[test]
+    fn test_refresh_replaces_expiries_of_reloaded_certs() {
+        let dir = tempfile::tempdir().unwrap();
+        write_cert(dir.path(), "renewed.example", Duration::days(5) + Duration::hours(1));
+        let mut loaded = Some(LoadedCerts {
+            expiries: nebulafx_utils::load_cert_expiries(dir.path().to_str().unwrap()).unwrap(),
+            warning_days: 7,
+        });
+
+        write_cert(dir.path(), "renewed.example", Duration::days(90) + Duration::hours(1));
+        refresh(&mut loaded, dir.path());
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.warning_days, 7);
+        let now = chrono::Utc::now().timestamp();
+        let reports = assess(&loaded.expiries, now, loaded.warning_days);
+        assert_eq!(reports.len(), 1);
+        assert_eq!((reports[0].status, reports[0].days_to_expiry), (CertStatus::Valid, 90));
+    }
+}
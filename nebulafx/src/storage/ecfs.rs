@@ -1,5 +1,6 @@
 use crate::auth::get_condition_values;
 use crate::error::ApiError;
+use crate::server::{ObjectSizeLimit, object_too_large};
 use crate::storage::entity;
 use crate::storage::helper::OperationHelper;
 use crate::storage::options::{filter_object_metadata, get_content_sha256};
@@ -100,7 +101,7 @@ use nebulafx_zip::CompressionFormat;
 use s3s::header::{X_AMZ_RESTORE, X_AMZ_RESTORE_OUTPUT_PATH};
 use s3s::{S3, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, dto::*, s3_error};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Debug,
     path::Path,
     str::FromStr,
@@ -252,6 +253,7 @@ impl FS {
 
     async fn put_object_extract(&self, req: S3Request<PutObjectInput>) -> S3Result<S3Response<PutObjectOutput>> {
         let helper = OperationHelper::new(&req, EventName::ObjectCreatedPut, "s3:PutObject").suppress_event();
+        let size_limit = req.extensions.get::<ObjectSizeLimit>().copied();
         let input = req.input;
 
         let PutObjectInput {
@@ -265,7 +267,10 @@ impl FS {
         } = input;
 
         let event_version_id = version_id;
-        let Some(body) = body else { return Err(s3_error!(IncompleteBody)) };
+        let Some(mut body) = body else { return Err(s3_error!(IncompleteBody)) };
+        if let Some(limit) = size_limit {
+            body = limit.limit_body(body);
+        }
 
         let body = StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))));
 
@@ -475,8 +480,7 @@ impl FS {
             ..Default::default()
         };
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 }
 
@@ -531,8 +535,7 @@ impl S3 for FS {
         let output = CreateBucketOutput::default();
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     /// Copy an object from one location to another
@@ -707,8 +710,7 @@ impl S3 for FS {
         helper = helper.object(object_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     async fn restore_object(&self, req: S3Request<RestoreObjectInput>) -> S3Result<S3Response<RestoreObjectOutput>> {
@@ -968,8 +970,7 @@ impl S3 for FS {
             .map_err(ApiError::from)?;
 
         let result = Ok(S3Response::new(DeleteBucketOutput {}));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     /// Delete an object
@@ -1084,8 +1085,7 @@ impl S3 for FS {
             .version_id(version_id.map(|v| v.to_string()).unwrap_or_default());
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     /// Delete multiple objects
@@ -1227,8 +1227,7 @@ impl S3 for FS {
                 .collect::<Vec<Option<DiskError>>>() as &[Option<DiskError>],
         ) {
             let result = Err(S3Error::with_message(S3ErrorCode::NoSuchBucket, "Bucket not found".to_string()));
-            let _ = helper.complete(&result);
-            return result;
+            return helper.finish(result).await;
         }
 
         for (i, err) in errs.iter().enumerate() {
@@ -1347,8 +1346,7 @@ impl S3 for FS {
         });
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     /// Get bucket location
@@ -1719,8 +1717,7 @@ impl S3 for FS {
         helper = helper.object(event_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self, req))]
@@ -1881,9 +1878,7 @@ impl S3 for FS {
         helper = helper.object(event_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -2161,6 +2156,7 @@ impl S3 for FS {
             return self.put_object_extract(req).await;
         }
 
+        let size_limit = req.extensions.get::<ObjectSizeLimit>().copied();
         let input = req.input;
 
         // Save SSE-C parameters before moving input
@@ -2207,6 +2203,13 @@ impl S3 for FS {
             return Err(s3_error!(UnexpectedContent));
         }
 
+        let body = match size_limit {
+            Some(limit) => {
+                limit.check(size as u64)?;
+                limit.limit_body(body)
+            }
+            None => body,
+        };
         let body = StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))));
 
         // let body = Box::new(StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string())))));
@@ -2462,8 +2465,7 @@ impl S3 for FS {
         };
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self, req))]
@@ -2607,12 +2609,12 @@ impl S3 for FS {
         };
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self, req))]
     async fn upload_part(&self, req: S3Request<UploadPartInput>) -> S3Result<S3Response<UploadPartOutput>> {
+        let size_limit = req.extensions.get::<ObjectSizeLimit>().copied();
         let input = req.input;
         let UploadPartInput {
             body,
@@ -2634,6 +2636,9 @@ impl S3 for FS {
 
         let mut size = content_length;
         let mut body_stream = body.ok_or_else(|| s3_error!(IncompleteBody))?;
+        if let Some(limit) = size_limit {
+            body_stream = limit.limit_body(body_stream);
+        }
 
         if size.is_none() {
             if let Some(val) = req.headers.get(AMZ_DECODED_CONTENT_LENGTH) {
@@ -2646,7 +2651,9 @@ impl S3 for FS {
                 let mut total = 0i64;
                 let mut buffer = bytes::BytesMut::new();
                 while let Some(chunk) = body_stream.next().await {
-                    let chunk = chunk.map_err(|e| ApiError::from(StorageError::other(e.to_string())))?;
+                    let chunk = chunk.map_err(|e| {
+                        object_too_large(&e).unwrap_or_else(|| ApiError::from(StorageError::other(e.to_string())).into())
+                    })?;
                     total += chunk.len() as i64;
                     buffer.extend_from_slice(&chunk);
                 }
@@ -3175,6 +3182,7 @@ impl S3 for FS {
         req: S3Request<CompleteMultipartUploadInput>,
     ) -> S3Result<S3Response<CompleteMultipartUploadOutput>> {
         let helper = OperationHelper::new(&req, EventName::ObjectCreatedCompleteMultipartUpload, "s3:CompleteMultipartUpload");
+        let size_limit = req.extensions.get::<ObjectSizeLimit>().copied();
         let input = req.input;
         let CompleteMultipartUploadInput {
             multipart_upload,
@@ -3217,6 +3225,22 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        // Each part was checked on upload, but together they may still exceed the limit
+        if let Some(limit) = size_limit {
+            let parts = store
+                .list_object_parts(&bucket, &key, &upload_id, None, MAX_PARTS_COUNT, &ObjectOptions::default())
+                .await
+                .map_err(ApiError::from)?;
+            let completed: HashSet<usize> = uploaded_parts.iter().map(|p| p.part_num).collect();
+            limit.check_parts(
+                parts
+                    .parts
+                    .iter()
+                    .filter(|p| completed.contains(&p.part_num))
+                    .map(|p| p.actual_size.max(0) as u64),
+            )?;
+        }
+
         info!("TDD: Got multipart info successfully");
         info!("TDD: Multipart info metadata: {:?}", multipart_info.user_defined);
 
@@ -3331,7 +3355,7 @@ impl S3 for FS {
             output.server_side_encryption, output.ssekms_key_id
         );
         let helper_result = Ok(S3Response::new(helper_output));
-        let _ = helper.complete(&helper_result);
+        helper.finish(helper_result).await?;
         Ok(S3Response::new(output))
     }
 
@@ -3474,8 +3498,7 @@ impl S3 for FS {
         helper = helper.version_id(version_id);
 
         let result = Ok(S3Response::new(PutObjectTaggingOutput { version_id: None }));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -3523,8 +3546,7 @@ impl S3 for FS {
         helper = helper.version_id(version_id);
 
         let result = Ok(S3Response::new(DeleteObjectTaggingOutput { version_id: None }));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -4288,8 +4310,7 @@ impl S3 for FS {
             .version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     async fn put_object_acl(&self, req: S3Request<PutObjectAclInput>) -> S3Result<S3Response<PutObjectAclOutput>> {
@@ -4452,8 +4473,7 @@ impl S3 for FS {
         helper = helper.object(object_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     async fn put_object_legal_hold(
@@ -4517,8 +4537,7 @@ impl S3 for FS {
         helper = helper.object(info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     async fn get_object_retention(
@@ -4564,8 +4583,7 @@ impl S3 for FS {
         helper = helper.object(object_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 
     async fn put_object_retention(
@@ -4625,8 +4643,7 @@ impl S3 for FS {
         helper = helper.object(object_info).version_id(version_id);
 
         let result = Ok(S3Response::new(output));
-        let _ = helper.complete(&result);
-        result
+        helper.finish(result).await
     }
 }
 
@@ -0,0 +1,193 @@
+use crate::config::StorageConfig;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How often local drive usage is sampled
+pub(crate) const FREE_SPACE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Free space every local drive must keep before S3 writes are refused
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct FreeSpaceFloor {
+    pub min_bytes: Option<u64>,
+    pub min_percent: Option<f64>,
+}
+
+impl FreeSpaceFloor {
+    /// Floor from `storage.min_free_bytes`/`min_free_percent`, or `None` when neither is set
+    pub(crate) fn from_config(storage: Option<&StorageConfig>) -> Result<Option<Self>, String> {
+        let Some(storage) = storage else {
+            return Ok(None);
+        };
+        let floor = Self {
+            min_bytes: storage.min_free_bytes_value()?,
+            min_percent: storage.min_free_percent_value()?,
+        };
+        Ok((floor != Self::default()).then_some(floor))
+    }
+
+    /// Free bytes a drive of `total` bytes must keep; the stricter of the two settings wins
+    pub(crate) fn required(&self, total: u64) -> u64 {
+        let by_percent = self
+            .min_percent
+            .map(|percent| (total as f64 * percent / 100.0).ceil() as u64)
+            .unwrap_or(0);
+        self.min_bytes.unwrap_or(0).max(by_percent)
+    }
+}
+
+/// Usage of one local drive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct DriveUsage {
+    pub path: String,
+    pub free: u64,
+    pub total: u64,
+}
+
+/// Tracks whether local drives are above the free-space floor
+///
+/// The write path only reads a flag; usage is refreshed by [`spawn_free_space_poller`].
+#[derive(Debug)]
+pub(crate) struct FreeSpaceGuard {
+    floor: FreeSpaceFloor,
+    below_floor: AtomicBool,
+    /// Drive with the least headroom in the last sample
+    tightest: Mutex<Option<DriveUsage>>,
+}
+
+impl FreeSpaceGuard {
+    pub(crate) fn new(floor: FreeSpaceFloor) -> Self {
+        Self {
+            floor,
+            below_floor: AtomicBool::new(false),
+            tightest: Mutex::new(None),
+        }
+    }
+
+    /// Writes are allowed until a sample shows a drive below the floor
+    pub(crate) fn allows_writes(&self) -> bool {
+        !self.below_floor.load(Ordering::Acquire)
+    }
+
+    /// Record a usage sample; writes are refused while any drive is below the floor
+    pub(crate) fn record(&self, drives: &[DriveUsage]) {
+        let headroom = |drive: &DriveUsage| drive.free as i128 - self.floor.required(drive.total) as i128;
+        let Some(tightest) = drives.iter().min_by_key(|drive| headroom(drive)) else {
+            return;
+        };
+        let below = headroom(tightest) < 0;
+
+        let was_below = self.below_floor.swap(below, Ordering::AcqRel);
+        if below && !was_below {
+            warn!(
+                target: "nebulafx::storage::free_space",
+                path = %tightest.path,
+                free = tightest.free,
+                required = self.floor.required(tightest.total),
+                "Drive is below the minimum free space, refusing S3 writes"
+            );
+        } else if !below && was_below {
+            info!(target: "nebulafx::storage::free_space", "Drives are above the minimum free space again, accepting S3 writes");
+        }
+        *self.tightest.lock().unwrap() = Some(tightest.clone());
+    }
+
+    /// Free space of the tightest drive, for health endpoints
+    pub(crate) fn status(&self) -> Value {
+        let tightest = self.tightest.lock().unwrap().clone();
+        json!({
+            "status": if self.allows_writes() { "ok" } else { "below_min_free" },
+            "path": tightest.as_ref().map(|d| d.path.as_str()),
+            "free_bytes": tightest.as_ref().map(|d| d.free),
+            "total_bytes": tightest.as_ref().map(|d| d.total),
+            "min_free_bytes": tightest.as_ref().map(|d| self.floor.required(d.total)),
+        })
+    }
+}
+
+static FREE_SPACE_GUARD: OnceLock<Arc<FreeSpaceGuard>> = OnceLock::new();
+
+/// Install the process-wide guard for `floor` and return it
+pub(crate) fn init_free_space_guard(floor: FreeSpaceFloor) -> Arc<FreeSpaceGuard> {
+    FREE_SPACE_GUARD.get_or_init(|| Arc::new(FreeSpaceGuard::new(floor))).clone()
+}
+
+/// The guard, if a free-space floor is configured
+pub(crate) fn free_space_guard() -> Option<&'static Arc<FreeSpaceGuard>> {
+    FREE_SPACE_GUARD.get()
+}
+
+/// Sample `paths` every `interval` and feed the results to `guard`
+pub(crate) fn spawn_free_space_poller(guard: Arc<FreeSpaceGuard>, paths: Vec<PathBuf>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let paths = paths.clone();
+            let sample = tokio::task::spawn_blocking(move || sample_drives(&paths))
+                .await
+                .unwrap_or_default();
+            guard.record(&sample);
+        }
+    });
+}
+
+fn sample_drives(paths: &[PathBuf]) -> Vec<DriveUsage> {
+    paths
+        .iter()
+        .filter_map(|path| match nebulafx_utils::os::get_info(path) {
+            Ok(info) => Some(DriveUsage {
+                path: path.display().to_string(),
+                free: info.free,
+                total: info.total,
+            }),
+            Err(e) => {
+                warn!(target: "nebulafx::storage::free_space", path = %path.display(), "Failed to read drive usage: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drive(free: u64, total: u64) -> DriveUsage {
+        DriveUsage {
+            path: "/data1".to_string(),
+            free,
+            total,
+        }
+    }
+
+    #[test]
+    fn test_stricter_setting_wins() {
+        let floor = FreeSpaceFloor {
+            min_bytes: Some(100),
+            min_percent: Some(5.0),
+        };
+        assert_eq!(floor.required(1_000), 100);
+        assert_eq!(floor.required(10_000), 500);
+    }
+
+    #[test]
+    fn test_any_drive_below_floor_refuses_writes() {
+        let guard = FreeSpaceGuard::new(FreeSpaceFloor {
+            min_bytes: Some(1_000),
+            min_percent: None,
+        });
+        assert!(guard.allows_writes());
+
+        guard.record(&[drive(5_000, 10_000), drive(999, 10_000)]);
+        assert!(!guard.allows_writes());
+        assert_eq!(guard.status()["free_bytes"], 999);
+
+        guard.record(&[drive(5_000, 10_000), drive(1_000, 10_000)]);
+        assert!(guard.allows_writes());
+    }
+}
@@ -2,6 +2,7 @@
 
 use http::StatusCode;
 use nebulafx_audit::{
+    AuditMode, audit_system, dispatch_audit_log,
     entity::{ApiDetails, ApiDetailsBuilder, AuditEntryBuilder},
     global::AuditLogger,
 };
@@ -11,9 +12,11 @@ use nebulafx_targets::EventName;
 use nebulafx_utils::{
     extract_req_params, extract_req_params_header, extract_resp_elements, get_request_host, get_request_user_agent,
 };
-use s3s::{S3Request, S3Response, S3Result};
+use s3s::{S3Error, S3ErrorCode, S3Request, S3Response, S3Result};
 use std::future::Future;
+use std::sync::Arc;
 use tokio::runtime::{Builder, Handle};
+use tracing::error;
 
 /// Schedules an asynchronous task on the current runtime;
 /// if there is no runtime, creates a minimal runtime execution on a new thread.
@@ -165,6 +168,21 @@ impl OperationHelper {
         self
     }
 
+    /// Complete the operation and return `result`, as [`OperationHelper::complete`] does.
+    /// With `audit.mode = "sync"` the audit entry is written before returning, and a failed
+    /// write turns the response into an error; otherwise it is dispatched on drop.
+    pub async fn finish<T: Send + Sync>(self, result: S3Result<S3Response<T>>) -> S3Result<S3Response<T>> {
+        let mut helper = self.complete(&result);
+        if audit_system().is_some_and(|system| system.mode() == AuditMode::Sync)
+            && let Some(builder) = helper.audit_builder.take()
+            && let Err(e) = dispatch_audit_log(Arc::new(builder.build())).await
+        {
+            error!(error = %e, "Synchronous audit write failed, failing the request");
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, format!("audit log write failed: {e}")));
+        }
+        result
+    }
+
     /// Suppresses the automatic event notification on drop.
     pub fn suppress_event(mut self) -> Self {
         self.event_builder = None;
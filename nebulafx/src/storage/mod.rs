@@ -3,6 +3,7 @@
 pub mod access;
 pub mod ecfs;
 pub(crate) mod entity;
+pub(crate) mod free_space;
 pub(crate) mod helper;
 pub mod options;
 pub mod tonic_service;
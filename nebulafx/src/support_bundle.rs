@@ -0,0 +1,235 @@
+use crate::admin::console::{CONSOLE_PREFIX, build_info, health_check};
+use flate2::{Compression, write::GzEncoder};
+use nebulafx_tomlx::{PrintFormat, Redactor};
+use serde_json::{Value, json};
+use std::io::{Error, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long the CLI waits for the running server's health report
+const HEALTH_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Key fragments redacted in bundles on top of the config's `redact_keys`
+///
+/// Access log entries carry request headers, whose credentials do not match the
+/// config-oriented defaults.
+const BUNDLE_REDACT_KEYS: [&str; 3] = ["authorization", "signature", "credential"];
+
+pub(crate) const CONFIG_ENTRY: &str = "config.toml";
+pub(crate) const VERSION_ENTRY: &str = "version.json";
+pub(crate) const HEALTH_ENTRY: &str = "health.json";
+pub(crate) const TOPOLOGY_ENTRY: &str = "topology.json";
+pub(crate) const POOLS_ENTRY: &str = "pools.json";
+pub(crate) const ACCESS_LOG_ENTRY: &str = "access_log.json";
+pub(crate) const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// Diagnostics gathered for an issue report, written as a gzipped tarball
+///
+/// Every JSON entry passes through the redactor before it is stored, so callers can
+/// add raw server state.
+pub(crate) struct SupportBundle {
+    redactor: Redactor,
+    entries: Vec<(String, Vec<u8>)>,
+    /// Entries that could not be gathered, with the reason
+    missing: Vec<(String, String)>,
+}
+
+impl SupportBundle {
+    pub(crate) fn new(redact_keys: &[String]) -> Self {
+        Self {
+            redactor: Redactor::with_keys(redact_keys.iter().map(String::as_str).chain(BUNDLE_REDACT_KEYS)),
+            entries: Vec::new(),
+            missing: Vec::new(),
+        }
+    }
+
+    /// Add the config file at `path`, rendered as TOML with secrets masked
+    pub(crate) fn add_config(&mut self, path: &Path) {
+        match nebulafx_tomlx::load_config_redacted::<Value>(path, PrintFormat::Toml) {
+            Ok((_, rendered)) => self.add_bytes(CONFIG_ENTRY, rendered.into_bytes()),
+            Err(e) => self.add_missing(CONFIG_ENTRY, format!("failed to load {}: {e}", path.display())),
+        }
+    }
+
+    pub(crate) fn add_json(&mut self, name: &str, mut value: Value) {
+        self.redactor.redact_json(&mut value);
+        match serde_json::to_vec_pretty(&value) {
+            Ok(bytes) => self.add_bytes(name, bytes),
+            Err(e) => self.add_missing(name, e.to_string()),
+        }
+    }
+
+    /// Add the health report along with the topology and pool stats it carries
+    pub(crate) fn add_health(&mut self, health: Value) {
+        let topology = json!({
+            "region": health["region"],
+            "role": health["role"],
+            "erasure": health["details"]["erasure"],
+        });
+        let pools = json!({ "database": health["details"]["database"] });
+        self.add_json(TOPOLOGY_ENTRY, topology);
+        self.add_json(POOLS_ENTRY, pools);
+        self.add_json(HEALTH_ENTRY, health);
+    }
+
+    pub(crate) fn add_missing(&mut self, name: &str, reason: impl Into<String>) {
+        self.missing.push((name.to_string(), reason.into()));
+    }
+
+    fn add_bytes(&mut self, name: &str, bytes: Vec<u8>) {
+        self.entries.push((name.to_string(), bytes));
+    }
+
+    /// The bundle as a gzipped tarball, with a manifest of what was and was not gathered
+    pub(crate) fn to_tar_gz(&self) -> Result<Vec<u8>> {
+        let manifest = json!({
+            "created_at": chrono::Utc::now().to_rfc3339(),
+            "entries": self.entries.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            "missing": self.missing.iter().map(|(name, reason)| json!({"entry": name, "reason": reason})).collect::<Vec<_>>(),
+        });
+        let manifest = serde_json::to_vec_pretty(&manifest).map_err(Error::other)?;
+
+        let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+        for (name, bytes) in std::iter::once((MANIFEST_ENTRY, manifest.as_slice()))
+            .chain(self.entries.iter().map(|(name, bytes)| (name.as_str(), bytes.as_slice())))
+        {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            archive.append_data(&mut header, name, bytes)?;
+        }
+        archive.into_inner()?.finish()
+    }
+}
+
+/// Bundle of the running server's state: config, version, health, topology, pools and recent access log
+pub(crate) async fn collect_running() -> SupportBundle {
+    let config = crate::config::get_config();
+    let mut bundle = SupportBundle::new(config.redact_keys.as_deref().unwrap_or_default());
+    bundle.add_config(&crate::config::active_config_path());
+    bundle.add_json(VERSION_ENTRY, build_info());
+    bundle.add_health(health_check().await.0);
+
+    let recent: Vec<_> = nebulafx_audit::audit_tail().recent();
+    match serde_json::to_value(&recent) {
+        Ok(entries) => bundle.add_json(ACCESS_LOG_ENTRY, entries),
+        Err(e) => bundle.add_missing(ACCESS_LOG_ENTRY, e.to_string()),
+    }
+    bundle
+}
+
+/// Bundle gathered outside the server: config and version locally, health from the server at `endpoint`
+///
+/// The access log lives in server memory, so only bundles from the console endpoint include it.
+pub(crate) async fn collect_from_cli(endpoint: &str) -> SupportBundle {
+    let config = crate::config::get_config();
+    let mut bundle = SupportBundle::new(config.redact_keys.as_deref().unwrap_or_default());
+    bundle.add_config(&crate::config::active_config_path());
+    bundle.add_json(VERSION_ENTRY, build_info());
+
+    let url = format!("{}{CONSOLE_PREFIX}/health", endpoint.trim_end_matches('/'));
+    match fetch_json(&url).await {
+        Ok(health) => bundle.add_health(health),
+        Err(e) => {
+            for entry in [HEALTH_ENTRY, TOPOLOGY_ENTRY, POOLS_ENTRY] {
+                bundle.add_missing(entry, format!("failed to fetch {url}: {e}"));
+            }
+        }
+    }
+    bundle.add_missing(ACCESS_LOG_ENTRY, "only available from the console support-bundle endpoint");
+    bundle
+}
+
+async fn fetch_json(url: &str) -> reqwest::Result<Value> {
+    let client = reqwest::Client::builder().timeout(HEALTH_FETCH_TIMEOUT).build()?;
+    client.get(url).send().await?.error_for_status()?.json().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    /// Entry names and contents of a bundle produced by [`SupportBundle::to_tar_gz`]
+    fn read_tar_gz(bytes: &[u8]) -> Result<Vec<(String, String)>> {
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+        let mut entries = Vec::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.display().to_string();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            entries.push((name, contents));
+        }
+        Ok(entries)
+    }
+
+    #[test]
+    fn test_bundle_contains_expected_entries_and_no_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "redact_keys = [\"dsn\"]\n[server]\naccess_key = \"AKIAEXAMPLE\"\nsecret_key = \"s3cr3t-value\"\n\
+             [database]\npassword = \"db-hunter2\"\ndsn = \"postgres://u:dsn-pass@h/db\"\n",
+        )
+        .unwrap();
+
+        let mut bundle = SupportBundle::new(&["dsn".to_string()]);
+        bundle.add_config(&config_path);
+        bundle.add_json(VERSION_ENTRY, json!({"version": "1.0.0"}));
+        bundle.add_health(json!({
+            "status": "ok",
+            "region": "us-east-1",
+            "role": "all",
+            "details": {
+                "erasure": [{"data": 4, "parity": 2}],
+                "database": {"status": "connected", "pool": "default", "size": 4, "idle": 2},
+            },
+        }));
+        bundle.add_json(
+            ACCESS_LOG_ENTRY,
+            json!([{
+                "api": {"name": "s3:PutObject", "bucket": "photos"},
+                "requestHeader": {"Authorization": "AWS4-HMAC-SHA256 Credential=AKIAEXAMPLE/Signature=abc123"},
+                "sessionToken": "session-tok",
+            }]),
+        );
+        bundle.add_missing("notes.txt", "not gathered in this test");
+
+        let entries = read_tar_gz(&bundle.to_tar_gz().unwrap()).unwrap();
+        let names: Vec<_> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                MANIFEST_ENTRY,
+                CONFIG_ENTRY,
+                VERSION_ENTRY,
+                TOPOLOGY_ENTRY,
+                POOLS_ENTRY,
+                HEALTH_ENTRY,
+                ACCESS_LOG_ENTRY
+            ]
+        );
+
+        for (name, contents) in &entries {
+            for secret in [
+                "AKIAEXAMPLE",
+                "s3cr3t-value",
+                "db-hunter2",
+                "dsn-pass",
+                "abc123",
+                "session-tok",
+            ] {
+                assert!(!contents.contains(secret), "{name} leaks {secret}");
+            }
+        }
+        let (_, pools) = entries.iter().find(|(name, _)| name == POOLS_ENTRY).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(pools).unwrap()["database"]["size"], 4);
+        let (_, manifest) = &entries[0];
+        assert!(manifest.contains("notes.txt"));
+    }
+}